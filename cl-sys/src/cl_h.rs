@@ -381,6 +381,18 @@ pub const CL_QUEUE_PROPERTIES:                          cl_uint = 0x1093;
     pub const CL_QUEUE_SIZE:                                cl_uint = 0x1094;
     pub const CL_QUEUE_DEVICE_DEFAULT:                      cl_uint = 0x1095;
 
+// cl_khr_priority_hints:
+pub const CL_QUEUE_PRIORITY_KHR:                        cl_uint = 0x1096;
+pub const CL_QUEUE_PRIORITY_HIGH_KHR:                   cl_uint = 1 << 0;
+pub const CL_QUEUE_PRIORITY_MED_KHR:                    cl_uint = 1 << 1;
+pub const CL_QUEUE_PRIORITY_LOW_KHR:                    cl_uint = 1 << 2;
+
+// cl_khr_throttle_hints:
+pub const CL_QUEUE_THROTTLE_KHR:                        cl_uint = 0x1097;
+pub const CL_QUEUE_THROTTLE_HIGH_KHR:                   cl_uint = 1 << 0;
+pub const CL_QUEUE_THROTTLE_MED_KHR:                    cl_uint = 1 << 1;
+pub const CL_QUEUE_THROTTLE_LOW_KHR:                    cl_uint = 1 << 2;
+
 // cl_mem_flags and cl_svm_mem_flags - bitfield:
 pub const CL_MEM_READ_WRITE:                            cl_bitfield = 1 << 0;
 pub const CL_MEM_WRITE_ONLY:                            cl_bitfield = 1 << 1;
@@ -661,11 +673,7 @@ pub const CL_PROFILING_COMMAND_END:                     cl_uint = 0x1283;
     pub const CL_PROFILING_COMMAND_COMPLETE:                cl_uint = 0x1284;
 
 
-//#[link_args = "-L$OPENCL_LIB -lOpenCL"]
-#[cfg_attr(target_os = "macos", link(name = "OpenCL", kind = "framework"))]
-#[cfg_attr(target_os = "windows", link(name = "OpenCL"))]
-#[cfg_attr(not(target_os = "macos"), link(name = "OpenCL"))]
-extern "system" {
+cl_extern! {
     // Platform API:
     pub fn clGetPlatformIDs(num_entries: cl_uint,
                             platforms: *mut cl_platform_id,
@@ -733,8 +741,8 @@ extern "system" {
     //############################### NEW 2.1 #################################
     #[cfg(feature = "opencl_version_2_1")]
     pub fn clGetDeviceAndHostTimer(device: cl_device_id,
-                                   device_timestamp: cl_ulong,
-                                   host_timestamp: cl_ulong) -> cl_int;
+                                   device_timestamp: *mut cl_ulong,
+                                   host_timestamp: *mut cl_ulong) -> cl_int;
 
     // extern CL_API_ENTRY cl_int CL_API_CALL
     // clGetHostTimer(cl_device_id /* device */,
@@ -742,7 +750,7 @@ extern "system" {
     //############################### NEW 2.1 #################################
     #[cfg(feature = "opencl_version_2_1")]
     pub fn clGetHostTimer(device: cl_device_id,
-                          host_timestamp: cl_ulong) -> cl_int;
+                          host_timestamp: *mut cl_ulong) -> cl_int;
 
     // Context APIs:
     pub fn clCreateContext(properties: *const cl_context_properties,
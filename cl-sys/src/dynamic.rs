@@ -0,0 +1,162 @@
+//! Runtime loading of the OpenCL library.
+//!
+//! Only available with the `dynamic` feature enabled. Rather than being
+//! linked at build time, the OpenCL library (generally the ICD loader) is
+//! opened on first use and every `cl*` function is resolved from it as it is
+//! called. A binary built this way will start normally on a machine without
+//! OpenCL installed.
+//!
+//! The library is located, in order of precedence, using:
+//!
+//! 1. The path passed to `load_from` (must be called before any other
+//!    function in this crate),
+//! 2. The path contained in the `OCL_LIBRARY_PATH` environment variable,
+//! 3. The platform default library names (e.g. `libOpenCL.so.1` and
+//!    `libOpenCL.so` on Linux, `OpenCL.dll` on Windows).
+//!
+//! Calling any OpenCL function when the library can not be loaded, or calling
+//! a function which the loaded library does not export, will panic. Call
+//! `load` first (`ocl_core::get_platform_ids` does this automatically) to
+//! handle a missing library gracefully.
+//!
+
+extern crate libloading;
+
+use std::env;
+use std::error::Error;
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::io;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use self::libloading::Library;
+
+
+/// The environment variable which can be set to specify the path of the
+/// OpenCL library.
+pub const LIBRARY_PATH_ENV_VAR: &'static str = "OCL_LIBRARY_PATH";
+
+#[cfg(target_os = "macos")]
+const DEFAULT_LIBRARY_NAMES: &'static [&'static str] =
+    &["/System/Library/Frameworks/OpenCL.framework/OpenCL"];
+
+#[cfg(target_os = "windows")]
+const DEFAULT_LIBRARY_NAMES: &'static [&'static str] = &["OpenCL.dll"];
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const DEFAULT_LIBRARY_NAMES: &'static [&'static str] = &["libOpenCL.so.1", "libOpenCL.so"];
+
+static LIBRARY: AtomicPtr<Library> = AtomicPtr::new(0 as *mut Library);
+
+
+/// An error encountered while loading the OpenCL library.
+#[derive(Debug)]
+pub enum LoadError {
+    /// None of the candidate paths could be opened. Contains each path tried
+    /// and the error returned by the last attempt.
+    Open { paths: Vec<OsString>, cause: io::Error },
+    /// `load_from` was called after a library had already been loaded.
+    AlreadyLoaded,
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LoadError::Open { ref paths, ref cause } => {
+                write!(f, "Unable to load the OpenCL library (tried: {:?}): {}", paths, cause)
+            },
+            LoadError::AlreadyLoaded => write!(f, "The OpenCL library has already been loaded."),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            LoadError::Open { ref cause, .. } => Some(cause),
+            LoadError::AlreadyLoaded => None,
+        }
+    }
+}
+
+
+/// Stores `lib` as the global library unless another thread got there
+/// first, in which case `lib` is simply dropped.
+fn install(lib: Library) {
+    let lib_ptr = Box::into_raw(Box::new(lib));
+
+    if LIBRARY.compare_exchange(ptr::null_mut(), lib_ptr, Ordering::AcqRel, Ordering::Acquire)
+            .is_err() {
+        unsafe { drop(Box::from_raw(lib_ptr)); }
+    }
+}
+
+/// Attempts to open each path in turn, returning the first success.
+fn open<I, P>(paths: I) -> Result<Library, LoadError>
+        where I: IntoIterator<Item=P>, P: AsRef<OsStr> {
+    let mut tried = Vec::new();
+    let mut last_err = io::Error::new(io::ErrorKind::NotFound, "no library paths specified");
+
+    for path in paths {
+        match Library::new(path.as_ref()) {
+            Ok(lib) => return Ok(lib),
+            Err(err) => {
+                tried.push(path.as_ref().to_os_string());
+                last_err = err;
+            },
+        }
+    }
+
+    Err(LoadError::Open { paths: tried, cause: last_err })
+}
+
+/// Returns true if the OpenCL library has been successfully loaded.
+pub fn is_loaded() -> bool {
+    !LIBRARY.load(Ordering::Acquire).is_null()
+}
+
+/// Loads the OpenCL library from the path specified by the
+/// `OCL_LIBRARY_PATH` environment variable or, if unset, from the platform
+/// default location.
+///
+/// Does nothing if the library has already been loaded.
+pub fn load() -> Result<(), LoadError> {
+    if is_loaded() { return Ok(()); }
+
+    let lib = match env::var_os(LIBRARY_PATH_ENV_VAR) {
+        Some(path) => open(Some(path))?,
+        None => open(DEFAULT_LIBRARY_NAMES)?,
+    };
+
+    install(lib);
+    Ok(())
+}
+
+/// Loads the OpenCL library from an explicit path.
+///
+/// Must be called before any OpenCL function (including `load`). Returns
+/// `LoadError::AlreadyLoaded` if a library has already been loaded.
+pub fn load_from<P: AsRef<OsStr>>(path: P) -> Result<(), LoadError> {
+    if is_loaded() { return Err(LoadError::AlreadyLoaded); }
+    install(open(Some(path))?);
+    Ok(())
+}
+
+/// Returns the address of the symbol `name` (which must be nul-terminated),
+/// loading the library first if necessary.
+///
+/// Panics if the library can not be loaded or does not export `name`.
+#[doc(hidden)]
+pub fn symbol(name: &[u8]) -> usize {
+    if let Err(err) = load() {
+        panic!("cl-sys: {}", err);
+    }
+
+    let lib = unsafe { &*LIBRARY.load(Ordering::Acquire) };
+
+    match unsafe { lib.get::<unsafe extern "system" fn()>(name) } {
+        Ok(sym) => *sym as usize,
+        Err(err) => panic!("cl-sys: Unable to resolve '{}' from the OpenCL library: {}",
+            String::from_utf8_lossy(&name[..name.len() - 1]), err),
+    }
+}
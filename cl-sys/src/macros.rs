@@ -0,0 +1,47 @@
+//! Macros used to declare the OpenCL entry points.
+
+
+/// Declares a block of OpenCL API functions.
+///
+/// By default this expands to a plain `extern "system"` block which links
+/// the OpenCL library at build time.
+///
+/// With the `dynamic` feature enabled, each declaration instead expands to
+/// an `unsafe fn` with an identical signature which resolves its symbol from
+/// the library loaded by the `dynamic` module upon first use and caches the
+/// resulting function pointer.
+///
+macro_rules! cl_extern {
+    ( $( $(#[$attr:meta])* pub fn $name:ident( $($arg:ident: $ty:ty),* $(,)* ) $(-> $ret:ty)*; )* ) => {
+        #[cfg(not(feature = "dynamic"))]
+        #[cfg_attr(target_os = "macos", link(name = "OpenCL", kind = "framework"))]
+        #[cfg_attr(not(target_os = "macos"), link(name = "OpenCL"))]
+        extern "system" {
+            $(
+                $(#[$attr])*
+                pub fn $name($($arg: $ty),*) $(-> $ret)*;
+            )*
+        }
+
+        $(
+            #[cfg(feature = "dynamic")]
+            $(#[$attr])*
+            #[inline]
+            #[allow(non_snake_case)]
+            pub unsafe fn $name($($arg: $ty),*) $(-> $ret)* {
+                use std::sync::atomic::{AtomicUsize, Ordering};
+                static ADDR: AtomicUsize = AtomicUsize::new(0);
+
+                let mut addr = ADDR.load(Ordering::Relaxed);
+                if addr == 0 {
+                    addr = ::dynamic::symbol(concat!(stringify!($name), "\0").as_bytes());
+                    ADDR.store(addr, Ordering::Relaxed);
+                }
+
+                let func: unsafe extern "system" fn($($ty),*) $(-> $ret)* =
+                    ::std::mem::transmute(addr);
+                func($($arg),*)
+            }
+        )*
+    };
+}
@@ -2,10 +2,12 @@
 //!
 
 use std::fmt;
-use failure::{Context, Fail, Backtrace};
+use std::error::Error as StdError;
 use util::UtilError;
-use functions::{ApiError, VersionLowError, ProgramBuildError, ApiWrapperError};
-use ::{Status, EmptyInfoResultError};
+use ::{Status, EmptyInfoResultError, InfoResultError};
+
+pub use functions::{ApiError, VersionLowError, ProgramBuildError, DeviceBuildLogs,
+    ApiWrapperError};
 
 
 /// Ocl error result type.
@@ -13,52 +15,83 @@ pub type Result<T> = ::std::result::Result<T, Error>;
 
 
 /// An enum one of several error types.
-#[derive(Debug, Fail)]
+#[derive(Debug)]
 pub enum ErrorKind {
     // String: An arbitrary error:
     //
-    // TODO: Remove this eventually. We need to replace every usage
-    // (conversion from String/str) with a dedicated error type/variant for
-    // each. In the meanwhile, refrain from creating new instances of this by
-    // converting strings to `Error`!
-    #[fail(display = "{}", _0)]
+    // This crate no longer creates errors of this kind. It remains only so
+    // that user code may convert strings into an `Error`.
     String(String),
     // FfiNul: Ffi string conversion error:
-    #[fail(display = "{}", _0)]
-    FfiNul(#[cause] ::std::ffi::NulError),
+    FfiNul(::std::ffi::NulError),
     // Io: std::io error:
-    #[fail(display = "{}", _0)]
-    Io(#[cause] ::std::io::Error),
+    Io(::std::io::Error),
     // FromUtf8: String conversion error:
-    #[fail(display = "{}", _0)]
-    FromUtf8(#[cause] ::std::string::FromUtf8Error),
+    FromUtf8(::std::string::FromUtf8Error),
     // IntoString: Ffi string conversion error:
-    #[fail(display = "{}", _0)]
-    IntoString(#[cause] ::std::ffi::IntoStringError),
+    IntoString(::std::ffi::IntoStringError),
     // EmptyInfoResult:
-    #[fail(display = "{}", _0)]
     EmptyInfoResult(EmptyInfoResultError),
+    // InfoResult:
+    InfoResult(InfoResultError),
     // Util:
-    #[fail(display = "{}", _0)]
     Util(UtilError),
-    // Api:
-    #[fail(display = "{}", _0)]
+    // Api: An OpenCL API function returned an error status:
     Api(ApiError),
-    // VersionLow:
-    #[fail(display = "{}", _0)]
+    // VersionLow: A device or platform version is too low:
     VersionLow(VersionLowError),
-    // ProgramBuild:
-    #[fail(display = "{}", _0)]
+    // ProgramBuild: A program failed to build:
     ProgramBuild(ProgramBuildError),
-    // ApiWrapper:
-    #[fail(display = "{}", _0)]
+    // ApiWrapper: An API function wrapper was used incorrectly:
     ApiWrapper(ApiWrapperError),
+    // LibraryLoad: The OpenCL library could not be loaded at runtime:
+    #[cfg(feature = "dynamic")]
+    LibraryLoad(::ffi::dynamic::LoadError),
+}
+
+impl ErrorKind {
+    /// Returns the wrapped error, if any.
+    fn inner(&self) -> Option<&(StdError + 'static)> {
+        match *self {
+            ErrorKind::String(_) => None,
+            ErrorKind::FfiNul(ref err) => Some(err),
+            ErrorKind::Io(ref err) => Some(err),
+            ErrorKind::FromUtf8(ref err) => Some(err),
+            ErrorKind::IntoString(ref err) => Some(err),
+            ErrorKind::EmptyInfoResult(ref err) => Some(err),
+            ErrorKind::InfoResult(ref err) => Some(err),
+            ErrorKind::Util(ref err) => Some(err),
+            ErrorKind::Api(ref err) => Some(err),
+            ErrorKind::VersionLow(ref err) => Some(err),
+            ErrorKind::ProgramBuild(ref err) => Some(err),
+            ErrorKind::ApiWrapper(ref err) => Some(err),
+            #[cfg(feature = "dynamic")]
+            ErrorKind::LibraryLoad(ref err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ErrorKind::String(ref desc) => write!(f, "{}", desc),
+            _ => fmt::Display::fmt(self.inner().unwrap(), f),
+        }
+    }
+}
+
+impl StdError for ErrorKind {
+    // Each kind is displayed as the error it wraps, so the chain continues
+    // with that error's source.
+    fn source(&self) -> Option<&(StdError + 'static)> {
+        self.inner().and_then(|err| err.source())
+    }
 }
 
 
 /// An Error.
 pub struct Error {
-    inner: Context<ErrorKind>,
+    kind: Box<ErrorKind>,
 }
 
 impl Error {
@@ -72,120 +105,120 @@ impl Error {
 
     /// Returns the error variant and contents.
     pub fn kind(&self) -> &ErrorKind {
-        self.inner.get_context()
+        &self.kind
     }
 
-    /// Returns the immediate cause of this error (e.g. the next error in the
-    /// chain).
-    pub fn cause(&self) -> Option<&Fail> {
-        self.inner.cause()
+    /// Consumes this error, returning its variant and contents.
+    pub fn into_kind(self) -> ErrorKind {
+        *self.kind
     }
 }
 
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        fmt::Display::fmt(&self.inner, f)
+        fmt::Display::fmt(&self.kind, f)
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        fmt::Display::fmt(&self.inner, f)
+        fmt::Display::fmt(&self.kind, f)
     }
 }
 
-impl Fail for Error {
-    fn cause(&self) -> Option<&Fail> {
-        self.inner.cause()
-    }
-
-    fn backtrace(&self) -> Option<&Backtrace> {
-        self.inner.backtrace()
+impl StdError for Error {
+    fn source(&self) -> Option<&(StdError + 'static)> {
+        self.kind.source()
     }
 }
 
 impl From<ErrorKind> for Error {
     fn from(kind: ErrorKind) -> Error {
-        Error { inner: Context::new(kind) }
+        Error { kind: Box::new(kind) }
     }
 }
 
-impl From<Context<ErrorKind>> for Error {
-    fn from(inner: Context<ErrorKind>) -> Error {
-        Error { inner: inner }
+impl From<EmptyInfoResultError> for Error {
+    fn from(err: EmptyInfoResultError) -> Self {
+        ErrorKind::EmptyInfoResult(err).into()
     }
 }
 
-impl From<EmptyInfoResultError> for Error {
-    fn from(err: EmptyInfoResultError) -> Self {
-        Error { inner: Context::new(ErrorKind::EmptyInfoResult(err)) }
+impl From<InfoResultError> for Error {
+    fn from(err: InfoResultError) -> Self {
+        ErrorKind::InfoResult(err).into()
     }
 }
 
-// TODO: Remove eventually
 impl<'a> From<&'a str> for Error {
     fn from(desc: &'a str) -> Self {
-        Error { inner: Context::new(ErrorKind::String(String::from(desc))) }
+        ErrorKind::String(String::from(desc)).into()
     }
 }
 
-// TODO: Remove eventually
 impl From<String> for Error {
     fn from(desc: String) -> Self {
-        Error { inner: Context::new(ErrorKind::String(desc)) }
+        ErrorKind::String(desc).into()
     }
 }
 
 impl From<::std::ffi::NulError> for Error {
     fn from(err: ::std::ffi::NulError) -> Self {
-        Error { inner: Context::new(ErrorKind::FfiNul(err)) }
+        ErrorKind::FfiNul(err).into()
     }
 }
 
 impl From<::std::io::Error> for Error {
     fn from(err: ::std::io::Error) -> Self {
-        Error { inner: Context::new(ErrorKind::Io(err)) }
+        ErrorKind::Io(err).into()
     }
 }
 
 impl From<::std::string::FromUtf8Error> for Error {
     fn from(err: ::std::string::FromUtf8Error) -> Self {
-        Error { inner: Context::new(ErrorKind::FromUtf8(err)) }
+        ErrorKind::FromUtf8(err).into()
     }
 }
 
 impl From<::std::ffi::IntoStringError> for Error {
     fn from(err: ::std::ffi::IntoStringError) -> Self {
-        Error { inner: Context::new(ErrorKind::IntoString(err)) }
+        ErrorKind::IntoString(err).into()
     }
 }
 
 impl From<UtilError> for Error {
     fn from(err: UtilError) -> Self {
-        Error { inner: Context::new(ErrorKind::Util(err)) }
+        ErrorKind::Util(err).into()
     }
 }
 
 impl From<ApiError> for Error {
     fn from(err: ApiError) -> Self {
-        Error { inner: Context::new(ErrorKind::Api(err)) }
+        ErrorKind::Api(err).into()
     }
 }
 
 impl From<VersionLowError> for Error {
     fn from(err: VersionLowError) -> Self {
-        Error { inner: Context::new(ErrorKind::VersionLow(err)) }
+        ErrorKind::VersionLow(err).into()
     }
 }
 
 impl From<ProgramBuildError> for Error {
     fn from(err: ProgramBuildError) -> Self {
-        Error { inner: Context::new(ErrorKind::ProgramBuild(err)) }
+        ErrorKind::ProgramBuild(err).into()
     }
 }
 
 impl From<ApiWrapperError> for Error {
     fn from(err: ApiWrapperError) -> Self {
-        Error { inner: Context::new(ErrorKind::ApiWrapper(err)) }
+        ErrorKind::ApiWrapper(err).into()
+    }
+}
+
+#[cfg(feature = "dynamic")]
+impl From<::ffi::dynamic::LoadError> for Error {
+    fn from(err: ::ffi::dynamic::LoadError) -> Self {
+        ErrorKind::LibraryLoad(err).into()
     }
 }
@@ -20,7 +20,7 @@ use std::thread;
 use std::time::Duration;
 use std::env;
 use std::fmt;
-use failure::Fail;
+use std::error::Error as StdError;
 use ffi::{size_t, c_void};
 use num_traits::FromPrimitive;
 
@@ -53,7 +53,16 @@ use ::{OclPrm, PlatformId, DeviceId, Context, ContextProperties, ContextInfo,
     CreateContextCallbackFn, UserDataPtr, ClPlatformIdPtr, ClDeviceIdPtr, ClContextPtr,
     EventCallbackFn, BuildProgramCallbackFn, MemMigrationFlags, MapFlags, BufferRegion,
     BufferCreateType, OpenclVersion, ClVersions, Status, CommandQueueProperties, MemMap, AsMem,
-    MemCmdRw, MemCmdAll, Event, ImageFormatParseResult};
+    MemCmdRw, MemCmdAll, Event, ImageFormatParseResult, DevicePartition, DeviceExecCapabilities,
+    NativeKernelFn, MemDestructorFn, EventStatusFn, QueueProperties};
+
+#[cfg(feature = "opencl_version_2_0")]
+use ::{SvmMemFlags, KernelExecInfo, PipeInfo, PipeInfoResult};
+
+#[cfg(feature = "opencl_version_2_1")]
+use ffi::{cl_ulong, cl_kernel_sub_group_info};
+#[cfg(feature = "opencl_version_2_1")]
+use ::{KernelSubGroupInfo, KernelSubGroupInfoResult};
 
 #[cfg(not(feature="opencl_vendor_mesa"))]
 use ::{GlContextInfo, GlContextInfoResult};
@@ -156,12 +165,23 @@ impl ApiError {
         }
     }
 
+    /// Returns the status code returned by the API function.
     pub fn status(&self) -> Status {
         self.status
     }
+
+    /// Returns the name of the API function which returned the error.
+    pub fn fn_name(&self) -> &'static str {
+        self.fn_name
+    }
+
+    /// Returns additional information about the call, if any.
+    pub fn fn_info(&self) -> Option<&str> {
+        self.fn_info.as_ref().map(|s| s.as_str())
+    }
 }
 
-impl Fail for ApiError {}
+impl StdError for ApiError {}
 
 impl fmt::Display for ApiError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -206,29 +226,73 @@ fn eval_errcode<T, S>(errcode: cl_int, result: T, fn_name: &'static str, fn_info
 
 
 /// An OpenCL program build error.
-#[derive(Debug, Fail)]
+#[derive(Debug)]
 pub enum ProgramBuildError {
-    #[fail(display = "Device list is empty. Aborting build.")]
     DeviceListEmpty,
-    #[fail(display =
-        "\n\n\
-        ###################### OPENCL PROGRAM BUILD DEBUG OUTPUT \
-        ######################\
-        \n\n{}\n\
-        ########################################################\
-        #######################\
-        \n\n",
-        _0
-    )]
-    BuildLog(String),
-    #[fail(display = "{}", _0)]
     InfoResult(Box<OclCoreError>),
+    DeviceBuildLogs(DeviceBuildLogs),
+}
+
+impl ProgramBuildError {
+    /// Returns the build log for each device on which the build failed, if
+    /// available.
+    pub fn device_logs(&self) -> Option<&[(DeviceId, String)]> {
+        match *self {
+            ProgramBuildError::DeviceBuildLogs(ref logs) => Some(&logs.0),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ProgramBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProgramBuildError::DeviceListEmpty => write!(f, "Device list is empty. Aborting \
+                build."),
+            ProgramBuildError::InfoResult(ref err) => write!(f, "{}", err),
+            ProgramBuildError::DeviceBuildLogs(ref logs) => write!(f, "\n\n\
+                ###################### OPENCL PROGRAM BUILD DEBUG OUTPUT \
+                ######################\
+                \n\n{}\
+                ########################################################\
+                #######################\
+                \n\n", logs),
+        }
+    }
+}
+
+impl StdError for ProgramBuildError {
+    fn source(&self) -> Option<&(StdError + 'static)> {
+        match *self {
+            // Displayed along with this error, so the chain continues with
+            // its source.
+            ProgramBuildError::InfoResult(ref err) => err.source(),
+            _ => None,
+        }
+    }
+}
+
+
+/// The build logs for each of a list of devices, as returned within a
+/// `ProgramBuildError::DeviceBuildLogs` when building, compiling, or linking
+/// a program fails.
+#[derive(Debug)]
+pub struct DeviceBuildLogs(pub Vec<(DeviceId, String)>);
+
+impl fmt::Display for DeviceBuildLogs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &(ref device, ref log) in self.0.iter() {
+            write!(f, "Device {:?}:\n{}\n\n", device, log)?;
+        }
+        Ok(())
+    }
 }
 
 
 /// If the program pointed to by `cl_program` for any of the devices listed in
-/// `device_ids` has a build log of any length, it will be returned as an
-/// errcode result.
+/// `device_ids` has a build log of any length, the build log of each such
+/// device will be returned as an errcode result
+/// (`ProgramBuildError::DeviceBuildLogs`).
 ///
 pub fn program_build_err<D: ClDeviceIdPtr>(program: &Program, device_ids: &[D])
         -> Result<(), ProgramBuildError> {
@@ -236,19 +300,58 @@ pub fn program_build_err<D: ClDeviceIdPtr>(program: &Program, device_ids: &[D])
         return Err(ProgramBuildError::DeviceListEmpty);
     }
 
+    let logs: Vec<_> = program_build_logs(program, device_ids)
+        .map_err(|err| ProgramBuildError::InfoResult(Box::new(err)))?
+        .into_iter()
+        .filter(|&(_, ref log)| log.len() > 1)
+        .collect();
+
+    if logs.is_empty() {
+        Ok(())
+    } else {
+        Err(ProgramBuildError::DeviceBuildLogs(DeviceBuildLogs(logs)))
+    }
+}
+
+
+/// Returns the build log of `program` for each device listed in `device_ids`.
+///
+/// Logs are returned regardless of length and in the same order as
+/// `device_ids`.
+///
+pub fn program_build_logs<D: ClDeviceIdPtr>(program: &Program, device_ids: &[D])
+        -> OclCoreResult<Vec<(DeviceId, String)>> {
+    let mut logs = Vec::with_capacity(device_ids.len());
+
     for device_id in device_ids.iter().cloned() {
-        match get_program_build_info(program, device_id, ProgramBuildInfo::BuildLog) {
-            Ok(ProgramBuildInfoResult::BuildLog(log)) => {
-                if log.len() > 1 {
-                    return Err(ProgramBuildError::BuildLog(log));
-                }
-            },
-            Err(err) => return Err(ProgramBuildError::InfoResult(Box::new(err))),
+        let device = unsafe { DeviceId::from_raw(device_id.as_ptr()) };
+
+        match get_program_build_info(program, device_id, ProgramBuildInfo::BuildLog)? {
+            ProgramBuildInfoResult::BuildLog(log) => logs.push((device, log)),
             _ => panic!("Unexpected 'ProgramBuildInfoResult' variant."),
         }
     }
 
-    Ok(())
+    Ok(logs)
+}
+
+/// Returns a `ProgramBuildError::DeviceBuildLogs` containing each non-empty
+/// build log of `program` for the listed devices (or all devices associated
+/// with `program` if `device_ids` is `None`).
+fn program_device_build_err<D: ClDeviceIdPtr>(program: &Program, device_ids: Option<&[D]>)
+        -> OclCoreError {
+    let logs = match device_ids {
+        Some(ds) => program_build_logs(program, ds),
+        None => program.devices().and_then(|ds| program_build_logs(program, &ds)),
+    };
+
+    match logs {
+        Ok(logs) => {
+            let logs = logs.into_iter().filter(|&(_, ref log)| log.len() > 1).collect();
+            ProgramBuildError::DeviceBuildLogs(DeviceBuildLogs(logs)).into()
+        },
+        Err(err) => ProgramBuildError::InfoResult(Box::new(err)).into(),
+    }
 }
 
 
@@ -256,9 +359,16 @@ pub fn program_build_err<D: ClDeviceIdPtr>(program: &Program, device_ids: &[D])
 #[derive(Debug)]
 pub(crate) enum ApiFunction {
     None,
+    CreateSubDevices,
     RetainDevice,
     ReleaseDevice,
     CreateImage,
+    CreateProgramWithBuiltInKernels,
+    #[cfg(feature = "opencl_version_2_1")] CreateProgramWithIl,
+    #[cfg(feature = "opencl_version_2_1")] GetDeviceAndHostTimer,
+    #[cfg(feature = "opencl_version_2_1")] GetHostTimer,
+    CompileProgram,
+    LinkProgram,
     CreateFromGLTexture,
     GetKernelArgInfo,
     EnqueueFillBuffer,
@@ -267,6 +377,19 @@ pub(crate) enum ApiFunction {
     EnqueueMarkerWithWaitList,
     EnqueueBarrierWithWaitList,
     GetExtensionFunctionAddressForPlatform,
+    #[cfg(feature = "opencl_version_2_0")] CreatePipe,
+    #[cfg(feature = "opencl_version_2_1")] SetDefaultDeviceCommandQueue,
+    #[cfg(feature = "opencl_version_2_1")] CloneKernel,
+    #[cfg(feature = "opencl_version_2_1")] GetKernelSubGroupInfo,
+    #[cfg(feature = "opencl_version_2_0")] SvmAlloc,
+    #[cfg(feature = "opencl_version_2_0")] SetKernelArgSvmPointer,
+    #[cfg(feature = "opencl_version_2_0")] SetKernelExecInfo,
+    #[cfg(feature = "opencl_version_2_0")] EnqueueSvmFree,
+    #[cfg(feature = "opencl_version_2_0")] EnqueueSvmMemcpy,
+    #[cfg(feature = "opencl_version_2_0")] EnqueueSvmMemFill,
+    #[cfg(feature = "opencl_version_2_0")] EnqueueSvmMap,
+    #[cfg(feature = "opencl_version_2_0")] EnqueueSvmUnmap,
+    #[cfg(feature = "opencl_version_2_1")] EnqueueSvmMigrateMem,
 }
 
 
@@ -279,9 +402,7 @@ pub(crate) enum VersionKind {
 
 
 /// A version too low error.
-#[derive(Debug, Fail)]
-#[fail(display = "OpenCL ({:?}) version too low to use {:?} (detected: {}, required: {}).",
-    kind, function, detected, required)]
+#[derive(Debug)]
 pub struct VersionLowError {
     detected: OpenclVersion,
     required: OpenclVersion,
@@ -289,43 +410,162 @@ pub struct VersionLowError {
     kind: VersionKind,
 }
 
+impl VersionLowError {
+    /// Returns the detected device or platform version.
+    pub fn detected(&self) -> OpenclVersion {
+        self.detected
+    }
+
+    /// Returns the version required to use the function.
+    pub fn required(&self) -> OpenclVersion {
+        self.required
+    }
+
+    /// Returns the name of the API function which could not be used.
+    pub fn function(&self) -> String {
+        format!("{:?}", self.function)
+    }
+
+    /// Returns true if the version of a device (rather than of a platform)
+    /// was too low.
+    pub fn is_device_version(&self) -> bool {
+        match self.kind {
+            VersionKind::Device => true,
+            VersionKind::Platform => false,
+        }
+    }
+}
+
+impl fmt::Display for VersionLowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "OpenCL ({:?}) version too low to use {:?} (detected: {}, required: {}).",
+            self.kind, self.function, self.detected, self.required)
+    }
+}
+
+impl StdError for VersionLowError {}
+
 
 /// An error representing miscellaneous errors from throughout this module.
-#[derive(Debug, Fail)]
+#[derive(Debug)]
 pub enum ApiWrapperError {
-    #[fail(display = "Unable to get platform id list after {} seconds of waiting.", _0)]
     GetPlatformIdsPlatformListUnavailable(u64),
-    #[fail(display = "`devices_max` can not be zero.")]
     GetDeviceIdsDevicesMaxZero,
-    #[fail(display = "No devices specified.")]
     CreateContextNoDevicesSpecified,
-    #[fail(display = "Buffer length and data length and do not match.")]
     CreateBufferDataLengthMismatch,
-    #[fail(display = "One or more of the devices contained in the list provided to \
-        '::create_context` doesn't support the cl_gl_sharing extension and cannot be \
-        used to create a context associated with OpenGL. [FIXME: determine recommended \
-        resolution - gl_device list fn doesn't work yet].")]
     CreateContextClGlSharingUnsupported,
-    #[fail(display = "Length of 'devices' must be greater than zero.")]
     CreateProgramWithBinaryDevicesLenZero,
-    #[fail(display = "Length of 'devices' must equal the length of 'binaries' \
-        (e.g. one binary per device).")]
     CreateProgramWithBinaryDevicesLenMismatch,
-    #[fail(display = "The specified function does not exist for the implementation or \
-        'platform' is not a valid platform.")]
+    CreateProgramWithBuiltInKernelsDevicesLenZero,
+    CompileProgramHeadersLenMismatch,
+    LinkProgramInputProgramsLenZero,
+    EnqueueNativeKernelUnsupported,
+    CreateCommandQueueWithPropertiesUnsupported(OpenclVersion),
+    #[cfg(feature = "opencl_version_2_0")]
+    SvmAllocFailed,
+    #[cfg(feature = "opencl_version_2_1")]
+    EnqueueSvmMigrateMemSizesLenMismatch,
     GetExtensionFunctionAddressForPlatformInvalidFunction,
-    #[fail(display = "No OpenCL platforms found. Check your driver.")]
     DefaultPlatformNoPlatforms,
-    #[fail(display = "The default platform set by the environment variable \
-        'OCL_DEFAULT_PLATFORM_IDX' has an index which is out of range \
-        (index: [{}], max: [{}]).", default_platform_idx, max_idx)]
     DefaultPlatformEnvVarBadIdx { default_platform_idx: usize, max_idx: usize },
-    #[fail(display = "The default device type set by the environment variable \
-        'OCL_DEFAULT_DEVICE_TYPE': ('{}') is invalid. Valid types are: 'DEFAULT', 'CPU', \
-        'GPU', 'ACCELERATOR', 'CUSTOM', and 'ALL'.", _0)]
     DefaultDeviceTypeInvalidType(String),
+    InfoUnavailable(Status),
+    GetContextInfoInvalidContext { result_size: usize },
+    GetGlContextInfoKhrNoPlatform,
+    GetGlContextInfoKhrUnsupported,
+    EventStatusInvalid(i32),
+    EventTerminated(i32),
+    PlatformIdNull,
+    DeviceIdNull,
+    EventSetCallbackNull,
+}
+
+impl fmt::Display for ApiWrapperError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ApiWrapperError::GetPlatformIdsPlatformListUnavailable(secs) => write!(f, "Unable \
+                to get platform id list after {} seconds of waiting.", secs),
+            ApiWrapperError::GetDeviceIdsDevicesMaxZero => write!(f, "`devices_max` can not be \
+                zero."),
+            ApiWrapperError::CreateContextNoDevicesSpecified => write!(f, "No devices \
+                specified."),
+            ApiWrapperError::CreateBufferDataLengthMismatch => write!(f, "Buffer length and \
+                data length and do not match."),
+            ApiWrapperError::CreateContextClGlSharingUnsupported => write!(f, "One or more of \
+                the devices contained in the list provided to '::create_context` doesn't \
+                support the cl_gl_sharing extension and cannot be used to create a context \
+                associated with OpenGL. [FIXME: determine recommended resolution - gl_device \
+                list fn doesn't work yet]."),
+            ApiWrapperError::CreateProgramWithBinaryDevicesLenZero => write!(f, "Length of \
+                'devices' must be greater than zero."),
+            ApiWrapperError::CreateProgramWithBinaryDevicesLenMismatch => write!(f, "Length of \
+                'devices' must equal the length of 'binaries' (e.g. one binary per device)."),
+            ApiWrapperError::CreateProgramWithBuiltInKernelsDevicesLenZero => write!(f, "Length \
+                of 'devices' must be greater than zero."),
+            ApiWrapperError::CompileProgramHeadersLenMismatch => write!(f, "Length of \
+                'input_headers' must equal the length of 'header_include_names' (e.g. one \
+                include name per header)."),
+            ApiWrapperError::LinkProgramInputProgramsLenZero => write!(f, "Length of \
+                'input_programs' must be greater than zero."),
+            ApiWrapperError::EnqueueNativeKernelUnsupported => write!(f, "The device associated \
+                with the command queue does not support native kernels \
+                ('DeviceExecCapabilities::NATIVE_KERNEL')."),
+            ApiWrapperError::CreateCommandQueueWithPropertiesUnsupported(ver) => write!(f, "The \
+                device (OpenCL {}) does not support creating command queues with properties \
+                other than 'CommandQueueProperties::OUT_OF_ORDER_EXEC_MODE_ENABLE' and \
+                'CommandQueueProperties::PROFILING_ENABLE'. OpenCL 2.0+ and the \
+                'opencl_version_2_0' feature are required.", ver),
+            #[cfg(feature = "opencl_version_2_0")]
+            ApiWrapperError::SvmAllocFailed => write!(f, "Unable to allocate shared virtual \
+                memory. The requested size, alignment, or flags may not be supported by one or \
+                more devices."),
+            #[cfg(feature = "opencl_version_2_1")]
+            ApiWrapperError::EnqueueSvmMigrateMemSizesLenMismatch => write!(f, "Length of \
+                'sizes' must be zero or equal the length of 'svm_pointers'."),
+            ApiWrapperError::GetExtensionFunctionAddressForPlatformInvalidFunction => write!(f,
+                "The specified function does not exist for the implementation or 'platform' is \
+                not a valid platform."),
+            ApiWrapperError::DefaultPlatformNoPlatforms => write!(f, "No OpenCL platforms \
+                found. Check your driver."),
+            ApiWrapperError::DefaultPlatformEnvVarBadIdx { default_platform_idx, max_idx } =>
+                write!(f, "The default platform set by the environment variable \
+                'OCL_DEFAULT_PLATFORM_IDX' has an index which is out of range \
+                (index: [{}], max: [{}]).", default_platform_idx, max_idx),
+            ApiWrapperError::DefaultDeviceTypeInvalidType(ref ty) => write!(f, "The default \
+                device type set by the environment variable 'OCL_DEFAULT_DEVICE_TYPE': ('{}') \
+                is invalid. Valid types are: 'DEFAULT', 'CPU', 'GPU', 'ACCELERATOR', 'CUSTOM', \
+                and 'ALL'.", ty),
+            ApiWrapperError::InfoUnavailable(status) => write!(f, "<unavailable ({:?})>",
+                status),
+            ApiWrapperError::GetContextInfoInvalidContext { result_size } => write!(f, "\n\n\
+                ocl::core::context_info(): Possible invalid context detected. \n\
+                Context info result size is either '> 10k bytes' or '== 0' ({} bytes). Almost \
+                certainly an \ninvalid context object. If not, please file an issue at: \n\
+                https://github.com/cogciprocate/ocl/issues.\n\n", result_size),
+            ApiWrapperError::GetGlContextInfoKhrNoPlatform => write!(f, "Context properties \
+                must specify a platform to get OpenGL context info."),
+            ApiWrapperError::GetGlContextInfoKhrUnsupported => write!(f, "Unable to get \
+                extension function address for clGetGLContextInfoKHR. The function is not \
+                supported by this platform."),
+            ApiWrapperError::EventStatusInvalid(status) => write!(f, "Error converting \
+                'clGetEventInfo' status output ({}).", status),
+            ApiWrapperError::EventTerminated(status) => match Status::from_i32(status) {
+                Some(s) => write!(f, "The command associated with this event terminated \
+                    abnormally (status: {:?}).", s),
+                None => write!(f, "The command associated with this event terminated \
+                    abnormally (status: {}).", status),
+            },
+            ApiWrapperError::PlatformIdNull => write!(f, "The platform id is null."),
+            ApiWrapperError::DeviceIdNull => write!(f, "The device id is null."),
+            ApiWrapperError::EventSetCallbackNull => write!(f, "This event is null. Cannot set \
+                callback until internal event pointer is actually created by a `clCreate...` \
+                function."),
+        }
+    }
 }
 
+impl StdError for ApiWrapperError {}
+
 
 //============================================================================
 //============================================================================
@@ -436,6 +676,11 @@ fn verify_device_versions<V: ClVersions>(provided_versions: Option<&[OpenclVersi
 
 /// Returns a list of available platforms as 'core' objects.
 pub fn get_platform_ids() -> OclCoreResult<Vec<PlatformId>> {
+    // Load the OpenCL library now so that its absence is reported as an
+    // error rather than a panic upon the first API call:
+    #[cfg(feature = "dynamic")]
+    try!(ffi::dynamic::load());
+
     let mut num_platforms = 0 as cl_uint;
 
     // Get a count of available platforms:
@@ -603,9 +848,9 @@ pub fn get_device_info<D: ClDeviceIdPtr>(device: D, request: DeviceInfo)
     // function and is a bug. Don't hold your breath for a fix.
     if errcode < 0 {
         if Status::from_i32(errcode).unwrap() == Status::CL_INVALID_VALUE {
-            return Err(OclCoreError::from("<unavailable (CL_INVALID_VALUE)>"));
+            return Err(ApiWrapperError::InfoUnavailable(Status::CL_INVALID_VALUE).into());
         } else if Status::from_i32(errcode).unwrap() == Status::CL_INVALID_OPERATION {
-            return Err(OclCoreError::from("<unavailable (CL_INVALID_OPERATION)>"));
+            return Err(ApiWrapperError::InfoUnavailable(Status::CL_INVALID_OPERATION).into());
         }
     }
 
@@ -645,18 +890,41 @@ pub fn get_device_info<D: ClDeviceIdPtr>(device: D, request: DeviceInfo)
     }
 }
 
-/// [UNIMPLEMENTED: Please implement me]
+/// Partitions a device into sub-devices as specified by `partition`.
+///
+/// The returned sub-devices each have a reference count of one and must be
+/// released with `::release_device` when no longer needed.
 ///
 /// [Version Controlled: OpenCL 1.2+] See module docs for more info.
-pub fn create_sub_devices(device_version: Option<&OpenclVersion>) -> OclCoreResult<()> {
-    // clCreateSubDevices(in_device: cl_device_id,
-    //                    properties: *const cl_device_partition_property,
-    //                    num_devices: cl_uint,
-    //                    out_devices: *mut cl_device_id,
-    //                    num_devices_ret: *mut cl_uint) -> cl_int;
+pub fn create_sub_devices(in_device: &DeviceId, partition: &DevicePartition,
+        device_version: Option<&OpenclVersion>) -> OclCoreResult<Vec<DeviceId>>
+{
+    verify_device_version(device_version, [1, 2], in_device, ApiFunction::CreateSubDevices)?;
+
+    let props_raw = partition.to_raw();
+    let mut num_devices = 0 as cl_uint;
+
+    // Get a count of the sub-devices which would be created:
+    let errcode = unsafe { ffi::clCreateSubDevices(
+        in_device.as_ptr(),
+        props_raw.as_ptr(),
+        0,
+        ptr::null_mut(),
+        &mut num_devices,
+    ) };
+    eval_errcode(errcode, (), "clCreateSubDevices", None::<String>)?;
+
+    let mut sub_devices: Vec<DeviceId> = iter::repeat(unsafe { DeviceId::null() })
+        .take(num_devices as usize).collect();
 
-    let _ = device_version;
-    unimplemented!();
+    let errcode = unsafe { ffi::clCreateSubDevices(
+        in_device.as_ptr(),
+        props_raw.as_ptr(),
+        num_devices,
+        sub_devices.as_mut_ptr() as *mut cl_device_id,
+        ptr::null_mut(),
+    ) };
+    eval_errcode(errcode, sub_devices, "clCreateSubDevices", None::<String>)
 }
 
 /// Increments the reference count of a device.
@@ -677,6 +945,47 @@ pub unsafe fn release_device(device: &DeviceId, device_version: Option<&OpenclVe
     eval_errcode(ffi::clReleaseDevice(device.as_ptr()), (), "clReleaseDevice", None::<String>)
 }
 
+/// Returns a reasonably synchronized pair of timestamps, `(device_timestamp,
+/// host_timestamp)`, from the device timer and the host timer.
+///
+/// Both are in nanoseconds. The device timestamp is in the same timebase as
+/// event profiling info. The host timestamp is in the same timebase as
+/// `::get_host_timer`.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.1/docs/man/xhtml/clGetDeviceAndHostTimer.html)
+///
+/// [Version Controlled: OpenCL 2.1+] See module docs for more info.
+#[cfg(feature = "opencl_version_2_1")]
+pub fn get_device_and_host_timer(device: &DeviceId, device_version: Option<&OpenclVersion>)
+            -> OclCoreResult<(u64, u64)> {
+    verify_device_version(device_version, [2, 1], device, ApiFunction::GetDeviceAndHostTimer)?;
+
+    let mut device_timestamp: cl_ulong = 0;
+    let mut host_timestamp: cl_ulong = 0;
+
+    let errcode = unsafe { ffi::clGetDeviceAndHostTimer(device.as_ptr(), &mut device_timestamp,
+        &mut host_timestamp) };
+    eval_errcode(errcode, (device_timestamp, host_timestamp), "clGetDeviceAndHostTimer",
+        None::<String>)
+}
+
+/// Returns the current value of the host timer (in nanoseconds) as seen by
+/// `device`.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.1/docs/man/xhtml/clGetHostTimer.html)
+///
+/// [Version Controlled: OpenCL 2.1+] See module docs for more info.
+#[cfg(feature = "opencl_version_2_1")]
+pub fn get_host_timer(device: &DeviceId, device_version: Option<&OpenclVersion>)
+            -> OclCoreResult<u64> {
+    verify_device_version(device_version, [2, 1], device, ApiFunction::GetHostTimer)?;
+
+    let mut host_timestamp: cl_ulong = 0;
+
+    let errcode = unsafe { ffi::clGetHostTimer(device.as_ptr(), &mut host_timestamp) };
+    eval_errcode(errcode, host_timestamp, "clGetHostTimer", None::<String>)
+}
+
 //============================================================================
 //============================= Context APIs  ================================
 //============================================================================
@@ -849,11 +1158,8 @@ fn get_context_info_unparsed<C>(context: C, request: ContextInfo)
         let err_if_zero_result_size = request as cl_context_info == ffi::CL_CONTEXT_DEVICES;
 
         if result_size > 10000 || (result_size == 0 && err_if_zero_result_size) {
-            return Err(OclCoreError::from("\n\nocl::core::context_info(): \
-                Possible invalid context detected. \n\
-                Context info result size is either '> 10k bytes' or '== 0'. Almost certainly an \n\
-                invalid context object. If not, please file an issue at: \n\
-                https://github.com/cogciprocate/ocl/issues.\n\n"));
+            return Err(ApiWrapperError::GetContextInfoInvalidContext { result_size: result_size }
+                .into());
         }
     }
 
@@ -944,8 +1250,7 @@ pub fn get_gl_context_info_khr(properties: &ContextProperties, request: GlContex
         let plat = match properties.get_platform() {
             Some(p) => p,
             None => {
-                return Err("ocl::core::get_gl_context_info_khr: \
-                    Context properties must specify a platform.".into());
+                return Err(ApiWrapperError::GetGlContextInfoKhrNoPlatform.into());
             },
         };
 
@@ -953,9 +1258,7 @@ pub fn get_gl_context_info_khr(properties: &ContextProperties, request: GlContex
             fn_name.as_ptr() as *mut _);
 
         if fn_ptr.is_null() {
-            return Err("Unable to get extension function \
-                address for clGetGLContextInfoKHR. The function is not supported by this \
-                platform.".into());
+            return Err(ApiWrapperError::GetGlContextInfoKhrUnsupported.into());
         }
 
         fn_ptr as ffi::clGetGLContextInfoKHR_fn
@@ -1035,6 +1338,80 @@ pub fn create_command_queue<C, D>(
 
 }
 
+/// Returns a new command queue pointer created using a list of properties.
+///
+/// Uses `clCreateCommandQueueWithProperties` if the device supports OpenCL
+/// 2.0+ and the `opencl_version_2_0` feature is enabled. Otherwise falls back
+/// to `clCreateCommandQueue`, in which case only the
+/// `OUT_OF_ORDER_EXEC_MODE_ENABLE` and `PROFILING_ENABLE` properties may be
+/// specified.
+///
+/// The device version is queried if `device_version` is `None`.
+pub fn create_command_queue_with_properties<C, D>(
+            context: C,
+            device: D,
+            properties: &QueueProperties,
+            device_version: Option<&OpenclVersion>,
+        ) -> OclCoreResult<CommandQueue>
+        where C: ClContextPtr, D: ClDeviceIdPtr
+{
+    let device_version = match device_version {
+        Some(dv) => *dv,
+        None => try!(unsafe { DeviceId::from_raw(device.as_ptr()) }.version()),
+    };
+
+    #[cfg(feature = "opencl_version_2_0")]
+    {
+        if device_version >= OpenclVersion::new(2, 0) {
+            // Verify that the context is valid:
+            try!(verify_context(context));
+
+            let props_raw = properties.to_raw();
+            let mut errcode: cl_int = 0;
+
+            let cq_ptr = unsafe { ffi::clCreateCommandQueueWithProperties(
+                context.as_ptr(),
+                device.as_ptr(),
+                props_raw.as_ptr(),
+                &mut errcode
+            ) };
+            return eval_errcode(errcode, cq_ptr, "clCreateCommandQueueWithProperties",
+                    None::<String>)
+                .map(|cq_ptr| unsafe { CommandQueue::from_raw_create_ptr(cq_ptr) });
+        }
+    }
+
+    if !properties.is_legacy_compatible() {
+        return Err(ApiWrapperError::CreateCommandQueueWithPropertiesUnsupported(device_version)
+            .into());
+    }
+
+    create_command_queue(context, device, properties.get_properties())
+}
+
+/// Replaces the default on-device command queue of `device` with `queue`.
+///
+/// `queue` must have been created with `CommandQueueProperties::ON_DEVICE`.
+#[cfg(feature = "opencl_version_2_1")]
+pub fn set_default_device_command_queue<C, D>(
+            context: C,
+            device: D,
+            queue: &CommandQueue,
+            device_version: Option<&OpenclVersion>,
+        ) -> OclCoreResult<()>
+        where C: ClContextPtr, D: ClDeviceIdPtr
+{
+    verify_device_version(device_version, [2, 1], &unsafe { DeviceId::from_raw(device.as_ptr()) },
+        ApiFunction::SetDefaultDeviceCommandQueue)?;
+
+    let errcode = unsafe { ffi::clSetDefaultDeviceCommandQueue(
+        context.as_ptr(),
+        device.as_ptr(),
+        queue.as_ptr(),
+    ) };
+    eval_errcode(errcode, (), "clSetDefaultDeviceCommandQueue", None::<String>)
+}
+
 /// Increments the reference count of a command queue.
 pub unsafe fn retain_command_queue(queue: &CommandQueue) -> OclCoreResult<()> {
     eval_errcode(ffi::clRetainCommandQueue(queue.as_ptr()), (), "clRetainCommandQueue", None::<String>)
@@ -1525,12 +1902,174 @@ pub fn get_image_info(obj: &Mem, request: ImageInfo) -> OclCoreResult<ImageInfoR
     ImageInfoResult::from_bytes(request, result)
 }
 
-/// [UNIMPLEMENTED: Please implement me]
-pub fn set_mem_object_destructor_callback() -> OclCoreResult<()> {
-    // ffi::clSetMemObjectDestructorCallback(memobj: cl_mem,
-    //                                     pfn_notify: extern fn (cl_mem, *mut c_void),
-    //                                     user_data: *mut c_void) -> cl_int;
-    unimplemented!();
+/// Creates a new pipe object which stores up to `pipe_max_packets` packets
+/// of `pipe_packet_size` bytes each.
+///
+/// Pipes can only be accessed by kernels and are used to pass data between
+/// them without involving the host. `flags` may only contain
+/// `MemFlags::READ_WRITE` and/or `MemFlags::HOST_NO_ACCESS` (or be empty).
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clCreatePipe.html)
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+#[cfg(feature = "opencl_version_2_0")]
+pub fn create_pipe<C>(
+            context: C,
+            flags: MemFlags,
+            pipe_packet_size: u32,
+            pipe_max_packets: u32,
+            device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<Mem>
+        where C: ClContextPtr + ClVersions
+{
+    verify_device_versions(device_versions, [2, 0], &context, ApiFunction::CreatePipe)?;
+
+    let mut errcode: cl_int = 0;
+
+    let pipe_ptr = unsafe { ffi::clCreatePipe(
+        context.as_ptr(),
+        flags.bits() as cl_mem_flags,
+        pipe_packet_size,
+        pipe_max_packets,
+        ptr::null(),
+        &mut errcode,
+    ) };
+
+    eval_errcode(errcode, pipe_ptr, "clCreatePipe", None::<String>)
+        .map(|ptr| unsafe { Mem::from_raw_create_ptr(ptr) })
+}
+
+/// Get pipe info.
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+#[cfg(feature = "opencl_version_2_0")]
+pub fn get_pipe_info(obj: &Mem, request: PipeInfo) -> OclCoreResult<PipeInfoResult> {
+    let mut result_size: size_t = 0;
+
+    let errcode = unsafe { ffi::clGetPipeInfo(
+        obj.as_ptr() as cl_mem,
+        request as ffi::cl_pipe_info,
+        0 as size_t,
+        0 as *mut c_void,
+        &mut result_size as *mut size_t,
+    ) };
+
+    eval_errcode(errcode, (), "clGetPipeInfo", None::<String>)?;
+
+    // If result size is zero, return an empty info result directly:
+    if result_size == 0 {
+        return PipeInfoResult::from_bytes(request, vec![]);
+    }
+
+    let mut result: Vec<u8> = iter::repeat(0u8).take(result_size).collect();
+
+    let errcode = unsafe { ffi::clGetPipeInfo(
+        obj.as_ptr() as cl_mem,
+        request as ffi::cl_pipe_info,
+        result_size,
+        result.as_mut_ptr() as *mut _ as *mut c_void,
+        0 as *mut size_t,
+    ) };
+
+    let result = eval_errcode(errcode, result, "clGetPipeInfo", None::<String>)?;
+    PipeInfoResult::from_bytes(request, result)
+}
+
+/// Calls the boxed destructor function pointed to by `user_data`.
+extern "C" fn _mem_object_destructor_trampoline(_memobj: cl_mem, user_data: *mut c_void) {
+    use std::panic::{self, AssertUnwindSafe};
+
+    let func = unsafe { Box::from_raw(user_data as *mut MemDestructorFn) };
+
+    // Unwinding across the FFI boundary is undefined behavior. There is
+    // nowhere to report a panic so it is discarded:
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| func()));
+}
+
+/// Registers a function to be called when the memory object is actually
+/// destroyed by the runtime (after its reference count reaches zero and
+/// all commands using it have completed).
+///
+/// Multiple functions may be registered for the same memory object and will
+/// be called in the reverse order of registration. Once called, it is safe
+/// to reuse or free any memory used as the `host_ptr` of the memory object
+/// (e.g. when created using `MemFlags::USE_HOST_PTR`).
+///
+/// If `func` panics, the panic is caught and silently discarded.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/1.2/docs/man/xhtml/clSetMemObjectDestructorCallback.html)
+pub fn set_mem_object_destructor_callback(memobj: &Mem, func: MemDestructorFn)
+        -> OclCoreResult<()> {
+    let user_data = Box::into_raw(Box::new(func));
+
+    let errcode = unsafe { ffi::clSetMemObjectDestructorCallback(
+        memobj.as_ptr(),
+        Some(_mem_object_destructor_trampoline),
+        user_data as *mut c_void,
+    ) };
+
+    if errcode != Status::CL_SUCCESS as i32 {
+        // The function will never be called:
+        unsafe { drop(Box::from_raw(user_data)); }
+    }
+
+    eval_errcode(errcode, (), "clSetMemObjectDestructorCallback", None::<String>)
+}
+
+//============================================================================
+//===================== Shared Virtual Memory APIs ===========================
+//============================================================================
+
+/// Allocates a shared virtual memory (SVM) buffer of `size` bytes which can
+/// be shared by the host and all devices in `context`.
+///
+/// An `alignment` of zero uses the default alignment (the size of the
+/// largest data type supported by the context's devices).
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clSVMAlloc.html)
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+#[cfg(feature = "opencl_version_2_0")]
+pub fn svm_alloc<C>(
+            context: C,
+            flags: SvmMemFlags,
+            size: usize,
+            alignment: u32,
+            device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<*mut c_void>
+        where C: ClContextPtr + ClVersions
+{
+    verify_device_versions(device_versions, [2, 0], &context, ApiFunction::SvmAlloc)?;
+
+    let svm_ptr = unsafe { ffi::clSVMAlloc(
+        context.as_ptr(),
+        flags.bits(),
+        size,
+        alignment,
+    ) };
+
+    if svm_ptr.is_null() {
+        Err(ApiWrapperError::SvmAllocFailed.into())
+    } else {
+        Ok(svm_ptr)
+    }
+}
+
+/// Frees a shared virtual memory buffer allocated with `::svm_alloc`.
+///
+/// This function returns immediately, without waiting for any previously
+/// enqueued commands which may be using the buffer. Use `::enqueue_svm_free`
+/// to free a buffer after those commands complete.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clSVMFree.html)
+///
+/// ## Safety
+///
+/// `svm_pointer` must have been returned by `::svm_alloc` using the same
+/// context and must not be used again after this call.
+#[cfg(feature = "opencl_version_2_0")]
+pub unsafe fn svm_free<C: ClContextPtr>(context: C, svm_pointer: *mut c_void) {
+    ffi::clSVMFree(context.as_ptr(), svm_pointer)
 }
 
 //============================================================================
@@ -1693,18 +2232,44 @@ pub fn create_program_with_binary<C, D>(
     unsafe { Ok(Program::from_raw_create_ptr(program)) }
 }
 
-/// [UNIMPLEMENTED: Please implement me]
+/// Creates a program object for a context, and loads the information
+/// related to the built-in kernels named in `kernel_names` into it.
+///
+/// `kernel_names` is a semi-colon separated list of built-in kernel names,
+/// each of which must be supported by every device in `devices` (see
+/// `DeviceInfo::BuiltInKernels`). The resulting program does not need to be
+/// built.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/1.2/docs/man/xhtml/clCreateProgramWithBuiltInKernels.html)
 ///
 /// [Version Controlled: OpenCL 1.2+] See module docs for more info.
-pub fn create_program_with_built_in_kernels(device_version: Option<&OpenclVersion>)
-            -> OclCoreResult<()> {
-    // clCreateProgramWithBuiltInKernels(context: cl_context,
-    //                                  num_devices: cl_uint,
-    //                                  device_list: *const cl_device_id,
-    //                                  kernel_names: *mut char,
-    //                                  errcode_ret: *mut cl_int) -> cl_program;
-    let _ =  device_version;
-    unimplemented!();
+pub fn create_program_with_built_in_kernels<C, D>(
+            context: C,
+            devices: &[D],
+            kernel_names: &CString,
+            device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<Program>
+        where C: ClContextPtr, D: ClDeviceIdPtr
+{
+    verify_device_versions(device_versions, [1, 2], &context.as_ptr(),
+        ApiFunction::CreateProgramWithBuiltInKernels)?;
+
+    if devices.len() == 0 {
+        return Err(ApiWrapperError::CreateProgramWithBuiltInKernelsDevicesLenZero.into())
+    }
+
+    let mut errcode: cl_int = 0;
+
+    let program_ptr = unsafe { ffi::clCreateProgramWithBuiltInKernels(
+        context.as_ptr(),
+        devices.len() as cl_uint,
+        devices.as_ptr() as *const _ as *const cl_device_id,
+        kernel_names.as_ptr() as *mut _,
+        &mut errcode,
+    ) };
+
+    eval_errcode(errcode, program_ptr, "clCreateProgramWithBuiltInKernels", None::<String>)
+        .map(|ptr| unsafe { Program::from_raw_create_ptr(ptr) })
 }
 
 /// Returns a new `Program` loaded with the provided IL bytes.
@@ -1718,8 +2283,8 @@ pub fn create_program_with_il<C>(
         ) -> OclCoreResult<Program>
         where C: ClContextPtr + ClVersions
 {
-    verify_device_versions(device_versions, [2, 1], &context)
-        .chain_err(|| "::create_program_with_il")?;
+    verify_device_versions(device_versions, [2, 1], &context,
+        ApiFunction::CreateProgramWithIl)?;
 
     let mut errcode: cl_int = 0;
 
@@ -1791,61 +2356,143 @@ pub fn build_program<D: ClDeviceIdPtr>(
     ) };
 
     if errcode == Status::CL_BUILD_PROGRAM_FAILURE as i32 {
-        if let Some(ds) = devices {
-            program_build_err(program, ds).map_err(|err| err.into())
-        } else {
-            let ds = program.devices()?;
-            program_build_err(program, &ds).map_err(|err| err.into())
-        }
+        Err(program_device_build_err(program, devices))
     } else {
         eval_errcode(errcode, (), "clBuildProgram", None::<String>)
     }
 }
 
-/// [UNIMPLEMENTED: Please implement me]
+/// Compiles a program's source for all of the devices (or the specified
+/// devices) associated with the program, producing an object which may then
+/// be linked using `link_program`.
 ///
-/// [Version Controlled: OpenCL 1.2+] See module docs for more info.
-pub fn compile_program(device_version: Option<&OpenclVersion>) -> OclCoreResult<()> {
-    // clCompileProgram(program: cl_program,
-    //                 num_devices: cl_uint,
-    //                 device_list: *const cl_device_id,
-    //                 options: *const c_char,
-    //                 num_input_headers: cl_uint,
-    //                 input_headers: *const cl_program,
-    //                 header_include_names: *const *const c_char,
-    //                 pfn_notify: extern fn (program: cl_program, user_data: *mut c_void),
-    //                 user_data: *mut c_void) -> cl_int;
-    let _ = device_version;
-    unimplemented!();
-}
-
-/// [UNIMPLEMENTED: Please implement me]
+/// Each program in `input_headers` must have been created from source and is
+/// made available to `#include` directives within `program` under the name
+/// given by the corresponding entry of `header_include_names`.
+///
+/// If compilation fails, the build log for each device is returned within a
+/// `ProgramBuildError::DeviceBuildLogs`.
+///
+/// Callback functions are not yet supported.
 ///
 /// [Version Controlled: OpenCL 1.2+] See module docs for more info.
-pub fn link_program(device_version: Option<&OpenclVersion>) -> OclCoreResult<()> {
-    // clLinkProgram(context: cl_context,
-    //               num_devices: cl_uint,
-    //               device_list: *const cl_device_id,
-    //               options: *const c_char,
-    //               num_input_programs: cl_uint,
-    //               input_programs: *const cl_program,
-    //               pfn_notify: extern fn (program: cl_program, user_data: *mut c_void),
-    //               user_data: *mut c_void,
-    //               errcode_ret: *mut cl_int) -> cl_program;
-    let _ = device_version;
-    unimplemented!();
-}
+pub fn compile_program<D: ClDeviceIdPtr>(
+            program: &Program,
+            devices: Option<&[D]>,
+            options: &CString,
+            input_headers: &[&Program],
+            header_include_names: &[CString],
+            device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<()>
+{
+    verify_device_versions(device_versions, [1, 2], program, ApiFunction::CompileProgram)?;
 
-// [DISABLED DUE TO PLATFORM INCOMPATABILITY]
-// /// [UNTESTED]
-// /// Unloads a platform compiler.
-// ///
-// /// [Version Controlled: OpenCL 1.2+] See module docs for more info.
-// pub fn unload_platform_compiler(platform: &PlatformId,
-//          device_version: Option<&OpenclVersion>) -> OclCoreResult<()> {
-//     unsafe { eval_errcode("clUnloadPlatformCompiler", "",
-//         ffi::clUnloadPlatformCompiler(platform.as_ptr())) }
-// }
+    if input_headers.len() != header_include_names.len() {
+        return Err(ApiWrapperError::CompileProgramHeadersLenMismatch.into())
+    }
+
+    let (devices_len, devices_ptr) = match devices {
+        Some(dvs) => (dvs.len() as u32, dvs.as_ptr() as *const cl_device_id),
+        None => (0, ptr::null() as *const cl_device_id),
+    };
+
+    let header_ptrs: Vec<cl_program> = input_headers.iter().map(|h| h.as_ptr()).collect();
+    let name_ptrs: Vec<*const _> = header_include_names.iter().map(|n| n.as_ptr()).collect();
+
+    let (headers_ptr, names_ptr) = if header_ptrs.is_empty() {
+        (ptr::null(), ptr::null())
+    } else {
+        (header_ptrs.as_ptr(), name_ptrs.as_ptr())
+    };
+
+    let errcode = unsafe { ffi::clCompileProgram(
+        program.as_ptr(),
+        devices_len,
+        devices_ptr,
+        options.as_ptr(),
+        header_ptrs.len() as cl_uint,
+        headers_ptr,
+        names_ptr,
+        None,
+        ptr::null_mut(),
+    ) };
+
+    if errcode == Status::CL_COMPILE_PROGRAM_FAILURE as i32 {
+        Err(program_device_build_err(program, devices))
+    } else {
+        eval_errcode(errcode, (), "clCompileProgram", None::<String>)
+    }
+}
+
+/// Links a set of compiled program objects and libraries for all of the
+/// devices (or the specified devices) associated with `context`, returning a
+/// new program.
+///
+/// Pass `-create-library` within `options` to create a library which may
+/// itself be linked into other programs, otherwise an executable is created.
+///
+/// If linking fails, the build log for each device is returned within a
+/// `ProgramBuildError::DeviceBuildLogs`.
+///
+/// Callback functions are not yet supported.
+///
+/// [Version Controlled: OpenCL 1.2+] See module docs for more info.
+pub fn link_program<C, D>(
+            context: C,
+            devices: Option<&[D]>,
+            options: &CString,
+            input_programs: &[&Program],
+            device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<Program>
+        where C: ClContextPtr, D: ClDeviceIdPtr
+{
+    verify_device_versions(device_versions, [1, 2], &context.as_ptr(), ApiFunction::LinkProgram)?;
+
+    if input_programs.len() == 0 {
+        return Err(ApiWrapperError::LinkProgramInputProgramsLenZero.into())
+    }
+
+    let (devices_len, devices_ptr) = match devices {
+        Some(dvs) => (dvs.len() as u32, dvs.as_ptr() as *const cl_device_id),
+        None => (0, ptr::null() as *const cl_device_id),
+    };
+
+    let program_ptrs: Vec<cl_program> = input_programs.iter().map(|p| p.as_ptr()).collect();
+    let mut errcode: cl_int = 0;
+
+    let program_ptr = unsafe { ffi::clLinkProgram(
+        context.as_ptr(),
+        devices_len,
+        devices_ptr,
+        options.as_ptr(),
+        program_ptrs.len() as cl_uint,
+        program_ptrs.as_ptr(),
+        None,
+        ptr::null_mut(),
+        &mut errcode,
+    ) };
+
+    // A failed link may still return a valid program from which the build
+    // logs can be retrieved:
+    if errcode == Status::CL_LINK_PROGRAM_FAILURE as i32 && !program_ptr.is_null() {
+        let program = unsafe { Program::from_raw_create_ptr(program_ptr) };
+        Err(program_device_build_err(&program, devices))
+    } else {
+        eval_errcode(errcode, program_ptr, "clLinkProgram", None::<String>)
+            .map(|ptr| unsafe { Program::from_raw_create_ptr(ptr) })
+    }
+}
+
+// [DISABLED DUE TO PLATFORM INCOMPATABILITY]
+// /// [UNTESTED]
+// /// Unloads a platform compiler.
+// ///
+// /// [Version Controlled: OpenCL 1.2+] See module docs for more info.
+// pub fn unload_platform_compiler(platform: &PlatformId,
+//          device_version: Option<&OpenclVersion>) -> OclCoreResult<()> {
+//     unsafe { eval_errcode("clUnloadPlatformCompiler", "",
+//         ffi::clUnloadPlatformCompiler(platform.as_ptr())) }
+// }
 
 
 fn get_program_info_raw(program: &Program, request: ProgramInfo) -> OclCoreResult<Vec<u8>> {
@@ -1981,13 +2628,56 @@ pub fn create_kernel<S: AsRef<str>>(program: &Program, name: S) -> OclCoreResult
     }
 }
 
-/// [UNIMPLEMENTED: Please implement me]
-pub fn create_kernels_in_program() -> OclCoreResult<()> {
-    // ffi::clCreateKernelsInProgram(program: cl_program,
-    //                             num_kernels: cl_uint,
-    //                             kernels: *mut cl_kernel,
-    //                             num_kernels_ret: *mut cl_uint) -> cl_int;
-    unimplemented!();
+/// Creates a kernel object for every kernel function in `program`.
+///
+/// The program must have been successfully built (or linked) for at least
+/// one device. Use `get_kernel_info` with `KernelInfo::FunctionName` to
+/// determine the name of each kernel.
+pub fn create_kernels_in_program(program: &Program) -> OclCoreResult<Vec<Kernel>> {
+    let mut num_kernels: cl_uint = 0;
+
+    let errcode = unsafe { ffi::clCreateKernelsInProgram(
+        program.as_ptr(),
+        0,
+        ptr::null_mut(),
+        &mut num_kernels,
+    ) };
+    try!(eval_errcode(errcode, (), "clCreateKernelsInProgram", None::<String>));
+
+    if num_kernels == 0 { return Ok(Vec::new()); }
+
+    let mut kernel_ptrs: Vec<cl_kernel> = vec![ptr::null_mut(); num_kernels as usize];
+
+    let errcode = unsafe { ffi::clCreateKernelsInProgram(
+        program.as_ptr(),
+        num_kernels,
+        kernel_ptrs.as_mut_ptr(),
+        ptr::null_mut(),
+    ) };
+    try!(eval_errcode(errcode, (), "clCreateKernelsInProgram", None::<String>));
+
+    Ok(kernel_ptrs.into_iter().map(|ptr| unsafe { Kernel::from_raw_create_ptr(ptr) }).collect())
+}
+
+/// Returns a copy of `source_kernel` including all of its argument values
+/// and execution info.
+///
+/// The returned kernel is an independent kernel object. Setting arguments
+/// on one does not affect the other.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.1/docs/man/xhtml/clCloneKernel.html)
+///
+/// [Version Controlled: OpenCL 2.1+] See module docs for more info.
+#[cfg(feature = "opencl_version_2_1")]
+pub fn clone_kernel(source_kernel: &Kernel, device_versions: Option<&[OpenclVersion]>)
+        -> OclCoreResult<Kernel> {
+    verify_device_versions(device_versions, [2, 1], source_kernel, ApiFunction::CloneKernel)?;
+
+    let mut errcode: cl_int = 0;
+
+    let kernel_ptr = unsafe { ffi::clCloneKernel(source_kernel.as_ptr(), &mut errcode) };
+    eval_errcode(errcode, kernel_ptr, "clCloneKernel", None::<String>)
+        .map(|ptr| unsafe { Kernel::from_raw_create_ptr(ptr) })
 }
 
 /// Increments a kernel reference counter.
@@ -2064,6 +2754,86 @@ pub fn set_kernel_arg<T: OclPrm>(kernel: &Kernel, arg_index: u32, arg: KernelArg
     }
 }
 
+/// Sets a shared virtual memory pointer as the argument value for a specific
+/// argument of a kernel.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clSetKernelArgSVMPointer.html)
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+#[cfg(feature = "opencl_version_2_0")]
+pub fn set_kernel_arg_svm_pointer<T: OclPrm>(
+            kernel: &Kernel,
+            arg_index: u32,
+            svm_pointer: *const T,
+            device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<()>
+{
+    verify_device_versions(device_versions, [2, 0], kernel,
+        ApiFunction::SetKernelArgSvmPointer)?;
+
+    let err = unsafe { ffi::clSetKernelArgSVMPointer(
+        kernel.as_ptr(),
+        arg_index,
+        svm_pointer as *const c_void,
+    ) };
+
+    if err != Status::CL_SUCCESS as i32 {
+        let name = get_kernel_name(kernel)?;
+        eval_errcode(err, (), "clSetKernelArgSVMPointer", Some(name))
+    } else {
+        Ok(())
+    }
+}
+
+/// Passes additional information, such as SVM pointers used indirectly by a
+/// kernel, to the implementation.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clSetKernelExecInfo.html)
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+#[cfg(feature = "opencl_version_2_0")]
+pub fn set_kernel_exec_info(
+            kernel: &Kernel,
+            exec_info: KernelExecInfo,
+            device_versions: Option<&[OpenclVersion]>,
+        ) -> OclCoreResult<()>
+{
+    verify_device_versions(device_versions, [2, 0], kernel,
+        ApiFunction::SetKernelExecInfo)?;
+
+    let fine_grain_system: cl_bool;
+
+    let (param_name, param_value_size, param_value) = match exec_info {
+        KernelExecInfo::SvmPtrs(svm_pointers) => (
+            ffi::CL_KERNEL_EXEC_INFO_SVM_PTRS,
+            mem::size_of::<*const c_void>() * svm_pointers.len(),
+            svm_pointers.as_ptr() as *const c_void,
+        ),
+        KernelExecInfo::SvmFineGrainSystem(enabled) => {
+            fine_grain_system = enabled as cl_bool;
+            (
+                ffi::CL_KERNEL_EXEC_INFO_SVM_FINE_GRAIN_SYSTEM,
+                mem::size_of::<cl_bool>(),
+                &fine_grain_system as *const cl_bool as *const c_void,
+            )
+        },
+    };
+
+    let err = unsafe { ffi::clSetKernelExecInfo(
+        kernel.as_ptr(),
+        param_name,
+        param_value_size,
+        param_value,
+    ) };
+
+    if err != Status::CL_SUCCESS as i32 {
+        let name = get_kernel_name(kernel)?;
+        eval_errcode(err, (), "clSetKernelExecInfo", Some(name))
+    } else {
+        Ok(())
+    }
+}
+
 /// Get kernel info.
 pub fn get_kernel_info(obj: &Kernel, request: KernelInfo) -> OclCoreResult<KernelInfoResult> {
     let mut result_size: size_t = 0;
@@ -2202,6 +2972,56 @@ pub fn get_kernel_work_group_info<D: ClDeviceIdPtr>(obj: &Kernel, device_obj: D,
     KernelWorkGroupInfoResult::from_bytes(request, result)
 }
 
+/// Returns sub-group information about a kernel for `device`.
+///
+/// `input_value` depends on `request`:
+///
+/// * `MaxSubGroupSizeForNdrange`, `SubGroupCountForNdrange`: The local work
+///   size (one to three dimensions).
+/// * `LocalSizeForSubGroupCount`: A single value, the desired number of
+///   sub-groups. A three dimensional local size is returned (`[0, 0, 0]` if
+///   no local size yields exactly that many sub-groups).
+/// * `MaxNumSubGroups`, `CompileNumSubGroups`: Empty.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.1/docs/man/xhtml/clGetKernelSubGroupInfo.html)
+///
+/// [Version Controlled: OpenCL 2.1+] See module docs for more info.
+#[cfg(feature = "opencl_version_2_1")]
+pub fn get_kernel_sub_group_info<D: ClDeviceIdPtr>(obj: &Kernel, device_obj: D,
+            request: KernelSubGroupInfo, input_value: &[usize],
+            device_versions: Option<&[OpenclVersion]>)
+            -> OclCoreResult<KernelSubGroupInfoResult>
+{
+    verify_device_versions(device_versions, [2, 1], obj, ApiFunction::GetKernelSubGroupInfo)?;
+
+    let result_size = match request {
+        KernelSubGroupInfo::LocalSizeForSubGroupCount => mem::size_of::<[usize; 3]>(),
+        _ => mem::size_of::<usize>(),
+    };
+
+    let input_value_ptr = if input_value.is_empty() {
+        ptr::null()
+    } else {
+        input_value.as_ptr() as *const c_void
+    };
+
+    let mut result: Vec<u8> = iter::repeat(0u8).take(result_size).collect();
+
+    let errcode = unsafe { ffi::clGetKernelSubGroupInfo(
+        obj.as_ptr() as cl_kernel,
+        device_obj.as_ptr() as cl_device_id,
+        request as cl_kernel_sub_group_info,
+        mem::size_of_val(input_value) as size_t,
+        input_value_ptr,
+        result_size as size_t,
+        result.as_mut_ptr() as *mut _ as *mut c_void,
+        0 as *mut size_t,
+    ) };
+
+    let result = eval_errcode(errcode, result, "clGetKernelSubGroupInfo", None::<String>)?;
+    KernelSubGroupInfoResult::from_bytes(request, result)
+}
+
 //============================================================================
 //========================== Event Object APIs ===============================
 //============================================================================
@@ -2322,6 +3142,59 @@ pub unsafe fn set_event_callback<'e, E: ClEventPtrRef<'e>>(
     ), (), "clSetEventCallback", None::<String>)
 }
 
+/// Calls the boxed event status function pointed to by `user_data`.
+extern "C" fn _event_status_trampoline(_event: cl_event, event_status: cl_int,
+        user_data: *mut c_void) {
+    use std::panic::{self, AssertUnwindSafe};
+
+    let func = unsafe { Box::from_raw(user_data as *mut EventStatusFn) };
+    let status = command_execution_status(event_status);
+
+    // Unwinding across the FFI boundary is undefined behavior. There is
+    // nowhere to report a panic so it is discarded:
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| func(status)));
+}
+
+/// Registers a function to be called as soon as the `callback_trigger`
+/// status is reached or the command associated with the event terminates
+/// abnormally.
+///
+/// The function is passed the status reached or, if the command terminated
+/// abnormally, an error (`ApiWrapperError::EventTerminated`) containing the
+/// negative status. It is called exactly once, on a thread of the runtime's
+/// choosing, and must not call blocking OpenCL functions (such as
+/// `wait_for_events` or `finish`). If it panics, the panic is caught and
+/// silently discarded.
+///
+/// A safe alternative to `set_event_callback`.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/1.2/docs/man/xhtml/clSetEventCallback.html)
+pub fn set_event_status_callback<'e, E: ClEventPtrRef<'e>>(
+            event: &'e E,
+            callback_trigger: CommandExecutionStatus,
+            func: EventStatusFn,
+        ) -> OclCoreResult<()>
+{
+    let event_ptr = unsafe { *event.as_ptr_ref() };
+    if event_ptr.is_null() { return Err(ApiWrapperError::EventSetCallbackNull.into()); }
+
+    let user_data = Box::into_raw(Box::new(func));
+
+    let errcode = unsafe { ffi::clSetEventCallback(
+        event_ptr,
+        callback_trigger as cl_int,
+        Some(_event_status_trampoline),
+        user_data as *mut c_void,
+    ) };
+
+    if errcode != Status::CL_SUCCESS as i32 {
+        // The function will never be called:
+        unsafe { drop(Box::from_raw(user_data)); }
+    }
+
+    eval_errcode(errcode, (), "clSetEventCallback", None::<String>)
+}
+
 //============================================================================
 //============================ Profiling APIs ================================
 //============================================================================
@@ -2349,7 +3222,7 @@ pub fn get_event_profiling_info<'e, E: ClEventPtrRef<'e>>(event: &'e E, request:
     // that event profiling info is not available on this platform.
     if errcode < 0 {
         if Status::from_i32(errcode).unwrap() == Status::CL_INVALID_VALUE {
-            return Err(OclCoreError::from("<unavailable (CL_INVALID_VALUE)>"));
+            return Err(ApiWrapperError::InfoUnavailable(Status::CL_INVALID_VALUE).into());
         }
     }
 
@@ -3246,6 +4119,251 @@ pub fn enqueue_migrate_mem_objects<En: ClNullEventPtr, Ewl: ClWaitListPtr>(
     eval_errcode(errcode, (), "clEnqueueMigrateMemObjects", None::<String>)
 }
 
+/// Enqueues a command to free shared virtual memory buffers allocated with
+/// `::svm_alloc`.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clEnqueueSVMFree.html)
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+///
+/// ## Safety
+///
+/// The buffers must not be used by any subsequently enqueued command or by
+/// the host once this command has been enqueued.
+#[cfg(feature = "opencl_version_2_0")]
+pub unsafe fn enqueue_svm_free<En: ClNullEventPtr, Ewl: ClWaitListPtr>(
+            command_queue: &CommandQueue,
+            svm_pointers: &[*mut c_void],
+            wait_list: Option<Ewl>,
+            new_event: Option<En>,
+            device_version: Option<&OpenclVersion>
+        ) -> OclCoreResult<()>
+{
+    verify_device_version(device_version, [2, 0], command_queue,
+        ApiFunction::EnqueueSvmFree)?;
+
+    let (wait_list_len, wait_list_ptr, new_event_ptr)
+        = resolve_event_ptrs(wait_list, new_event);
+
+    let errcode = ffi::clEnqueueSVMFree(
+        command_queue.as_ptr(),
+        svm_pointers.len() as cl_uint,
+        svm_pointers.as_ptr() as *const *const c_void,
+        None,
+        ptr::null_mut(),
+        wait_list_len,
+        wait_list_ptr,
+        new_event_ptr,
+    );
+    eval_errcode(errcode, (), "clEnqueueSVMFree", None::<String>)
+}
+
+/// Enqueues a command to copy `len` elements between two shared virtual
+/// memory (or host) regions.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clEnqueueSVMMemcpy.html)
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+///
+/// ## Safety
+///
+/// `dst_ptr` and `src_ptr` must each be valid for `len` elements, must not
+/// overlap, and must remain valid until the command completes.
+#[cfg(feature = "opencl_version_2_0")]
+pub unsafe fn enqueue_svm_memcpy<T, En, Ewl>(
+            command_queue: &CommandQueue,
+            block: bool,
+            dst_ptr: *mut T,
+            src_ptr: *const T,
+            len: usize,
+            wait_list: Option<Ewl>,
+            new_event: Option<En>,
+            device_version: Option<&OpenclVersion>
+        ) -> OclCoreResult<()>
+        where T: OclPrm, En: ClNullEventPtr, Ewl: ClWaitListPtr
+{
+    verify_device_version(device_version, [2, 0], command_queue,
+        ApiFunction::EnqueueSvmMemcpy)?;
+
+    let (wait_list_len, wait_list_ptr, new_event_ptr)
+        = resolve_event_ptrs(wait_list, new_event);
+
+    let errcode = ffi::clEnqueueSVMMemcpy(
+        command_queue.as_ptr(),
+        block as cl_bool,
+        dst_ptr as *mut c_void,
+        src_ptr as *const c_void,
+        len * mem::size_of::<T>(),
+        wait_list_len,
+        wait_list_ptr,
+        new_event_ptr,
+    );
+    eval_errcode(errcode, (), "clEnqueueSVMMemcpy", None::<String>)
+}
+
+/// Enqueues a command to fill `len` elements of a shared virtual memory
+/// region with `pattern`.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clEnqueueSVMMemFill.html)
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+///
+/// ## Safety
+///
+/// `svm_ptr` must be valid for `len` elements and must remain valid until
+/// the command completes.
+#[cfg(feature = "opencl_version_2_0")]
+pub unsafe fn enqueue_svm_mem_fill<T, En, Ewl>(
+            command_queue: &CommandQueue,
+            svm_ptr: *mut T,
+            pattern: T,
+            len: usize,
+            wait_list: Option<Ewl>,
+            new_event: Option<En>,
+            device_version: Option<&OpenclVersion>
+        ) -> OclCoreResult<()>
+        where T: OclPrm, En: ClNullEventPtr, Ewl: ClWaitListPtr
+{
+    verify_device_version(device_version, [2, 0], command_queue,
+        ApiFunction::EnqueueSvmMemFill)?;
+
+    let (wait_list_len, wait_list_ptr, new_event_ptr)
+        = resolve_event_ptrs(wait_list, new_event);
+
+    let errcode = ffi::clEnqueueSVMMemFill(
+        command_queue.as_ptr(),
+        svm_ptr as *mut c_void,
+        &pattern as *const T as *const c_void,
+        mem::size_of::<T>(),
+        len * mem::size_of::<T>(),
+        wait_list_len,
+        wait_list_ptr,
+        new_event_ptr,
+    );
+    eval_errcode(errcode, (), "clEnqueueSVMMemFill", None::<String>)
+}
+
+/// Enqueues a command to map `len` elements of a coarse-grained shared
+/// virtual memory region for host access.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clEnqueueSVMMap.html)
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+///
+/// ## Safety
+///
+/// The host must not access the region until the map is complete and must
+/// not access it after `::enqueue_svm_unmap` has been enqueued.
+#[cfg(feature = "opencl_version_2_0")]
+pub unsafe fn enqueue_svm_map<T, En, Ewl>(
+            command_queue: &CommandQueue,
+            block: bool,
+            map_flags: MapFlags,
+            svm_ptr: *mut T,
+            len: usize,
+            wait_list: Option<Ewl>,
+            new_event: Option<En>,
+            device_version: Option<&OpenclVersion>
+        ) -> OclCoreResult<()>
+        where T: OclPrm, En: ClNullEventPtr, Ewl: ClWaitListPtr
+{
+    verify_device_version(device_version, [2, 0], command_queue,
+        ApiFunction::EnqueueSvmMap)?;
+
+    let (wait_list_len, wait_list_ptr, new_event_ptr)
+        = resolve_event_ptrs(wait_list, new_event);
+
+    let errcode = ffi::clEnqueueSVMMap(
+        command_queue.as_ptr(),
+        block as cl_bool,
+        map_flags.bits(),
+        svm_ptr as *mut c_void,
+        len * mem::size_of::<T>(),
+        wait_list_len,
+        wait_list_ptr,
+        new_event_ptr,
+    );
+    eval_errcode(errcode, (), "clEnqueueSVMMap", None::<String>)
+}
+
+/// Enqueues a command to unmap a shared virtual memory region previously
+/// mapped with `::enqueue_svm_map`.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clEnqueueSVMUnmap.html)
+///
+/// [Version Controlled: OpenCL 2.0+] See module docs for more info.
+#[cfg(feature = "opencl_version_2_0")]
+pub unsafe fn enqueue_svm_unmap<T, En, Ewl>(
+            command_queue: &CommandQueue,
+            svm_ptr: *mut T,
+            wait_list: Option<Ewl>,
+            new_event: Option<En>,
+            device_version: Option<&OpenclVersion>
+        ) -> OclCoreResult<()>
+        where T: OclPrm, En: ClNullEventPtr, Ewl: ClWaitListPtr
+{
+    verify_device_version(device_version, [2, 0], command_queue,
+        ApiFunction::EnqueueSvmUnmap)?;
+
+    let (wait_list_len, wait_list_ptr, new_event_ptr)
+        = resolve_event_ptrs(wait_list, new_event);
+
+    let errcode = ffi::clEnqueueSVMUnmap(
+        command_queue.as_ptr(),
+        svm_ptr as *mut c_void,
+        wait_list_len,
+        wait_list_ptr,
+        new_event_ptr,
+    );
+    eval_errcode(errcode, (), "clEnqueueSVMUnmap", None::<String>)
+}
+
+/// Enqueues a command to indicate which device a set of shared virtual
+/// memory ranges should be associated with.
+///
+/// `sizes` may either be empty, in which case each entire allocation is
+/// migrated, or contain a size (in bytes) for each pointer in
+/// `svm_pointers`. A size of zero also migrates the entire allocation.
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/2.1/docs/man/xhtml/clEnqueueSVMMigrateMem.html)
+///
+/// [Version Controlled: OpenCL 2.1+] See module docs for more info.
+#[cfg(feature = "opencl_version_2_1")]
+pub fn enqueue_svm_migrate_mem<En: ClNullEventPtr, Ewl: ClWaitListPtr>(
+            command_queue: &CommandQueue,
+            svm_pointers: &[*const c_void],
+            sizes: &[usize],
+            flags: MemMigrationFlags,
+            wait_list: Option<Ewl>,
+            new_event: Option<En>,
+            device_version: Option<&OpenclVersion>
+        ) -> OclCoreResult<()>
+{
+    verify_device_version(device_version, [2, 1], command_queue,
+        ApiFunction::EnqueueSvmMigrateMem)?;
+
+    if !sizes.is_empty() && sizes.len() != svm_pointers.len() {
+        return Err(ApiWrapperError::EnqueueSvmMigrateMemSizesLenMismatch.into());
+    }
+
+    let sizes_ptr = if sizes.is_empty() { ptr::null() } else { sizes.as_ptr() };
+
+    let (wait_list_len, wait_list_ptr, new_event_ptr)
+        = resolve_event_ptrs(wait_list, new_event);
+
+    let errcode = unsafe { ffi::clEnqueueSVMMigrateMem(
+        command_queue.as_ptr(),
+        svm_pointers.len() as cl_uint,
+        svm_pointers.as_ptr(),
+        sizes_ptr,
+        flags.bits(),
+        wait_list_len,
+        wait_list_ptr,
+        new_event_ptr,
+    ) };
+    eval_errcode(errcode, (), "clEnqueueSVMMigrateMem", None::<String>)
+}
+
 /// Enqueues a command to execute a kernel on a device.
 ///
 /// ## Safety
@@ -3383,19 +4501,100 @@ pub unsafe fn enqueue_task<En: ClNullEventPtr, Ewl: ClWaitListPtr>(
     eval_errcode(errcode, (), "clEnqueueTask", kernel_name)
 }
 
-/// [UNIMPLEMENTED: Please implement me]
-pub fn enqueue_native_kernel() -> OclCoreResult<()> {
-    // ffi::clEnqueueNativeKernel(command_queue: cl_command_queue,
-    //                          user_func: extern fn (*mut c_void),
-    //                          args: *mut c_void,
-    //                          cb_args: size_t,
-    //                          num_mem_objects: cl_uint,
-    //                          mem_list: *const cl_mem,
-    //                          args_mem_loc: *const *const c_void,
-    //                          num_events_in_wait_list: cl_uint,
-    //                          event_wait_list: *const cl_event,
-    //                          event: *mut cl_event) -> cl_int;
-    unimplemented!();
+/// Runs the boxed native kernel function contained within `args`.
+///
+/// `args` points to the runtime's copy of the argument block assembled by
+/// `enqueue_native_kernel`: the function pointer, the number of memory
+/// objects, then one (runtime-substituted) host pointer per memory object.
+extern "C" fn _native_kernel_trampoline(args: *mut c_void) {
+    use std::panic::{self, AssertUnwindSafe};
+
+    unsafe {
+        let words = args as *mut usize;
+        let func = Box::from_raw(*words as *mut NativeKernelFn);
+        let mem_ptrs = ::std::slice::from_raw_parts(words.offset(2) as *const *mut c_void,
+            *words.offset(1));
+
+        // Unwinding across the FFI boundary is undefined behavior. There is
+        // nowhere to report a panic so it is discarded:
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| func(mem_ptrs)));
+    }
+}
+
+/// Enqueues a command to execute a native (host) function.
+///
+/// `func` is called on a runtime-owned host thread once all events in
+/// `wait_list` have completed. It is passed a pointer to the contents of
+/// each of the buffers in `mem_objects` (in order), as mapped into host
+/// memory by the runtime.
+///
+/// Returns an error if the device associated with `command_queue` does not
+/// support native kernels (see `DeviceExecCapabilities::NATIVE_KERNEL`).
+///
+/// If `func` panics, the panic is caught and silently discarded (the command
+/// still completes normally).
+///
+/// [SDK Docs](https://www.khronos.org/registry/OpenCL/sdk/1.2/docs/man/xhtml/clEnqueueNativeKernel.html)
+pub fn enqueue_native_kernel<En, Ewl>(
+            command_queue: &CommandQueue,
+            func: NativeKernelFn,
+            mem_objects: &[&Mem],
+            wait_list: Option<Ewl>,
+            new_event: Option<En>,
+        ) -> OclCoreResult<()>
+        where En: ClNullEventPtr, Ewl: ClWaitListPtr
+{
+    let device = command_queue.device()?;
+
+    match get_device_info(&device, DeviceInfo::ExecutionCapabilities)? {
+        DeviceInfoResult::ExecutionCapabilities(caps) => {
+            if !caps.contains(DeviceExecCapabilities::NATIVE_KERNEL) {
+                return Err(ApiWrapperError::EnqueueNativeKernelUnsupported.into());
+            }
+        },
+        _ => unreachable!(),
+    }
+
+    let (wait_list_len, wait_list_ptr, new_event_ptr) =
+        resolve_event_ptrs(wait_list, new_event);
+
+    // The argument block (copied by the runtime): [func, mem_count, mem_ptr_0, ...]
+    let func_ptr = Box::into_raw(Box::new(func));
+    let mut args: Vec<usize> = Vec::with_capacity(2 + mem_objects.len());
+    args.push(func_ptr as usize);
+    args.push(mem_objects.len());
+    args.extend(iter::repeat(0).take(mem_objects.len()));
+
+    let mem_list: Vec<cl_mem> = mem_objects.iter().map(|m| m.as_ptr()).collect();
+    let args_mem_loc: Vec<*const c_void> = (0..mem_objects.len())
+        .map(|i| unsafe { args.as_ptr().offset(2 + i as isize) as *const c_void })
+        .collect();
+
+    let (mem_list_ptr, args_mem_loc_ptr) = if mem_list.is_empty() {
+        (ptr::null(), ptr::null())
+    } else {
+        (mem_list.as_ptr(), args_mem_loc.as_ptr())
+    };
+
+    let errcode = unsafe { ffi::clEnqueueNativeKernel(
+        command_queue.as_ptr(),
+        Some(_native_kernel_trampoline),
+        args.as_mut_ptr() as *mut c_void,
+        args.len() * mem::size_of::<usize>(),
+        mem_list.len() as cl_uint,
+        mem_list_ptr,
+        args_mem_loc_ptr,
+        wait_list_len,
+        wait_list_ptr,
+        new_event_ptr,
+    ) };
+
+    if errcode != Status::CL_SUCCESS as i32 {
+        // The function will never be called:
+        unsafe { drop(Box::from_raw(func_ptr)); }
+    }
+
+    eval_errcode(errcode, (), "clEnqueueNativeKernel", None::<String>)
 }
 
 /// Enqueues a marker command which waits for either a list of events to
@@ -3640,8 +4839,18 @@ pub fn event_status<'e, E: ClEventPtrRef<'e>>(event: &'e E) -> OclCoreResult<Com
     };
     try!(eval_errcode(errcode, (), "clGetEventInfo", None::<String>));
 
-    CommandExecutionStatus::from_i32(status_int).ok_or_else(|| OclCoreError::from("Error converting \
-        'clGetEventInfo' status output."))
+    command_execution_status(status_int)
+}
+
+/// Converts a raw event status into a `CommandExecutionStatus`, returning an
+/// error for negative (abnormal termination) statuses.
+fn command_execution_status(status_int: cl_int) -> OclCoreResult<CommandExecutionStatus> {
+    if status_int < 0 {
+        return Err(ApiWrapperError::EventTerminated(status_int).into());
+    }
+
+    CommandExecutionStatus::from_i32(status_int)
+        .ok_or_else(|| ApiWrapperError::EventStatusInvalid(status_int).into())
 }
 
 /// Returns true if an event is complete, false if not complete.
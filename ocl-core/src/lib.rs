@@ -109,8 +109,6 @@ extern crate enum_primitive;
 extern crate num_traits;
 #[cfg(feature = "ocl-core-vector")]
 extern crate ocl_core_vector as vector;
-#[macro_use]
-extern crate failure;
 pub extern crate cl_sys as ffi;
 
 #[cfg(test)] mod tests;
@@ -126,17 +124,21 @@ pub use self::types::abs::{ClWaitListPtr, ClNullEventPtr, ClEventPtrRef, ClPlatf
     Program, Kernel, Event, Sampler, ClVersions, AsMem, MemCmdRw, MemCmdAll, MemMap};
 
 pub use self::types::structs::{self, OpenclVersion, ContextProperties, ImageFormatParseError,
-    ImageFormatParseResult, ImageFormat, ImageDescriptor, BufferRegion, ContextPropertyValue};
+    ImageFormatParseResult, ImageFormat, ImageDescriptor, BufferRegion, ContextPropertyValue,
+    DevicePartition, QueueProperties};
 
-pub use self::types::enums::{EmptyInfoResultError, KernelArg, PlatformInfoResult, DeviceInfoResult,
-    ContextInfoResult, GlContextInfoResult, CommandQueueInfoResult, MemInfoResult, ImageInfoResult,
-    SamplerInfoResult, ProgramInfoResult, ProgramBuildInfoResult, KernelInfoResult,
-    KernelArgInfoResult, KernelWorkGroupInfoResult, EventInfoResult, ProfilingInfoResult};
+pub use self::types::enums::{EmptyInfoResultError, InfoResultError, KernelArg, KernelExecInfo,
+    PlatformInfoResult, DeviceInfoResult, ContextInfoResult, GlContextInfoResult, CommandQueueInfoResult, MemInfoResult,
+    ImageInfoResult, PipeInfoResult, SamplerInfoResult, ProgramInfoResult, ProgramBuildInfoResult,
+    KernelInfoResult, KernelArgInfoResult, KernelWorkGroupInfoResult, KernelSubGroupInfoResult,
+    EventInfoResult,
+    ProfilingInfoResult};
 
 pub use self::functions::{get_platform_ids, get_platform_info, get_device_ids, get_device_info,
     create_sub_devices, retain_device, release_device, create_context, create_context_from_type,
     retain_context, release_context, get_context_info,
-    create_command_queue, retain_command_queue, release_command_queue, get_command_queue_info,
+    create_command_queue, create_command_queue_with_properties, retain_command_queue,
+    release_command_queue, get_command_queue_info,
     create_buffer, create_sub_buffer, create_image, retain_mem_object, release_mem_object,
     get_supported_image_formats, get_mem_object_info, get_image_info,
     set_mem_object_destructor_callback, create_sampler, retain_sampler, release_sampler,
@@ -146,7 +148,7 @@ pub use self::functions::{get_platform_ids, get_platform_info, get_device_ids, g
     create_kernel, create_kernels_in_program, retain_kernel, release_kernel, set_kernel_arg,
     get_kernel_info, get_kernel_arg_info, get_kernel_work_group_info, wait_for_events,
     get_event_info, create_user_event, retain_event, release_event, set_user_event_status,
-    set_event_callback, get_event_profiling_info, flush, finish, enqueue_read_buffer,
+    set_event_callback, set_event_status_callback, get_event_profiling_info, flush, finish, enqueue_read_buffer,
     enqueue_read_buffer_rect, enqueue_write_buffer, enqueue_write_buffer_rect, enqueue_copy_buffer,
     enqueue_copy_buffer_rect,
     enqueue_read_image, enqueue_write_image, enqueue_fill_image, enqueue_copy_image,
@@ -154,9 +156,9 @@ pub use self::functions::{get_platform_ids, get_platform_info, get_device_ids, g
     enqueue_map_image, enqueue_unmap_mem_object, enqueue_migrate_mem_objects, enqueue_kernel,
     enqueue_task, enqueue_native_kernel, enqueue_marker_with_wait_list,
     enqueue_barrier_with_wait_list, get_extension_function_address_for_platform, wait_for_event,
-    event_status, default_platform_idx, program_build_err, verify_context, default_platform,
-    default_device_type, device_versions, event_is_complete, _dummy_event_callback,
-    _complete_user_event, get_context_platform};
+    event_status, default_platform_idx, program_build_err, program_build_logs, verify_context,
+    default_platform, default_device_type, device_versions, event_is_complete,
+    _dummy_event_callback, _complete_user_event, get_context_platform};
 
 #[cfg(not(feature="opencl_vendor_mesa"))]
 pub use self::functions::{
@@ -185,8 +187,15 @@ pub use self::vector::{
 #[cfg(feature = "ocl-core-vector")]
 pub use traits::OclVec;
 
+#[cfg(feature = "opencl_version_2_0")]
+pub use self::functions::{create_pipe, get_pipe_info, svm_alloc, svm_free,
+    set_kernel_arg_svm_pointer, set_kernel_exec_info, enqueue_svm_free, enqueue_svm_memcpy,
+    enqueue_svm_mem_fill, enqueue_svm_map, enqueue_svm_unmap};
+
 #[cfg(feature = "opencl_version_2_1")]
-pub use self::functions::{create_program_with_il};
+pub use self::functions::{create_program_with_il, enqueue_svm_migrate_mem,
+    set_default_device_command_queue, clone_kernel, get_kernel_sub_group_info,
+    get_device_and_host_timer, get_host_timer};
 
 
 
@@ -206,6 +215,14 @@ pub type CreateContextCallbackFn = extern "C" fn (*const ffi::c_char, *const ffi
     ffi::size_t, *mut ffi::c_void);
 pub type BuildProgramCallbackFn = extern "C" fn (*mut ffi::c_void, *mut ffi::c_void);
 pub type UserDataPtr = *mut ffi::c_void;
+/// A host function enqueued as a native kernel. Receives the host pointers
+/// of each memory object passed to `enqueue_native_kernel`, in order.
+pub type NativeKernelFn = Box<FnOnce(&[*mut ffi::c_void]) + Send>;
+/// A function called when a memory object is destroyed.
+pub type MemDestructorFn = Box<FnOnce() + Send>;
+/// A function called when an event reaches a command execution status or
+/// terminates abnormally (`Err`).
+pub type EventStatusFn = Box<FnOnce(error::Result<CommandExecutionStatus>) + Send>;
 
 //=============================================================================
 //================================== TRAITS ===================================
@@ -427,6 +444,10 @@ impl CommandQueueProperties {
         CommandQueueProperties::OUT_OF_ORDER_EXEC_MODE_ENABLE }
     #[inline] pub fn profiling(self) -> CommandQueueProperties { self |
         CommandQueueProperties::PROFILING_ENABLE }
+    #[inline] pub fn on_device(self) -> CommandQueueProperties { self |
+        CommandQueueProperties::ON_DEVICE }
+    #[inline] pub fn on_device_default(self) -> CommandQueueProperties { self |
+        CommandQueueProperties::ON_DEVICE_DEFAULT }
 }
 
 impl Default for CommandQueueProperties {
@@ -462,6 +483,22 @@ pub const DEVICE_AFFINITY_DOMAIN_L1_CACHE: DeviceAffinityDomain = DeviceAffinity
 pub const DEVICE_AFFINITY_DOMAIN_NEXT_PARTITIONABLE: DeviceAffinityDomain = DeviceAffinityDomain::NEXT_PARTITIONABLE;
 
 
+bitflags! {
+    /// cl_device_svm_capabilities - bitfield
+    pub struct DeviceSvmCapabilities: u64 {
+        const COARSE_GRAIN_BUFFER = 1 << 0;
+        const FINE_GRAIN_BUFFER = 1 << 1;
+        const FINE_GRAIN_SYSTEM = 1 << 2;
+        const ATOMICS = 1 << 3;
+    }
+}
+
+pub const DEVICE_SVM_COARSE_GRAIN_BUFFER: DeviceSvmCapabilities = DeviceSvmCapabilities::COARSE_GRAIN_BUFFER;
+pub const DEVICE_SVM_FINE_GRAIN_BUFFER: DeviceSvmCapabilities = DeviceSvmCapabilities::FINE_GRAIN_BUFFER;
+pub const DEVICE_SVM_FINE_GRAIN_SYSTEM: DeviceSvmCapabilities = DeviceSvmCapabilities::FINE_GRAIN_SYSTEM;
+pub const DEVICE_SVM_ATOMICS: DeviceSvmCapabilities = DeviceSvmCapabilities::ATOMICS;
+
+
 bitflags! {
     /// cl_mem_flags - bitfield
     pub struct MemFlags: u64 {
@@ -507,6 +544,37 @@ pub const MEM_HOST_READ_ONLY: MemFlags = MemFlags::HOST_READ_ONLY;
 pub const MEM_HOST_NO_ACCESS: MemFlags = MemFlags::HOST_NO_ACCESS;
 
 
+bitflags! {
+    /// cl_svm_mem_flags - bitfield
+    pub struct SvmMemFlags: u64 {
+        const READ_WRITE = 1 << 0;
+        const WRITE_ONLY = 1 << 1;
+        const READ_ONLY = 1 << 2;
+        const FINE_GRAIN_BUFFER = 1 << 10;
+        const ATOMICS = 1 << 11;
+    }
+}
+
+impl SvmMemFlags {
+    #[inline] pub fn new() -> SvmMemFlags { SvmMemFlags::empty() }
+    #[inline] pub fn read_write(self) -> SvmMemFlags { self | SvmMemFlags::READ_WRITE }
+    #[inline] pub fn write_only(self) -> SvmMemFlags { self | SvmMemFlags::WRITE_ONLY }
+    #[inline] pub fn read_only(self) -> SvmMemFlags { self | SvmMemFlags::READ_ONLY }
+    #[inline] pub fn fine_grain_buffer(self) -> SvmMemFlags { self | SvmMemFlags::FINE_GRAIN_BUFFER }
+    #[inline] pub fn atomics(self) -> SvmMemFlags { self | SvmMemFlags::ATOMICS }
+}
+
+impl Default for SvmMemFlags {
+    #[inline] fn default() -> SvmMemFlags { SvmMemFlags::READ_WRITE }
+}
+
+pub const SVM_MEM_READ_WRITE: SvmMemFlags = SvmMemFlags::READ_WRITE;
+pub const SVM_MEM_WRITE_ONLY: SvmMemFlags = SvmMemFlags::WRITE_ONLY;
+pub const SVM_MEM_READ_ONLY: SvmMemFlags = SvmMemFlags::READ_ONLY;
+pub const SVM_MEM_FINE_GRAIN_BUFFER: SvmMemFlags = SvmMemFlags::FINE_GRAIN_BUFFER;
+pub const SVM_MEM_ATOMICS: SvmMemFlags = SvmMemFlags::ATOMICS;
+
+
 bitflags! {
     /// cl_mem_migration_flags - bitfield
     pub struct MemMigrationFlags: u64 {
@@ -568,6 +636,7 @@ bitflags! {
         const CONST = 1 << 0;
         const RESTRICT = 1 << 1;
         const VOLATILE = 1 << 2;
+        const PIPE = 1 << 3;
     }
 }
 
@@ -575,6 +644,7 @@ pub const KERNEL_ARG_TYPE_NONE: KernelArgTypeQualifier = KernelArgTypeQualifier:
 pub const KERNEL_ARG_TYPE_CONST: KernelArgTypeQualifier = KernelArgTypeQualifier::CONST;
 pub const KERNEL_ARG_TYPE_RESTRICT: KernelArgTypeQualifier = KernelArgTypeQualifier::RESTRICT;
 pub const KERNEL_ARG_TYPE_VOLATILE: KernelArgTypeQualifier = KernelArgTypeQualifier::VOLATILE;
+pub const KERNEL_ARG_TYPE_PIPE: KernelArgTypeQualifier = KernelArgTypeQualifier::PIPE;
 
 //=============================================================================
 //=============================== ENUMERATORS =================================
@@ -886,6 +956,10 @@ enum_from_primitive! {
         PrintfBufferSize = ffi::CL_DEVICE_PRINTF_BUFFER_SIZE as isize,
         ImagePitchAlignment = ffi::CL_DEVICE_IMAGE_PITCH_ALIGNMENT as isize,
         ImageBaseAddressAlignment = ffi::CL_DEVICE_IMAGE_BASE_ADDRESS_ALIGNMENT as isize,
+        SvmCapabilities = ffi::CL_DEVICE_SVM_CAPABILITIES as isize,
+        MaxNumSubGroups = ffi::CL_DEVICE_MAX_NUM_SUB_GROUPS as isize,
+        SubGroupIndependentForwardProgress =
+            ffi::CL_DEVICE_SUB_GROUP_INDEPENDENT_FORWARD_PROGRESS as isize,
     }
 }
 
@@ -979,11 +1053,10 @@ enum_from_primitive! {
 
 
 enum_from_primitive! {
-    /// [INCOMPLETE] cl_device_partition_property
+    /// cl_device_partition_property
     ///
-    /// [FIXME]: This types variants should also contain data described in:
-    /// [https://www.khronos.org/registry/cl/sdk/1.2/docs/man/xhtml/clCreateSubDevices.html]
-    /// (https://www.khronos.org/registry/cl/sdk/1.2/docs/man/xhtml/clCreateSubDevices.html)
+    /// Use `DevicePartition` to specify a partition along with its associated
+    /// values when calling `::create_sub_devices`.
     ///
     #[repr(C)]
     #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
@@ -1005,6 +1078,36 @@ enum_from_primitive! {
         Device = ffi::CL_QUEUE_DEVICE as isize,
         ReferenceCount = ffi::CL_QUEUE_REFERENCE_COUNT as isize,
         Properties = ffi::CL_QUEUE_PROPERTIES as isize,
+        Size = ffi::CL_QUEUE_SIZE as isize,
+        DeviceDefault = ffi::CL_QUEUE_DEVICE_DEFAULT as isize,
+    }
+}
+
+
+enum_from_primitive! {
+    /// cl_queue_priority_khr
+    ///
+    /// Requires the `cl_khr_priority_hints` extension.
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum QueuePriority {
+        High = ffi::CL_QUEUE_PRIORITY_HIGH_KHR as isize,
+        Med = ffi::CL_QUEUE_PRIORITY_MED_KHR as isize,
+        Low = ffi::CL_QUEUE_PRIORITY_LOW_KHR as isize,
+    }
+}
+
+
+enum_from_primitive! {
+    /// cl_queue_throttle_khr
+    ///
+    /// Requires the `cl_khr_throttle_hints` extension.
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum QueueThrottle {
+        High = ffi::CL_QUEUE_THROTTLE_HIGH_KHR as isize,
+        Med = ffi::CL_QUEUE_THROTTLE_MED_KHR as isize,
+        Low = ffi::CL_QUEUE_THROTTLE_LOW_KHR as isize,
     }
 }
 
@@ -1046,6 +1149,7 @@ enum_from_primitive! {
         Image1d = ffi::CL_MEM_OBJECT_IMAGE1D as isize,
         Image1dArray = ffi::CL_MEM_OBJECT_IMAGE1D_ARRAY as isize,
         Image1dBuffer = ffi::CL_MEM_OBJECT_IMAGE1D_BUFFER as isize,
+        Pipe = ffi::CL_MEM_OBJECT_PIPE as isize,
     }
 }
 
@@ -1088,6 +1192,17 @@ enum_from_primitive! {
 }
 
 
+enum_from_primitive! {
+    /// cl_pipe_info
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum PipeInfo {
+        PacketSize = ffi::CL_PIPE_PACKET_SIZE as isize,
+        MaxPackets = ffi::CL_PIPE_MAX_PACKETS as isize,
+    }
+}
+
+
 enum_from_primitive! {
     /// cl_addressing_mode
     #[repr(C)]
@@ -1245,6 +1360,28 @@ enum_from_primitive! {
 }
 
 
+enum_from_primitive! {
+    /// cl_kernel_sub_group_info
+    ///
+    /// * `MaxSubGroupSizeForNdrange` and `SubGroupCountForNdrange` take the
+    ///   local work size as input.
+    /// * `LocalSizeForSubGroupCount` takes the desired number of sub-groups
+    ///   as input.
+    /// * `MaxNumSubGroups` and `CompileNumSubGroups` take no input.
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub enum KernelSubGroupInfo {
+        MaxSubGroupSizeForNdrange = ffi::CL_KERNEL_MAX_SUB_GROUP_SIZE_FOR_NDRANGE as isize,
+        SubGroupCountForNdrange = ffi::CL_KERNEL_SUB_GROUP_COUNT_FOR_NDRANGE as isize,
+        LocalSizeForSubGroupCount = ffi::CL_KERNEL_LOCAL_SIZE_FOR_SUB_GROUP_COUNT as isize,
+        MaxNumSubGroups = ffi::CL_KERNEL_MAX_NUM_SUB_GROUPS as isize,
+        CompileNumSubGroups = ffi::CL_KERNEL_COMPILE_NUM_SUB_GROUPS as isize,
+    }
+}
+
+
 enum_from_primitive! {
     /// cl_event_info
     #[repr(C)]
@@ -50,7 +50,7 @@ use ::{CommandExecutionStatus, OpenclVersion, PlatformInfo, DeviceInfo, DeviceIn
     ProgramInfoResult, KernelInfo, KernelInfoResult, Status, EventCallbackFn, OclPrm,
     EventInfo, EventInfoResult, DeviceType};
 use error::{Result as OclCoreResult, Error as OclCoreError};
-use functions::{self, ApiFunction, VersionKind};
+use functions::{self, ApiFunction, VersionKind, ApiWrapperError};
 
 //=============================================================================
 //================================ CONSTANTS ==================================
@@ -345,7 +345,7 @@ impl PlatformId {
         if !self.0.is_null() {
             functions::get_platform_info(self, PlatformInfo::Version)?.as_opencl_version()
         } else {
-            Err("PlatformId::version(): This platform_id is invalid.".into())
+            Err(ApiWrapperError::PlatformIdNull.into())
         }
     }
 }
@@ -407,7 +407,7 @@ impl DeviceId {
         if !self.0.is_null() {
             functions::get_device_info(self, DeviceInfo::Version)?.as_opencl_version()
         } else {
-            Err("DeviceId::device_versions(): This device_id is invalid.".into())
+            Err(ApiWrapperError::DeviceIdNull.into())
         }
     }
 }
@@ -1017,8 +1017,8 @@ impl Event {
     /// `user_data` must be guaranteed to still exist if and when `callback_receiver`
     /// is ever called.
     ///
-    /// TODO: Create a safer type wrapper for `callback_receiver` (using an
-    /// `Arc`?, etc.) within `ocl`.
+    /// See `::set_event_status_callback` (or `ocl::Event::on_complete`) for a
+    /// safe, closure-based alternative.
     ///
     //
     // [NOTE]: Making callback_receiver optional is pointless. There is no way
@@ -1032,8 +1032,7 @@ impl Event {
             ::set_event_callback(self, CommandExecutionStatus::Complete,
                 Some(callback_receiver), user_data_ptr as *mut _ as *mut c_void)
         } else {
-            Err("ocl_core::Event::set_callback: This event is null. Cannot set callback until \
-                internal event pointer is actually created by a `clCreate...` function.".into())
+            Err(ApiWrapperError::EventSetCallbackNull.into())
         }
     }
 
@@ -11,64 +11,112 @@
 
 use std::fmt;
 // use std::ptr;
-use failure::Fail;
+use std::error::Error as StdError;
 use num_traits::FromPrimitive;
 use util;
-use ffi::{cl_image_format, cl_context_properties, size_t, c_void};
+use ffi::{cl_image_format, cl_context_properties, cl_device_partition_property, size_t, c_void};
 
 use ::{OclPrm, CommandQueueProperties, PlatformId, PlatformInfo, DeviceId, DeviceInfo, ContextInfo,
     GlContextInfo, Context, CommandQueue, CommandQueueInfo, CommandType, CommandExecutionStatus,
     Mem, MemInfo, MemObjectType, MemFlags, Sampler, SamplerInfo, AddressingMode, FilterMode,
-    ProgramInfo, ProgramBuildInfo, Program, ProgramBuildStatus, ProgramBinaryType, KernelInfo,
-    KernelArgInfo, KernelWorkGroupInfo, KernelArgAddressQualifier, KernelArgAccessQualifier,
-    KernelArgTypeQualifier, ImageInfo, ImageFormat, EventInfo, ProfilingInfo, DeviceType,
+    PipeInfo, ProgramInfo, ProgramBuildInfo, Program, ProgramBuildStatus, ProgramBinaryType, KernelInfo,
+    KernelArgInfo, KernelWorkGroupInfo, KernelSubGroupInfo, KernelArgAddressQualifier,
+    KernelArgAccessQualifier, KernelArgTypeQualifier, ImageInfo, ImageFormat, EventInfo, ProfilingInfo, DeviceType,
     DeviceFpConfig, DeviceMemCacheType, DeviceLocalMemType, DeviceExecCapabilities,
-    DevicePartitionProperty, DeviceAffinityDomain, OpenclVersion, ContextProperties,
+    DevicePartitionProperty, DeviceAffinityDomain, DeviceSvmCapabilities, OpenclVersion, ContextProperties,
     ImageFormatParseResult, Status};
 
 use error::{Result as OclCoreResult, Error as OclCoreError};
 
 
-#[derive(Fail)]
+/// An error indicating that an info query returned an empty result.
 pub enum EmptyInfoResultError {
-    #[fail(display = "Platform info unavailable")]
     Platform,
-    #[fail(display = "Device info unavailable")]
     Device,
-    #[fail(display = "Context info unavailable")]
     Context,
-    #[fail(display = "OpenGL info unavailable")]
     GlContext,
-    #[fail(display = "Command queue info unavailable")]
     CommandQueue,
-    #[fail(display = "Mem object info unavailable")]
     Mem,
-    #[fail(display = "Image info unavailable")]
     Image,
-    #[fail(display = "Sampler info unavailable")]
+    Pipe,
     Sampler,
-    #[fail(display = "Program info unavailable")]
     Program,
-    #[fail(display = "Program build info unavailable")]
     ProgramBuild,
-    #[fail(display = "Kernel info unavailable")]
     Kernel,
-    #[fail(display = "Kernel argument info unavailable")]
     KernelArg,
-    #[fail(display = "Kernel work-group info unavailable")]
     KernelWorkGroup,
-    #[fail(display = "Event info unavailable")]
+    KernelSubGroup,
     Event,
-    #[fail(display = "Event profiling info unavailable")]
     Profiling,
 }
 
+impl fmt::Display for EmptyInfoResultError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match *self {
+            EmptyInfoResultError::Platform => "Platform",
+            EmptyInfoResultError::Device => "Device",
+            EmptyInfoResultError::Context => "Context",
+            EmptyInfoResultError::GlContext => "OpenGL",
+            EmptyInfoResultError::CommandQueue => "Command queue",
+            EmptyInfoResultError::Mem => "Mem object",
+            EmptyInfoResultError::Image => "Image",
+            EmptyInfoResultError::Pipe => "Pipe",
+            EmptyInfoResultError::Sampler => "Sampler",
+            EmptyInfoResultError::Program => "Program",
+            EmptyInfoResultError::ProgramBuild => "Program build",
+            EmptyInfoResultError::Kernel => "Kernel",
+            EmptyInfoResultError::KernelArg => "Kernel argument",
+            EmptyInfoResultError::KernelWorkGroup => "Kernel work-group",
+            EmptyInfoResultError::KernelSubGroup => "Kernel sub-group",
+            EmptyInfoResultError::Event => "Event",
+            EmptyInfoResultError::Profiling => "Event profiling",
+        };
+        write!(f, "{} info unavailable", kind)
+    }
+}
+
 impl fmt::Debug for EmptyInfoResultError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self)
     }
 }
 
+impl StdError for EmptyInfoResultError {}
+
+
+/// An error converting the raw result of an info query, or accessing the
+/// contents of an info result.
+#[derive(Debug)]
+pub enum InfoResultError {
+    /// A raw value did not correspond to any variant of the named enum.
+    InvalidEnumValue { enum_name: &'static str, value: i64 },
+    /// A method was called on an info result of the wrong variant.
+    WrongVariant { method: &'static str, expected: &'static str },
+    /// The number of work-item dimensions reported by a device is
+    /// unsupported.
+    MaxWorkItemDims(u32),
+    /// A version string could not be parsed.
+    VersionParse(String),
+}
+
+impl fmt::Display for InfoResultError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InfoResultError::InvalidEnumValue { enum_name, value } => write!(f, "Error \
+                converting '{}' to {}.", value, enum_name),
+            InfoResultError::WrongVariant { method, expected } => write!(f, "{}: Invalid info \
+                result variant. This function can only be called on a '{}' variant.", method,
+                expected),
+            InfoResultError::MaxWorkItemDims(dims) => write!(f, "Error determining number of \
+                dimensions for MaxWorkItemSizes (dimensions: {}).", dims),
+            InfoResultError::VersionParse(ref ver) => write!(f, "Error parsing version from the \
+                string: '{}'.", ver),
+        }
+    }
+}
+
+impl StdError for InfoResultError {}
+
 
 
 /// [UNSAFE] Kernel argument option type.
@@ -115,6 +163,22 @@ pub enum KernelArg<'a, T: 'a + OclPrm> {
 }
 
 
+/// Kernel execution information used when calling `::set_kernel_exec_info`.
+///
+/// [SDK docs]: https://www.khronos.org/registry/OpenCL/sdk/2.0/docs/man/xhtml/clSetKernelExecInfo.html
+#[derive(Debug)]
+pub enum KernelExecInfo<'a> {
+    /// SVM pointers which the kernel may access but which are not passed
+    /// directly as arguments (i.e. pointers stored within other SVM
+    /// allocations).
+    SvmPtrs(&'a [*const c_void]),
+    /// Whether or not the kernel may access system allocations (memory
+    /// allocated with `malloc`, etc.) directly. Requires a device supporting
+    /// fine-grained system SVM.
+    SvmFineGrainSystem(bool),
+}
+
+
 
 /// Platform info result.
 ///
@@ -150,9 +214,10 @@ impl PlatformInfoResult {
         if let PlatformInfoResult::Version(ref ver) = *self {
             OpenclVersion::from_info_str(ver)
         } else {
-            Err(format!("PlatformInfoResult::as_opencl_version(): Invalid platform info \
-                result variant: ({:?}). This function can only be called on a \
-                'PlatformInfoResult::Version' variant.", self).into())
+            Err(InfoResultError::WrongVariant {
+                method: "PlatformInfoResult::as_opencl_version",
+                expected: "PlatformInfoResult::Version",
+            }.into())
         }
     }
 }
@@ -268,6 +333,9 @@ pub enum DeviceInfoResult {
     PrintfBufferSize(usize),         // usize
     ImagePitchAlignment(u32),      // cl_uint
     ImageBaseAddressAlignment(u32),// cl_uint
+    SvmCapabilities(DeviceSvmCapabilities), // cl_device_svm_capabilities  FLAGS u64
+    MaxNumSubGroups(u32),          // cl_uint
+    SubGroupIndependentForwardProgress(bool), // cl_bool
 }
 
 impl DeviceInfoResult {
@@ -298,8 +366,7 @@ impl DeviceInfoResult {
                         v.extend_from_slice(&r);
                         Ok(DeviceInfoResult::MaxWorkItemSizes(v))
                     },
-                    _ => Err(OclCoreError::from("Error \
-                        determining number of dimensions for MaxWorkItemSizes.")),
+                    _ => Err(InfoResultError::MaxWorkItemDims(max_wi_dims).into()),
                 }
             },
             _ => panic!("DeviceInfoResult::from_bytes_max_work_item_sizes: Called with \
@@ -432,8 +499,8 @@ impl DeviceInfoResult {
                 let r = unsafe { util::bytes_into::<u32>(result)? };
                 match DeviceMemCacheType::from_u32(r) {
                     Some(e) => DeviceInfoResult::GlobalMemCacheType(e),
-                    None => return Err(OclCoreError::from(format!("Error converting '{:X}' to \
-                            DeviceMemCacheType.", r))),
+                    None => return Err(OclCoreError::from(InfoResultError::InvalidEnumValue {
+                        enum_name: "DeviceMemCacheType", value: r as i64 })),
                 }
             },
             DeviceInfo::GlobalMemCachelineSize => {
@@ -460,8 +527,8 @@ impl DeviceInfoResult {
                 let r = unsafe { util::bytes_into::<u32>(result)? };
                 match DeviceLocalMemType::from_u32(r) {
                     Some(e) => DeviceInfoResult::LocalMemType(e),
-                    None => return Err(OclCoreError::from(format!("Error converting '{:X}' to \
-                            DeviceLocalMemType.", r))),
+                    None => return Err(OclCoreError::from(InfoResultError::InvalidEnumValue {
+                        enum_name: "DeviceLocalMemType", value: r as i64 })),
                 }
             },
             DeviceInfo::LocalMemSize => {
@@ -617,16 +684,12 @@ impl DeviceInfoResult {
                 DeviceInfoResult::PartitionMaxSubDevices(r)
             },
             DeviceInfo::PartitionProperties => {
-                // [FIXME]: INCOMPLETE:
-                //
-                // let r = unsafe { util::bytes_into::<u32>(result)? };
-                // match DevicePartitionProperty::from_u32(r) {
-                //     Some(e) => DeviceInfoResult::PartitionProperties(e),
-                //     None => DeviceInfoResult::Error(Box::new(
-                //         OclCoreError::from(format!("Error converting '{:X}' to \
-                //             DevicePartitionProperty.", r)))),
-                // }
-                DeviceInfoResult::PartitionProperties(Vec::with_capacity(0))
+                // A single zero value indicates that partitioning is
+                // unsupported:
+                let r = unsafe { util::bytes_into_vec::<cl_device_partition_property>(result)? };
+                let props = r.into_iter().filter(|&p| p != 0)
+                    .filter_map(DevicePartitionProperty::from_isize).collect();
+                DeviceInfoResult::PartitionProperties(props)
             },
             DeviceInfo::PartitionAffinityDomain => {
                 let r = unsafe { util::bytes_into::<DeviceAffinityDomain>(result)? };
@@ -664,6 +727,18 @@ impl DeviceInfoResult {
                 let r = unsafe { util::bytes_into::<u32>(result)? };
                 DeviceInfoResult::ImageBaseAddressAlignment(r)
             },
+            DeviceInfo::SvmCapabilities => {
+                let r = unsafe { util::bytes_into::<DeviceSvmCapabilities>(result)? };
+                DeviceInfoResult::SvmCapabilities(r)
+            },
+            DeviceInfo::MaxNumSubGroups => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                DeviceInfoResult::MaxNumSubGroups(r)
+            },
+            DeviceInfo::SubGroupIndependentForwardProgress => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                DeviceInfoResult::SubGroupIndependentForwardProgress(r != 0)
+            },
             // _ => DeviceInfoResult::TemporaryPlaceholderVariant(result),
         };
 
@@ -675,9 +750,10 @@ impl DeviceInfoResult {
         if let DeviceInfoResult::Version(ver) = *self {
             Ok(ver)
         } else {
-            Err(format!("DeviceInfoResult::as_opencl_version(): Invalid device info \
-                result variant: ({:?}). This function can only be called on a \
-                'DeviceInfoResult::Version' variant.", self).into())
+            Err(InfoResultError::WrongVariant {
+                method: "DeviceInfoResult::as_opencl_version",
+                expected: "DeviceInfoResult::Version",
+            }.into())
         }
     }
 }
@@ -767,6 +843,9 @@ impl fmt::Display for DeviceInfoResult {
             DeviceInfoResult::PrintfBufferSize(ref s) => write!(f, "{}", s),
             DeviceInfoResult::ImagePitchAlignment(ref s) => write!(f, "{}", s),
             DeviceInfoResult::ImageBaseAddressAlignment(ref s) => write!(f, "{}", s),
+            DeviceInfoResult::SvmCapabilities(ref s) => write!(f, "{:?}", s),
+            DeviceInfoResult::MaxNumSubGroups(ref s) => write!(f, "{}", s),
+            DeviceInfoResult::SubGroupIndependentForwardProgress(ref s) => write!(f, "{}", s),
         }
     }
 }
@@ -872,7 +951,10 @@ impl GlContextInfoResult {
     pub fn device(self) -> OclCoreResult<DeviceId> {
         match self {
             GlContextInfoResult::CurrentDevice(d) => Ok(d),
-            _ => Err("GlContextInfoResult::device: Not a 'GlContextInfoResult::Device(...)'.".into()),
+            _ => Err(InfoResultError::WrongVariant {
+                method: "GlContextInfoResult::device",
+                expected: "GlContextInfoResult::CurrentDevice",
+            }.into()),
         }
     }
 }
@@ -906,6 +988,8 @@ pub enum CommandQueueInfoResult {
     Device(DeviceId),
     ReferenceCount(u32),
     Properties(CommandQueueProperties),
+    Size(u32),
+    DeviceDefault(Option<CommandQueue>),
 }
 
 impl CommandQueueInfoResult {
@@ -933,6 +1017,19 @@ impl CommandQueueInfoResult {
                 let r = unsafe { util::bytes_into::<CommandQueueProperties>(result)? };
                 CommandQueueInfoResult::Properties(r)
             }
+            CommandQueueInfo::Size => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                CommandQueueInfoResult::Size(r)
+            }
+            CommandQueueInfo::DeviceDefault => {
+                let ptr = unsafe { util::bytes_into::<*mut c_void>(result)? };
+                if ptr.is_null() {
+                    CommandQueueInfoResult::DeviceDefault(None)
+                } else {
+                    CommandQueueInfoResult::DeviceDefault(Some(unsafe {
+                        CommandQueue::from_raw_copied_ptr(ptr) }))
+                }
+            }
         };
         Ok(ir)
     }
@@ -951,6 +1048,8 @@ impl fmt::Display for CommandQueueInfoResult {
             CommandQueueInfoResult::Device(ref s) => write!(f, "{:?}", s),
             CommandQueueInfoResult::ReferenceCount(ref s) => write!(f, "{}", s),
             CommandQueueInfoResult::Properties(ref s) => write!(f, "{:?}", s),
+            CommandQueueInfoResult::Size(ref s) => write!(f, "{}", s),
+            CommandQueueInfoResult::DeviceDefault(ref s) => write!(f, "{:?}", s),
             // _ => panic!("CommandQueueInfoResult: Converting this variant to string not yet implemented."),
         }
     }
@@ -1007,8 +1106,8 @@ impl MemInfoResult {
                 let r = unsafe { util::bytes_into::<u32>(result)? };
                 match MemObjectType::from_u32(r) {
                     Some(am) => MemInfoResult::Type(am),
-                    None => return Err(OclCoreError::from(format!("Error converting '{}' to \
-                            MemObjectType.", r))),
+                    None => return Err(OclCoreError::from(InfoResultError::InvalidEnumValue {
+                        enum_name: "MemObjectType", value: r as i64 })),
                 }
             },
             MemInfo::Flags => {
@@ -1213,6 +1312,53 @@ impl From<ImageInfoResult> for String {
 }
 
 
+/// A pipe info result.
+pub enum PipeInfoResult {
+    PacketSize(u32),
+    MaxPackets(u32),
+}
+
+impl PipeInfoResult {
+    pub fn from_bytes(request: PipeInfo, result: Vec<u8>) -> OclCoreResult<PipeInfoResult> {
+        if result.is_empty() {
+            return Err(OclCoreError::from(EmptyInfoResultError::Pipe));
+        }
+        let ir = match request {
+            PipeInfo::PacketSize => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                PipeInfoResult::PacketSize(r)
+            },
+            PipeInfo::MaxPackets => {
+                let r = unsafe { util::bytes_into::<u32>(result)? };
+                PipeInfoResult::MaxPackets(r)
+            },
+        };
+        Ok(ir)
+    }
+}
+
+impl fmt::Debug for PipeInfoResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", &self.to_string())
+    }
+}
+
+impl fmt::Display for PipeInfoResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            PipeInfoResult::PacketSize(s) => write!(f, "{}", s),
+            PipeInfoResult::MaxPackets(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<PipeInfoResult> for String {
+    fn from(ir: PipeInfoResult) -> String {
+        ir.to_string()
+    }
+}
+
+
 /// A sampler info result.
 pub enum SamplerInfoResult {
     ReferenceCount(u32),
@@ -1245,16 +1391,16 @@ impl SamplerInfoResult {
                 let r = unsafe { util::bytes_into::<u32>(result)? };
                 match AddressingMode::from_u32(r) {
                     Some(am) => SamplerInfoResult::AddressingMode(am),
-                    None => return Err(OclCoreError::from(format!("Error converting '{}' to \
-                        AddressingMode.", r))),
+                    None => return Err(OclCoreError::from(InfoResultError::InvalidEnumValue {
+                        enum_name: "AddressingMode", value: r as i64 })),
                 }
             },
             SamplerInfo::FilterMode => {
                 let r = unsafe { util::bytes_into::<u32>(result)? };
                 match FilterMode::from_u32(r) {
                     Some(fm) => SamplerInfoResult::FilterMode(fm),
-                    None => return Err(OclCoreError::from(format!("Error converting '{}' to \
-                        FilterMode.", r))),
+                    None => return Err(OclCoreError::from(InfoResultError::InvalidEnumValue {
+                        enum_name: "FilterMode", value: r as i64 })),
                 }
             },
         };
@@ -1407,8 +1553,8 @@ impl ProgramBuildInfoResult {
                 let r = unsafe { util::bytes_into::<i32>(result)? };
                 match ProgramBuildStatus::from_i32(r) {
                     Some(b) => ProgramBuildInfoResult::BuildStatus(b),
-                    None => return Err(OclCoreError::from(format!("Error converting '{}' to \
-                        ProgramBuildStatus.", r))),
+                    None => return Err(OclCoreError::from(InfoResultError::InvalidEnumValue {
+                        enum_name: "ProgramBuildStatus", value: r as i64 })),
                 }
             },
             ProgramBuildInfo::BuildOptions => {
@@ -1552,16 +1698,16 @@ impl KernelArgInfoResult {
                 let r = unsafe { util::bytes_into::<u32>(result)? };
                 match KernelArgAddressQualifier::from_u32(r) {
                     Some(kaaq) => KernelArgInfoResult::AddressQualifier(kaaq),
-                    None => return Err(OclCoreError::from(format!("Error converting '{}' to \
-                            KernelArgAddressQualifier.", r))),
+                    None => return Err(OclCoreError::from(InfoResultError::InvalidEnumValue {
+                        enum_name: "KernelArgAddressQualifier", value: r as i64 })),
                 }
             },
             KernelArgInfo::AccessQualifier => {
                 let r = unsafe { util::bytes_into::<u32>(result)? };
                 match KernelArgAccessQualifier::from_u32(r) {
                     Some(kaaq) => KernelArgInfoResult::AccessQualifier(kaaq),
-                    None => return Err(OclCoreError::from(format!("Error converting '{}' to \
-                            KernelArgAccessQualifier.", r))),
+                    None => return Err(OclCoreError::from(InfoResultError::InvalidEnumValue {
+                        enum_name: "KernelArgAccessQualifier", value: r as i64 })),
                 }
             },
             KernelArgInfo::TypeName => {
@@ -1610,8 +1756,6 @@ impl From<KernelArgInfoResult> for String {
 }
 
 
-impl Fail for KernelArgInfoResult {}
-
 
 /// A kernel work group info result.
 pub enum KernelWorkGroupInfoResult {
@@ -1717,6 +1861,73 @@ impl From<KernelWorkGroupInfoResult> for String {
 }
 
 
+/// A kernel sub-group info result.
+pub enum KernelSubGroupInfoResult {
+    MaxSubGroupSizeForNdrange(usize),
+    SubGroupCountForNdrange(usize),
+    LocalSizeForSubGroupCount([usize; 3]),
+    MaxNumSubGroups(usize),
+    CompileNumSubGroups(usize),
+}
+
+impl KernelSubGroupInfoResult {
+    pub fn from_bytes(request: KernelSubGroupInfo, result: Vec<u8>)
+            -> OclCoreResult<KernelSubGroupInfoResult> {
+        if result.is_empty() {
+            return Err(OclCoreError::from(
+                EmptyInfoResultError::KernelSubGroup));
+        }
+        let ir = match request {
+            KernelSubGroupInfo::MaxSubGroupSizeForNdrange => {
+                let r = unsafe { util::bytes_into::<usize>(result)? };
+                KernelSubGroupInfoResult::MaxSubGroupSizeForNdrange(r)
+            },
+            KernelSubGroupInfo::SubGroupCountForNdrange => {
+                let r = unsafe { util::bytes_into::<usize>(result)? };
+                KernelSubGroupInfoResult::SubGroupCountForNdrange(r)
+            },
+            KernelSubGroupInfo::LocalSizeForSubGroupCount => {
+                let r = unsafe { util::bytes_into::<[usize; 3]>(result)? };
+                KernelSubGroupInfoResult::LocalSizeForSubGroupCount(r)
+            },
+            KernelSubGroupInfo::MaxNumSubGroups => {
+                let r = unsafe { util::bytes_into::<usize>(result)? };
+                KernelSubGroupInfoResult::MaxNumSubGroups(r)
+            },
+            KernelSubGroupInfo::CompileNumSubGroups => {
+                let r = unsafe { util::bytes_into::<usize>(result)? };
+                KernelSubGroupInfoResult::CompileNumSubGroups(r)
+            },
+        };
+        Ok(ir)
+    }
+}
+
+impl fmt::Debug for KernelSubGroupInfoResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", &self.to_string())
+    }
+}
+
+impl fmt::Display for KernelSubGroupInfoResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            KernelSubGroupInfoResult::MaxSubGroupSizeForNdrange(s) => write!(f, "{}", s),
+            KernelSubGroupInfoResult::SubGroupCountForNdrange(s) => write!(f, "{}", s),
+            KernelSubGroupInfoResult::LocalSizeForSubGroupCount(s) => write!(f, "{:?}", s),
+            KernelSubGroupInfoResult::MaxNumSubGroups(s) => write!(f, "{}", s),
+            KernelSubGroupInfoResult::CompileNumSubGroups(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl From<KernelSubGroupInfoResult> for String {
+    fn from(ir: KernelSubGroupInfoResult) -> String {
+        ir.to_string()
+    }
+}
+
+
 /// An event info result.
 pub enum EventInfoResult {
     CommandQueue(CommandQueue),
@@ -1741,8 +1952,8 @@ impl EventInfoResult {
                 let code = unsafe { util::bytes_into::<u32>(result)? };
                 match CommandType::from_u32(code) {
                     Some(ces) => EventInfoResult::CommandType(ces),
-                    None => return Err(OclCoreError::from(format!(
-                        "Error converting '{}' to CommandType.", code))),
+                    None => return Err(OclCoreError::from(InfoResultError::InvalidEnumValue {
+                        enum_name: "CommandType", value: code as i64 })),
                 }
             },
             EventInfo::ReferenceCount => { EventInfoResult::ReferenceCount(
@@ -1752,8 +1963,8 @@ impl EventInfoResult {
                 let code = unsafe { util::bytes_into::<i32>(result)? };
                 match CommandExecutionStatus::from_i32(code) {
                     Some(ces) => EventInfoResult::CommandExecutionStatus(ces),
-                    None => return Err(OclCoreError::from(format!("Error converting '{}' to \
-                            CommandExecutionStatus.", code))),
+                    None => return Err(OclCoreError::from(InfoResultError::InvalidEnumValue {
+                        enum_name: "CommandExecutionStatus", value: code as i64 })),
                 }
             },
             EventInfo::Context => {
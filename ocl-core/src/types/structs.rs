@@ -6,9 +6,11 @@ use std::marker::PhantomData;
 use std::collections::HashMap;
 use num_traits::FromPrimitive;
 use error::{Error as OclCoreError, Result as OclCoreResult};
-use ffi::{self, cl_mem, cl_buffer_region, cl_context_properties, cl_platform_id, c_void};
+use ffi::{self, cl_mem, cl_buffer_region, cl_context_properties, cl_platform_id,
+    cl_device_partition_property, cl_queue_properties, c_void};
 use ::{Mem, MemObjectType, ImageChannelOrder, ImageChannelDataType, ContextProperty,
-    PlatformId, OclPrm};
+    PlatformId, OclPrm, DevicePartitionProperty, DeviceAffinityDomain, CommandQueueProperties,
+    QueuePriority, QueueThrottle, InfoResultError};
 
 
 // Until everything can be implemented:
@@ -76,8 +78,7 @@ impl OpenclVersion {
 
         match version {
             Some(cl_ver) => Ok(cl_ver),
-            None => Err(format!("DeviceInfoResult::as_opencl_version(): \
-                Error parsing version from the string: '{}'.", ver).into()),
+            None => Err(InfoResultError::VersionParse(ver.to_owned()).into()),
         }
     }
 }
@@ -449,8 +450,8 @@ impl ContextProperties {
             let val_raw = *raw_context_properties.get_unchecked(idz + 1);
 
             let key = ContextProperty::from_isize(key_raw).ok_or(OclCoreError::from(
-                format!("ContextProperties::from_raw: Unable to convert '{}' using \
-                    'ContextProperty::from_isize'.", key_raw)))?;
+                InfoResultError::InvalidEnumValue { enum_name: "ContextProperty",
+                    value: key_raw as i64 }))?;
 
             match key {
                     ContextProperty::Platform => {
@@ -539,6 +540,182 @@ impl From<ContextProperties> for Vec<isize> {
 
 
 
+/// Specifies how a device is to be partitioned into sub-devices by
+/// `::create_sub_devices`.
+///
+/// ### Info (from [SDK](https://www.khronos.org/registry/cl/sdk/1.2/docs/man/xhtml/clCreateSubDevices.html))
+///
+/// * `Equally`: Split the device into as many sub-devices as can be created
+///   with the specified number of compute units each.
+/// * `ByCounts`: Create one sub-device for each element of the list, each
+///   containing the specified number of compute units.
+/// * `ByAffinityDomain`: Split the device into sub-devices sharing the
+///   specified level of the cache hierarchy or NUMA node.
+///
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DevicePartition {
+    Equally(u32),
+    ByCounts(Vec<u32>),
+    ByAffinityDomain(DeviceAffinityDomain),
+}
+
+impl DevicePartition {
+    /// Returns the kind of partitioning specified.
+    pub fn property(&self) -> DevicePartitionProperty {
+        match *self {
+            DevicePartition::Equally(_) => DevicePartitionProperty::Equally,
+            DevicePartition::ByCounts(_) => DevicePartitionProperty::ByCounts,
+            DevicePartition::ByAffinityDomain(_) => DevicePartitionProperty::ByAffinityDomain,
+        }
+    }
+
+    /// Converts this partition into the zero-terminated list of
+    /// `cl_device_partition_property` words expected by `clCreateSubDevices`.
+    pub fn to_raw(&self) -> Vec<cl_device_partition_property> {
+        let mut props_raw = Vec::with_capacity(4);
+        props_raw.push(self.property() as cl_device_partition_property);
+
+        match *self {
+            DevicePartition::Equally(compute_units) => {
+                props_raw.push(compute_units as cl_device_partition_property);
+            },
+            DevicePartition::ByCounts(ref counts) => {
+                props_raw.extend(counts.iter().map(|&c| c as cl_device_partition_property));
+                props_raw.push(DevicePartitionProperty::ByCountsListEnd as
+                    cl_device_partition_property);
+            },
+            DevicePartition::ByAffinityDomain(domain) => {
+                props_raw.push(domain.bits() as cl_device_partition_property);
+            },
+        }
+
+        // Add a terminating 0:
+        props_raw.push(0);
+        props_raw
+    }
+}
+
+
+
+/// A list of properties used to create a command queue with
+/// `::create_command_queue_with_properties`.
+///
+/// * `properties`: A bitfield of `CommandQueueProperties`. `ON_DEVICE`
+///   requires `OUT_OF_ORDER_EXEC_MODE_ENABLE`. `ON_DEVICE_DEFAULT` requires
+///   `ON_DEVICE`.
+/// * `size`: The size of an on-device queue in bytes. Only valid for
+///   on-device queues.
+/// * `priority`: A priority hint (`cl_khr_priority_hints`).
+/// * `throttle`: A throttle hint (`cl_khr_throttle_hints`).
+///
+/// Properties which are not set are omitted from the list passed to OpenCL
+/// and take on their implementation defined defaults.
+///
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QueueProperties {
+    properties: Option<CommandQueueProperties>,
+    size: Option<u32>,
+    priority: Option<QueuePriority>,
+    throttle: Option<QueueThrottle>,
+}
+
+impl QueueProperties {
+    /// Returns an empty new list of queue properties.
+    pub fn new() -> QueueProperties {
+        QueueProperties::default()
+    }
+
+    /// Specifies the command queue properties bitfield (builder-style).
+    pub fn properties(mut self, properties: CommandQueueProperties) -> QueueProperties {
+        self.properties = Some(properties);
+        self
+    }
+
+    /// Specifies the size of an on-device queue in bytes (builder-style).
+    pub fn size(mut self, size: u32) -> QueueProperties {
+        self.size = Some(size);
+        self
+    }
+
+    /// Specifies a priority hint (builder-style).
+    pub fn priority(mut self, priority: QueuePriority) -> QueueProperties {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Specifies a throttle hint (builder-style).
+    pub fn throttle(mut self, throttle: QueueThrottle) -> QueueProperties {
+        self.throttle = Some(throttle);
+        self
+    }
+
+    /// Returns the command queue properties bitfield, if set.
+    pub fn get_properties(&self) -> Option<CommandQueueProperties> {
+        self.properties
+    }
+
+    /// Returns the on-device queue size, if set.
+    pub fn get_size(&self) -> Option<u32> {
+        self.size
+    }
+
+    /// Returns the priority hint, if set.
+    pub fn get_priority(&self) -> Option<QueuePriority> {
+        self.priority
+    }
+
+    /// Returns the throttle hint, if set.
+    pub fn get_throttle(&self) -> Option<QueueThrottle> {
+        self.throttle
+    }
+
+    /// Returns true if only properties supported by `clCreateCommandQueue`
+    /// (OpenCL 1.x) have been specified.
+    pub fn is_legacy_compatible(&self) -> bool {
+        let legacy_props = CommandQueueProperties::OUT_OF_ORDER_EXEC_MODE_ENABLE |
+            CommandQueueProperties::PROFILING_ENABLE;
+
+        self.properties.map(|p| legacy_props.contains(p)).unwrap_or(true) &&
+            self.size.is_none() && self.priority.is_none() && self.throttle.is_none()
+    }
+
+    /// Converts these properties into the zero-terminated list of
+    /// `cl_queue_properties` (property, value) pairs expected by
+    /// `clCreateCommandQueueWithProperties`.
+    pub fn to_raw(&self) -> Vec<cl_queue_properties> {
+        let mut props_raw = Vec::with_capacity(9);
+
+        if let Some(props) = self.properties {
+            props_raw.push(ffi::CL_QUEUE_PROPERTIES as cl_queue_properties);
+            props_raw.push(props.bits() as cl_queue_properties);
+        }
+        if let Some(size) = self.size {
+            props_raw.push(ffi::CL_QUEUE_SIZE as cl_queue_properties);
+            props_raw.push(size as cl_queue_properties);
+        }
+        if let Some(priority) = self.priority {
+            props_raw.push(ffi::CL_QUEUE_PRIORITY_KHR as cl_queue_properties);
+            props_raw.push(priority as cl_queue_properties);
+        }
+        if let Some(throttle) = self.throttle {
+            props_raw.push(ffi::CL_QUEUE_THROTTLE_KHR as cl_queue_properties);
+            props_raw.push(throttle as cl_queue_properties);
+        }
+
+        // Add a terminating 0:
+        props_raw.push(0);
+        props_raw
+    }
+}
+
+impl From<CommandQueueProperties> for QueueProperties {
+    fn from(properties: CommandQueueProperties) -> QueueProperties {
+        QueueProperties::new().properties(properties)
+    }
+}
+
+
+
 /// Defines a buffer region for creating a sub-buffer.
 ///
 /// ### Info (from [SDK](https://www.khronos.org/registry/cl/sdk/1.2/docs/man/xhtml/clCreateSubBuffer.html))
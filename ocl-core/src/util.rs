@@ -4,6 +4,8 @@
 //!
 //! Printing functions may be moved/renamed/removed at any time.
 use std::ops::Range;
+use std::fmt;
+use std::error::Error as StdError;
 use std::mem;
 use std::ptr;
 use std::iter;
@@ -69,22 +71,45 @@ pub mod colors {
 //=============================================================================
 
 /// An error caused by a utility function.
-#[derive(Debug, Fail)]
+#[derive(Debug)]
 pub enum UtilError {
-    #[fail(display = "The size of the source byte slice ({} bytes) does not match \
-        the size of the destination type ({} bytes).", src, dst)]
     BytesTo { src: usize, dst: usize, },
-    #[fail(display = "The size of the source byte vector ({} bytes) does not match \
-        the size of the destination type ({} bytes).", src, dst)]
     BytesInto { src: usize, dst: usize, },
-    #[fail(display = "The size of the source byte vector ({} bytes) is not evenly \
-        divisible by the size of the destination type ({} bytes).", src, dst)]
     BytesIntoVec { src: usize, dst: usize, },
-    #[fail(display = "The size of the source byte slice ({} bytes) is not evenly \
-        divisible by the size of the destination type ({} bytes).", src, dst)]
     BytesToVec { src: usize, dst: usize, },
-    #[fail(display = "Unable to convert bytes into string: {}", _0)]
-    BytesIntoString(#[cause] FromUtf8Error),
+    BytesIntoString(FromUtf8Error),
+}
+
+impl fmt::Display for UtilError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            UtilError::BytesTo { src, dst } => write!(f, "The size of the source byte slice \
+                ({} bytes) does not match the size of the destination type ({} bytes).",
+                src, dst),
+            UtilError::BytesInto { src, dst } => write!(f, "The size of the source byte vector \
+                ({} bytes) does not match the size of the destination type ({} bytes).",
+                src, dst),
+            UtilError::BytesIntoVec { src, dst } => write!(f, "The size of the source byte \
+                vector ({} bytes) is not evenly divisible by the size of the destination type \
+                ({} bytes).", src, dst),
+            UtilError::BytesToVec { src, dst } => write!(f, "The size of the source byte slice \
+                ({} bytes) is not evenly divisible by the size of the destination type \
+                ({} bytes).", src, dst),
+            UtilError::BytesIntoString(ref err) => write!(f, "Unable to convert bytes into \
+                string: {}", err),
+        }
+    }
+}
+
+impl StdError for UtilError {
+    fn source(&self) -> Option<&(StdError + 'static)> {
+        match *self {
+            // Displayed along with this error, so the chain continues with
+            // its source.
+            UtilError::BytesIntoString(ref err) => err.source(),
+            _ => None,
+        }
+    }
 }
 
 /// Copies a byte slice to a new `u32`.
@@ -242,15 +267,26 @@ pub fn padded_len(len: usize, incr: usize) -> usize {
 
 
 /// An error caused by `util::vec_remove_rebuild`.
-#[derive(Fail, Debug)]
+#[derive(Debug)]
 pub enum VecRemoveRebuildError {
-    #[fail(display = "Remove list is longer than source vector.")]
     TooLong,
-    #[fail(display = "'remove_list' contains at least one out of range index: [{}] \
-        ('orig_vec' length: {}).", idx, orig_len)]
     OutOfRange { idx: usize, orig_len: usize },
 }
 
+impl fmt::Display for VecRemoveRebuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            VecRemoveRebuildError::TooLong => write!(f, "Remove list is longer than source \
+                vector."),
+            VecRemoveRebuildError::OutOfRange { idx, orig_len } => write!(f, "'remove_list' \
+                contains at least one out of range index: [{}] ('orig_vec' length: {}).",
+                idx, orig_len),
+        }
+    }
+}
+
+impl StdError for VecRemoveRebuildError {}
+
 /// Batch removes elements from a vector using a list of indices to remove.
 ///
 /// Will create a new vector and do a streamlined rebuild if
@@ -42,7 +42,7 @@ enum TaskKind {
 #[allow(dead_code)]
 struct Task {
     task_id: usize,
-    cmd_graph: CommandGraph,
+    cmd_graph: CommandGraph<'static>,
     kernels: Vec<Kernel>,
     expected_result: Option<Float4>,
     kind: TaskKind,
@@ -628,7 +628,7 @@ pub fn async_menagerie() -> OclResult<()> {
     // A pool of available device side memory (one big buffer with an attached allocator).
     let mut buf_pool: SubBufferPool<Float4> = SubBufferPool::new(INITIAL_BUFFER_LEN,
         Queue::new(&context, device, queue_flags)
-            .or_else(|_| Queue::new(&context, device, None))?);
+            .or_else(|_| Queue::new(&context, device, None))?)?;
     let mut tasks = Vec::with_capacity(256);
 
     // Our thread pool for offloading reading, writing, and other host-side processing.
@@ -0,0 +1,74 @@
+//! Structs used within kernels, implementing `OclPrm` and declared in OpenCL
+//! C using `#[derive(OclPrm)]` from `ocl-derive`.
+
+#[macro_use] extern crate ocl_derive;
+extern crate ocl;
+
+use ocl::{ProQue, MemFlags};
+use ocl::prm::Float3;
+
+/// A particle.
+///
+/// OpenCL C aligns `float3` to 16 bytes, so `_pad` is needed to place `pos`
+/// at the same offset in both languages. Without it, compilation fails.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, OclPrm)]
+pub struct Particle {
+    pub id: u32,
+    _pad: [u32; 3],
+    pub pos: Float3,
+    pub vel: Float3,
+}
+
+static KERNEL_SRC: &'static str = r#"
+    __kernel void step(__global Particle* particles, float dt) {
+        size_t idx = get_global_id(0);
+        particles[idx].pos += particles[idx].vel * dt;
+    }
+"#;
+
+fn derive_ocl_prm() -> ocl::Result<()> {
+    // The struct declaration must precede its use:
+    let pro_que = ProQue::builder()
+        .prog_bldr(ocl::Program::builder().src(Particle::CL_TYPEDEF).src(KERNEL_SRC))
+        .dims(1 << 10)
+        .build()?;
+
+    let particles: Vec<Particle> = (0..pro_que.dims().to_len() as u32).map(|id| Particle {
+        id: id,
+        pos: Float3::new(0., 0., id as f32),
+        vel: Float3::new(1., 2., 3.),
+        ..Default::default()
+    }).collect();
+
+    let buffer = ocl::Buffer::<Particle>::builder()
+        .queue(pro_que.queue().clone())
+        .flags(MemFlags::new().read_write().copy_host_ptr())
+        .len(particles.len())
+        .host_data(&particles)
+        .build()?;
+
+    let kernel = pro_que.create_kernel("step")?
+        .arg_buf(&buffer)
+        .arg_scl(0.5f32);
+
+    unsafe { kernel.enq()?; }
+
+    let mut results = vec![Particle::default(); buffer.len()];
+    buffer.read(&mut results).enq()?;
+
+    for (before, after) in particles.iter().zip(results.iter()) {
+        assert_eq!(after.id, before.id);
+        assert_eq!(after.pos, before.pos + Float3::new(0.5, 1., 1.5));
+    }
+
+    println!("{}Moved {} particles.", Particle::CL_TYPEDEF, results.len());
+    Ok(())
+}
+
+pub fn main() {
+    match derive_ocl_prm() {
+        Ok(_) => (),
+        Err(err) => println!("{}", err),
+    }
+}
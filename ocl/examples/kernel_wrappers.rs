@@ -0,0 +1,50 @@
+//! Typed kernel wrappers generated from an OpenCL C source file using the
+//! `ocl_kernels!` macro from `ocl-derive`.
+
+#[macro_use] extern crate ocl_derive;
+extern crate ocl;
+
+use ocl::ProQue;
+
+/// `AddScalar` and `MultiplyByScalar`, one for each kernel within the file.
+mod kernels {
+    ocl_kernels!("examples/cl/kernel_file.cl");
+}
+
+fn kernel_wrappers() -> ocl::Result<()> {
+    let pro_que = ProQue::builder()
+        .src(kernels::SRC)
+        .dims(1 << 12)
+        .build()?;
+
+    let src = ocl::Buffer::<f32>::builder()
+        .queue(pro_que.queue().clone())
+        .len(pro_que.dims().clone())
+        .fill_val(2.0)
+        .build()?;
+    let res = pro_que.create_buffer::<f32>()?;
+
+    // Argument types are verified against the kernel's argument info here:
+    let mut multiply = kernels::MultiplyByScalar::build(pro_que.program())?;
+    multiply.src = Some(&src);
+    multiply.coeff = 10.0;
+    multiply.res = Some(&res);
+
+    // Every argument is set from the fields above before enqueuing:
+    unsafe { multiply.enq(pro_que.queue(), pro_que.dims().clone())?; }
+
+    let mut vec = vec![0.0f32; res.len()];
+    res.read(&mut vec).enq()?;
+
+    assert!(vec.iter().all(|&val| val == 20.0));
+    println!("All {} elements of '{}' are now '{}'.", vec.len(),
+        kernels::MultiplyByScalar::NAME, vec[0]);
+    Ok(())
+}
+
+pub fn main() {
+    match kernel_wrappers() {
+        Ok(_) => (),
+        Err(err) => println!("{}", err),
+    }
+}
@@ -0,0 +1,81 @@
+//! Kernel parameter type mapping.
+//!
+//! The Rust type chosen for each parameter (`prm_type`) is the one
+//! `Kernel::verify_arg_type` accepts for it at runtime (`ArgType::is_match`).
+//! The type table is shared with `ocl`'s tests, which verify this.
+
+use parse::{KernelParam, AddressSpace};
+
+
+/// The kind of value a kernel argument is set with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ArgKind {
+    /// A scalar or vector passed by value.
+    Value { ty: String },
+    /// A `__global` or `__constant` pointer, set using a buffer.
+    Buffer { elem: String },
+    /// A `__local` pointer, set using a length in elements.
+    Local { elem: String },
+    Image,
+    Sampler,
+}
+
+
+include!("prm_types.rs");
+
+
+/// Returns the kind of value the parameter `param` of `kernel` is set with.
+pub fn arg_kind(kernel: &str, param: &KernelParam) -> Result<ArgKind, String> {
+    let unsupported = |reason: &str| {
+        Err(format!("Parameter '{}' of kernel '{}' ({}) is not supported: {}.", param.name,
+            kernel, param.type_name, reason))
+    };
+
+    if param.is_pipe {
+        return unsupported("pipes must be set using 'Kernel::arg_pipe'");
+    }
+    if param.is_aggregate {
+        return unsupported("structs, unions, and enums must be set manually");
+    }
+
+    if param.type_name.starts_with("image") && param.type_name.ends_with("_t") {
+        return Ok(ArgKind::Image);
+    }
+    if param.type_name == "sampler_t" {
+        return Ok(ArgKind::Sampler);
+    }
+
+    let ty = match prm_type(&param.type_name) {
+        Some(ty) => ty,
+        None => return unsupported("only OpenCL scalar and vector types, images, and samplers \
+            are supported"),
+    };
+
+    if !param.is_ptr {
+        return Ok(ArgKind::Value { ty });
+    }
+
+    match param.address_space {
+        AddressSpace::Global | AddressSpace::Constant => Ok(ArgKind::Buffer { elem: ty }),
+        AddressSpace::Local => Ok(ArgKind::Local { elem: ty }),
+        AddressSpace::Private => unsupported("pointers must be '__global', '__constant', or \
+            '__local'"),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prm_types() {
+        assert_eq!(prm_type("float").unwrap(), "f32");
+        assert_eq!(prm_type("uint").unwrap(), "u32");
+        assert_eq!(prm_type("int4").unwrap(), "::ocl::prm::Int4");
+        assert_eq!(prm_type("uchar16").unwrap(), "::ocl::prm::Uchar16");
+        assert!(prm_type("float5").is_none());
+        assert!(prm_type("half").is_none());
+        assert!(prm_type("size_t").is_none());
+    }
+}
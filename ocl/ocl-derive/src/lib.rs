@@ -0,0 +1,380 @@
+//! Procedural macros for [ocl].
+//!
+//! ## `ocl_kernels!`
+//!
+//! Generates a typed wrapper for each kernel defined within an OpenCL C
+//! source file. The path is relative to the directory containing the
+//! invoking crate's `Cargo.toml`:
+//!
+//! ```rust,ignore
+//! #[macro_use] extern crate ocl_derive;
+//! extern crate ocl;
+//!
+//! mod kernels {
+//!     ocl_kernels!("src/kernels.cl");
+//! }
+//! ```
+//!
+//! Given a kernel such as:
+//!
+//! ```c
+//! __kernel void add_scaled(__global float* buffer, float4 scale, int count,
+//!         __local float* scratch) { ... }
+//! ```
+//!
+//! a struct named `AddScaled` is generated with one public field per
+//! parameter:
+//!
+//! | Parameter                           | Field type                    |
+//! |-------------------------------------|-------------------------------|
+//! | `__global` / `__constant` `float*`  | `Option<&Buffer<f32>>`        |
+//! | `__local float*`                    | `usize` (length)              |
+//! | `float`, `int`, etc.                | `f32`, `i32`, etc.            |
+//! | `float4`, `int4`, etc.              | `ocl::prm::Float4`, etc.      |
+//! | `image2d_t`, etc.                   | `Option<&ocl::core::Mem>`     |
+//! | `sampler_t`                         | `Option<&Sampler>`            |
+//!
+//! `AddScaled::build(&program)` creates the kernel, verifying that the type
+//! of each field matches the kernel's argument info (see
+//! `Kernel::verify_arg_type`), and `::enq(&queue, gws)` sets every argument
+//! from the fields then enqueues the kernel:
+//!
+//! ```rust,ignore
+//! let program = Program::builder().src(kernels::SRC).build(&context)?;
+//! let mut add_scaled = kernels::AddScaled::build(&program)?;
+//! add_scaled.buffer = Some(&buffer);
+//! add_scaled.scale = Float4::new(1., 2., 3., 4.);
+//! add_scaled.scratch = 64;
+//! unsafe { add_scaled.enq(&queue, buffer.len())?; }
+//! ```
+//!
+//! The source itself is available as `SRC`. Invoke the macro within its own
+//! module to avoid name collisions. Parameters named after Rust keywords
+//! have an underscore appended (ex.: `in_`). Pipes, structs, and types
+//! without an `ocl::prm` equivalent (`half`, `size_t`, `bool`) are rejected.
+//!
+//! ## `#[derive(OclPrm)]`
+//!
+//! Implements `OclPrm` for a `#[repr(C)]` struct whose fields are Rust
+//! scalars (`u8`, `i32`, `f32`, etc.), OpenCL vectors (`ocl::prm::Float4`,
+//! etc.), or fixed-size arrays of either. The `Debug`, `Clone`, `Copy`,
+//! `Default`, and `PartialEq` bounds of `OclPrm` must be derived (or
+//! implemented) separately.
+//!
+//! The layout of the struct is verified at compile time against that of the
+//! equivalent OpenCL C struct. OpenCL vectors are aligned to their own size
+//! (16 bytes for a `float3` or `float4`), unlike `ocl::prm` vectors which are
+//! aligned to the size of their components, so padding must be added where
+//! OpenCL C would insert it:
+//!
+//! ```rust,ignore
+//! #[repr(C)]
+//! #[derive(Debug, Clone, Copy, Default, PartialEq, OclPrm)]
+//! pub struct Particle {
+//!     pub id: u32,
+//!     _pad: [u32; 3],
+//!     pub pos: Float3,
+//!     pub vel: Float3,
+//! }
+//! ```
+//!
+//! Without `_pad`, `pos` would be at offset 4 instead of 16 and compilation
+//! would fail. The matching OpenCL C declaration is available as
+//! `Particle::CL_TYPEDEF` and must be added to program source ahead of any
+//! kernel using it:
+//!
+//! ```rust,ignore
+//! let program = Program::builder()
+//!     .src(Particle::CL_TYPEDEF)
+//!     .src(kernel_src)
+//!     .build(&context)?;
+//! ```
+//!
+//! `#[repr(C, align(N))]` is supported (and reflected in the declaration).
+//! Generic and packed structs are not, nor are fields named after OpenCL C
+//! keywords or types (`global`, `kernel`, `int`, `float4`, etc.).
+//!
+//! [ocl]: https://github.com/cogciprocate/ocl
+
+extern crate proc_macro;
+
+mod parse;
+mod arg_type;
+mod ocl_prm;
+
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use proc_macro::{TokenStream, TokenTree, Delimiter};
+use parse::{KernelSig, KernelParam, AddressSpace};
+use arg_type::ArgKind;
+
+
+/// Identifiers which can not be used as field names.
+static RESERVED: &[&str] = &["as", "break", "const", "continue", "crate", "else",
+    "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+    "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+    "true", "type", "unsafe", "use", "where", "while", "abstract", "become", "box", "do",
+    "final", "macro", "override", "priv", "typeof", "unsized", "virtual", "yield", "async",
+    "await", "dyn", "try", "kernel", "_lifetime"];
+
+
+/// Returns a `compile_error!` invocation containing `msg`.
+fn compile_error(msg: &str) -> TokenStream {
+    format!("compile_error!({:?});", msg).parse().unwrap()
+}
+
+
+/// Returns the value of the single string literal within `input`.
+fn string_literal(input: TokenStream) -> Result<String, String> {
+    let mut tokens: Vec<TokenTree> = input.into_iter().collect();
+
+    // Literals passed through `macro_rules!` macros arrive within a group:
+    while tokens.len() == 1 {
+        let inner = match tokens[0] {
+            TokenTree::Group(ref group) if group.delimiter() == Delimiter::None => group.stream(),
+            _ => break,
+        };
+        tokens = inner.into_iter().collect();
+    }
+
+    let lit = match (tokens.len(), tokens.first()) {
+        (1, Some(TokenTree::Literal(lit))) => lit.to_string(),
+        _ => return Err("Expected a single string literal (the path of an OpenCL C source \
+            file).".to_owned()),
+    };
+
+    if let Some(raw) = lit.strip_prefix('r') {
+        let hashes = raw.find('"').unwrap_or(0);
+        Ok(raw[(hashes + 1)..(raw.len() - hashes - 1)].to_owned())
+    } else if let Some(quoted) = lit.strip_prefix('"').and_then(|lit| lit.strip_suffix('"')) {
+        Ok(quoted.replace("\\\"", "\"").replace("\\\\", "\\"))
+    } else {
+        Err(format!("Expected a string literal, found: {}", lit))
+    }
+}
+
+
+/// Converts a kernel name to an upper camel case struct name.
+fn struct_name(kernel_name: &str) -> String {
+    kernel_name.split('_').filter(|part| !part.is_empty()).map(|part| {
+        let mut chars = part.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }).collect()
+}
+
+
+/// Returns the field name for a parameter.
+fn field_name(param: &KernelParam) -> String {
+    if RESERVED.contains(&param.name.as_str()) {
+        format!("{}_", param.name)
+    } else {
+        param.name.clone()
+    }
+}
+
+
+/// Returns the declaration of a parameter as it appears in a doc comment.
+fn param_decl(param: &KernelParam) -> String {
+    let address_space = match param.address_space {
+        AddressSpace::Global => "__global ",
+        AddressSpace::Local => "__local ",
+        AddressSpace::Constant => "__constant ",
+        AddressSpace::Private => "",
+    };
+
+    format!("`{}{}{} {}`", address_space, param.type_name, if param.is_ptr { "*" } else { "" },
+        param.name)
+}
+
+
+/// Generates the wrapper for a single kernel.
+fn kernel_wrapper(kernel: &KernelSig, src_path: &str) -> Result<String, String> {
+    let name = struct_name(&kernel.name);
+    let mut fields = String::new();
+    let mut defaults = String::new();
+    let mut verifications = String::new();
+    let mut setters = String::new();
+
+    for (arg_idx, param) in kernel.params.iter().enumerate() {
+        let field = field_name(param);
+
+        let (field_ty, default, verify_ty, setter) = match arg_type::arg_kind(&kernel.name,
+                param)? {
+            ArgKind::Value { ty } => {
+                let setter = format!("self.kernel.set_arg_idx({}, self.{})?;", arg_idx, field);
+                (ty.clone(), "Default::default()".to_owned(), ty, setter)
+            },
+            ArgKind::Buffer { elem } => {
+                let setter = format!("match self.{f} {{ \
+                        Some(buffer) => self.kernel.set_arg_idx({i}, buffer), \
+                        None => self.kernel.set_arg_idx({i}, ::ocl::ArgVal::MemNull::<{e}>), \
+                    }}?;", f = field, i = arg_idx, e = elem);
+                (format!("Option<&'a ::ocl::Buffer<{}>>", elem), "None".to_owned(), elem, setter)
+            },
+            ArgKind::Local { elem } => {
+                let setter = format!("self.kernel.set_arg_idx({}, ::ocl::ArgVal::Local::<{}>(\
+                    self.{}))?;", arg_idx, elem, field);
+                ("usize".to_owned(), "0".to_owned(), elem, setter)
+            },
+            ArgKind::Image => {
+                let setter = format!("match self.{f} {{ \
+                        Some(image) => self.kernel.set_arg_idx({i}, \
+                            ::ocl::ArgVal::Image::<u64>(image)), \
+                        None => self.kernel.set_arg_idx({i}, ::ocl::ArgVal::MemNull::<u64>), \
+                    }}?;", f = field, i = arg_idx);
+                ("Option<&'a ::ocl::core::Mem>".to_owned(), "None".to_owned(), "u64".to_owned(),
+                    setter)
+            },
+            ArgKind::Sampler => {
+                let setter = format!("match self.{f} {{ \
+                        Some(sampler) => self.kernel.set_arg_idx({i}, sampler), \
+                        None => self.kernel.set_arg_idx({i}, \
+                            ::ocl::ArgVal::SamplerNull::<u64>), \
+                    }}?;", f = field, i = arg_idx);
+                ("Option<&'a ::ocl::Sampler>".to_owned(), "None".to_owned(), "u64".to_owned(),
+                    setter)
+            },
+        };
+
+        fields.push_str(&format!("#[doc = {:?}] pub {}: {},\n", param_decl(param), field,
+            field_ty));
+        defaults.push_str(&format!("{}: {},\n", field, default));
+        verifications.push_str(&format!("kernel.verify_arg_type::<{}>({})?;\n", verify_ty,
+            arg_idx));
+        setters.push_str(&setter);
+        setters.push('\n');
+    }
+
+    Ok(format!(r#"
+        #[doc = {struct_doc:?}]
+        pub struct {name}<'a> {{
+            kernel: ::ocl::Kernel,
+            _lifetime: ::std::marker::PhantomData<&'a ()>,
+            {fields}
+        }}
+
+        impl<'a> {name}<'a> {{
+            /// The name of the kernel.
+            pub const NAME: &'static str = {kernel_name:?};
+
+            /// Creates the kernel from `program`, verifying the type of each
+            /// argument.
+            pub fn build(program: &::ocl::Program) -> ::ocl::Result<{name}<'a>> {{
+                let kernel = ::ocl::Kernel::new({kernel_name:?}, program)?;
+                let num_args = kernel.num_args()?;
+                if num_args != {num_args} {{
+                    return Err(::ocl::error::KernelError::ArgCountMismatch {{
+                        kernel: {kernel_name:?}.to_owned(),
+                        expected: {num_args},
+                        actual: num_args,
+                    }}.into());
+                }}
+                {verifications}
+                Ok({name} {{
+                    kernel: kernel,
+                    _lifetime: ::std::marker::PhantomData,
+                    {defaults}
+                }})
+            }}
+
+            /// Returns the kernel.
+            pub fn kernel(&self) -> &::ocl::Kernel {{
+                &self.kernel
+            }}
+
+            /// Sets every argument from the fields of this struct then
+            /// enqueues the kernel on `queue`.
+            ///
+            /// # Safety
+            ///
+            /// All kernel code must be considered untrusted (see
+            /// `Kernel::enq`).
+            pub unsafe fn enq<D>(&mut self, queue: &::ocl::Queue, gws: D) -> ::ocl::Result<()>
+                    where D: Into<::ocl::SpatialDims> {{
+                {setters}
+                self.kernel.cmd().queue(queue).gws(gws).enq()
+            }}
+        }}
+        "#,
+        struct_doc = format!("The `{}` kernel, generated from `{}`.", kernel.name, src_path),
+        name = name,
+        kernel_name = kernel.name,
+        num_args = kernel.params.len(),
+        fields = fields,
+        defaults = defaults,
+        verifications = verifications,
+        setters = setters))
+}
+
+
+/// Reads and parses the source file at `path` then generates its wrappers.
+fn kernels(path: &str) -> Result<String, String> {
+    let mut full_path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    full_path.push(path);
+
+    let mut src = String::new();
+    File::open(&full_path).and_then(|mut file| file.read_to_string(&mut src))
+        .map_err(|err| format!("Unable to read '{}': {}", full_path.display(), err))?;
+
+    let kernels = parse::parse_kernels(&src).map_err(|err| format!("{} ({})", err, path))?;
+    if kernels.is_empty() {
+        return Err(format!("No kernels found in '{}'.", path));
+    }
+
+    // Including the file causes the invoking crate to be rebuilt when it changes:
+    let mut code = format!("/// The OpenCL C source containing the kernels.\n\
+        pub static SRC: &'static str = include_str!({:?});\n", full_path.display().to_string());
+
+    for kernel in kernels.iter() {
+        code.push_str(&kernel_wrapper(kernel, path)?);
+    }
+
+    Ok(code)
+}
+
+
+/// Generates a typed wrapper for each kernel within an OpenCL C source file.
+///
+/// See the [crate documentation](index.html).
+#[proc_macro]
+pub fn ocl_kernels(input: TokenStream) -> TokenStream {
+    let code = string_literal(input).and_then(|path| kernels(&path));
+
+    match code {
+        Ok(code) => code.parse().unwrap_or_else(|_| {
+            compile_error("ocl_kernels!: Unable to generate valid kernel wrappers.")
+        }),
+        Err(msg) => compile_error(&format!("ocl_kernels!: {}", msg)),
+    }
+}
+
+
+/// Implements `OclPrm` for a `#[repr(C)]` struct, verifying that its layout
+/// matches that of the equivalent OpenCL C struct.
+///
+/// See the [crate documentation](index.html).
+#[proc_macro_derive(OclPrm)]
+pub fn derive_ocl_prm(input: TokenStream) -> TokenStream {
+    match ocl_prm::derive(input) {
+        Ok(code) => code.parse().unwrap_or_else(|_| {
+            compile_error("#[derive(OclPrm)]: Unable to generate a valid implementation.")
+        }),
+        Err(msg) => compile_error(&format!("#[derive(OclPrm)]: {}", msg)),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn struct_names() {
+        assert_eq!(super::struct_name("add"), "Add");
+        assert_eq!(super::struct_name("add_scaled_values"), "AddScaledValues");
+        assert_eq!(super::struct_name("_fooBar_"), "FooBar");
+    }
+}
@@ -0,0 +1,524 @@
+//! `#[derive(OclPrm)]`.
+//!
+//! The OpenCL C layout of a struct is computed from the size and alignment
+//! of each field's OpenCL type (vectors are aligned to their own size, with
+//! 3-component vectors occupying the space of 4). The Rust layout is then
+//! checked against it by compile-time assertions on the offset of each field
+//! and the size of the struct, leaving the actual Rust layout up to the
+//! compiler (and target).
+
+use proc_macro::{TokenStream, TokenTree, Delimiter, Spacing};
+
+
+/// OpenCL C keywords, qualifiers, and built-in type names, which can not be
+/// used as field names within the OpenCL C declaration.
+static CL_RESERVED: &[&str] = &["auto", "break", "case", "const", "continue", "default",
+    "do", "else", "enum", "extern", "for", "goto", "if", "inline", "register", "restrict",
+    "return", "signed", "sizeof", "static", "struct", "switch", "typedef", "union",
+    "unsigned", "void", "volatile", "while", "_Bool", "_Complex", "_Imaginary", "__global",
+    "global", "__local", "local", "__constant", "constant", "__private", "private",
+    "__generic", "generic", "__kernel", "kernel", "__read_only", "read_only", "__write_only",
+    "write_only", "__read_write", "read_write", "__attribute__", "uniform", "pipe", "complex",
+    "imaginary", "size_t", "ptrdiff_t", "intptr_t", "uintptr_t", "image1d_t", "image1d_array_t",
+    "image1d_buffer_t", "image2d_t", "image2d_array_t", "image2d_depth_t",
+    "image2d_array_depth_t", "image3d_t", "sampler_t", "event_t", "queue_t", "ndrange_t",
+    "clk_event_t", "reserve_id_t", "cl_mem_fence_flags"];
+
+/// OpenCL C scalar type names, each of which (followed by a vector
+/// cardinality) is also a vector type name.
+static CL_SCALAR_TYPES: &[&str] = &["bool", "char", "uchar", "short", "ushort", "int", "uint",
+    "long", "ulong", "float", "double", "half", "quad"];
+
+
+/// Returns true if `name` is an OpenCL C keyword or built-in type name.
+pub fn is_cl_reserved(name: &str) -> bool {
+    let split = name.find(|c: char| c.is_ascii_digit()).unwrap_or(name.len());
+    let (base, card) = name.split_at(split);
+    CL_RESERVED.contains(&name) || (CL_SCALAR_TYPES.contains(&base) &&
+        ["", "2", "3", "4", "8", "16"].contains(&card))
+}
+
+
+/// The OpenCL type of a struct field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClType {
+    /// The OpenCL C type name (ex.: `float4`).
+    pub name: String,
+    pub size: usize,
+    pub align: usize,
+    /// The length of a fixed-size array field (`[T; N]`).
+    pub array_len: Option<usize>,
+}
+
+impl ClType {
+    /// Returns the OpenCL type of a Rust scalar (ex.: `f32`) or `ocl::prm`
+    /// vector (ex.: `Float4`) type name.
+    pub fn from_rust(type_name: &str) -> Option<ClType> {
+        let (scalar, size) = match type_name {
+            "i8" => ("char", 1),
+            "u8" => ("uchar", 1),
+            "i16" => ("short", 2),
+            "u16" => ("ushort", 2),
+            "i32" => ("int", 4),
+            "u32" => ("uint", 4),
+            "i64" => ("long", 8),
+            "u64" => ("ulong", 8),
+            "f32" => ("float", 4),
+            "f64" => ("double", 8),
+            _ => return ClType::vector_from_rust(type_name),
+        };
+
+        Some(ClType { name: scalar.to_owned(), size, align: size, array_len: None })
+    }
+
+    fn vector_from_rust(type_name: &str) -> Option<ClType> {
+        let split = type_name.find(|c: char| c.is_ascii_digit()).unwrap_or(type_name.len());
+        let (base, card) = type_name.split_at(split);
+
+        let (scalar, scalar_size) = match base {
+            "Char" => ("char", 1),
+            "Uchar" => ("uchar", 1),
+            "Short" => ("short", 2),
+            "Ushort" => ("ushort", 2),
+            "Int" => ("int", 4),
+            "Uint" => ("uint", 4),
+            "Long" => ("long", 8),
+            "Ulong" => ("ulong", 8),
+            "Float" => ("float", 4),
+            "Double" => ("double", 8),
+            _ => return None,
+        };
+
+        // 3-component vectors have the size and alignment of 4-component ones:
+        let stored_card = match card {
+            "" => 1,
+            "2" => 2,
+            "3" | "4" => 4,
+            "8" => 8,
+            "16" => 16,
+            _ => return None,
+        };
+
+        Some(ClType {
+            name: format!("{}{}", scalar, card),
+            size: scalar_size * stored_card,
+            align: scalar_size * stored_card,
+            array_len: None,
+        })
+    }
+
+    /// Returns the number of bytes occupied by the field.
+    pub fn field_size(&self) -> usize {
+        self.size * self.array_len.unwrap_or(1)
+    }
+}
+
+
+/// A struct field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Field {
+    /// The field name (possibly a raw identifier) or tuple index.
+    pub ident: String,
+    pub ty: ClType,
+}
+
+impl Field {
+    /// Returns the name used within the OpenCL C declaration.
+    pub fn cl_name(&self) -> String {
+        let ident = self.ident.trim_start_matches("r#");
+        match ident.chars().next() {
+            Some(c) if c.is_ascii_digit() => format!("_{}", ident),
+            _ => ident.to_owned(),
+        }
+    }
+}
+
+
+/// The OpenCL C layout of a struct.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Layout {
+    pub offsets: Vec<usize>,
+    pub size: usize,
+    pub align: usize,
+}
+
+
+fn align_up(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
+
+
+/// Computes the OpenCL C layout of a struct containing `fields`, aligned to
+/// at least `min_align`.
+pub fn layout(fields: &[Field], min_align: usize) -> Layout {
+    let mut offsets = Vec::with_capacity(fields.len());
+    let mut offset = 0;
+    let mut align = min_align.max(1);
+
+    for field in fields {
+        offset = align_up(offset, field.ty.align);
+        offsets.push(offset);
+        offset += field.ty.field_size();
+        align = align.max(field.ty.align);
+    }
+
+    Layout { offsets, size: align_up(offset, align), align }
+}
+
+
+/// Returns the OpenCL C `typedef struct` declaration of a struct.
+pub fn cl_typedef(name: &str, fields: &[Field], repr_align: Option<usize>) -> String {
+    let mut src = "typedef struct {\n".to_owned();
+
+    for field in fields {
+        match field.ty.array_len {
+            Some(len) => src.push_str(&format!("    {} {}[{}];\n", field.ty.name,
+                field.cl_name(), len)),
+            None => src.push_str(&format!("    {} {};\n", field.ty.name, field.cl_name())),
+        }
+    }
+
+    match repr_align {
+        Some(align) => src.push_str(&format!("}} __attribute__((aligned({}))) {};\n", align,
+            name)),
+        None => src.push_str(&format!("}} {};\n", name)),
+    }
+
+    src
+}
+
+
+/// Returns the `repr` options within an attribute (`#[...]` contents).
+fn repr_options(attr: TokenStream) -> Option<Vec<TokenTree>> {
+    let tokens: Vec<TokenTree> = attr.into_iter().collect();
+
+    match (tokens.first(), tokens.get(1)) {
+        (Some(TokenTree::Ident(ident)), Some(TokenTree::Group(group)))
+                if ident.to_string() == "repr" && group.delimiter() == Delimiter::Parenthesis => {
+            Some(group.stream().into_iter().collect())
+        },
+        _ => None,
+    }
+}
+
+
+/// Parses an integer literal, ignoring any suffix (ex.: `4usize`).
+fn parse_usize(lit: &str) -> Option<usize> {
+    let digits: String = lit.chars().take_while(|c| c.is_ascii_digit() || *c == '_')
+        .filter(|c| *c != '_').collect();
+    digits.parse().ok()
+}
+
+
+/// Returns the OpenCL type of a field declared with the type `tokens`.
+fn field_type(struct_name: &str, ident: &str, tokens: &[TokenTree]) -> Result<ClType, String> {
+    let unsupported = || {
+        let ty: TokenStream = tokens.iter().cloned().collect();
+        Err(format!("Field '{}' of '{}' has an unsupported type ('{}'). Fields must be Rust \
+            scalars ('u8' through 'u64', 'i8' through 'i64', 'f32', 'f64'), OpenCL vectors \
+            ('ocl::prm::Float4', etc.), or fixed-size arrays of either ('usize', 'isize', and \
+            'bool' have no fixed OpenCL equivalent).", ident, struct_name, ty))
+    };
+
+    // Arrays:
+    if tokens.len() == 1 {
+        if let TokenTree::Group(ref group) = tokens[0] {
+            if group.delimiter() != Delimiter::Bracket { return unsupported(); }
+            let inner: Vec<TokenTree> = group.stream().into_iter().collect();
+            let semi = inner.iter().position(|t| match *t {
+                TokenTree::Punct(ref p) => p.as_char() == ';',
+                _ => false,
+            });
+
+            let (elem, len) = match semi {
+                Some(semi) if semi + 2 == inner.len() => (&inner[..semi], &inner[semi + 1]),
+                _ => return unsupported(),
+            };
+            let len = match *len {
+                TokenTree::Literal(ref lit) => parse_usize(&lit.to_string()),
+                _ => None,
+            };
+
+            let mut ty = field_type(struct_name, ident, elem)?;
+            if ty.array_len.is_some() { return unsupported(); }
+            return match len {
+                Some(len) if len > 0 => {
+                    ty.array_len = Some(len);
+                    Ok(ty)
+                },
+                _ => unsupported(),
+            };
+        }
+    }
+
+    // Paths (ex.: `f32`, `Float4`, or `ocl::prm::Float4`):
+    let mut last = None;
+    for token in tokens {
+        match *token {
+            TokenTree::Ident(ref i) => last = Some(i.to_string()),
+            TokenTree::Punct(ref p) if p.as_char() == ':' => (),
+            _ => return unsupported(),
+        }
+    }
+
+    match last.and_then(|name| ClType::from_rust(&name)) {
+        Some(ty) => Ok(ty),
+        None => unsupported(),
+    }
+}
+
+
+/// Splits `tokens` at each comma outside of angle brackets.
+fn split_fields(tokens: Vec<TokenTree>) -> Vec<Vec<TokenTree>> {
+    let mut fields = vec![Vec::new()];
+    let mut depth = 0i32;
+
+    for token in tokens {
+        if let TokenTree::Punct(ref p) = token {
+            match p.as_char() {
+                '<' => depth += 1,
+                // Skips the `>` of `->`:
+                '>' if fields.last().and_then(|f| f.last()).map(|t| match *t {
+                    TokenTree::Punct(ref prev) => prev.as_char() == '-' &&
+                        prev.spacing() == Spacing::Joint,
+                    _ => false,
+                }) != Some(true) => depth -= 1,
+                ',' if depth == 0 => {
+                    fields.push(Vec::new());
+                    continue;
+                },
+                _ => (),
+            }
+        }
+        fields.last_mut().unwrap().push(token);
+    }
+
+    fields.retain(|f| !f.is_empty());
+    fields
+}
+
+
+/// Returns the index following any attributes and visibility at `i`.
+fn skip_attrs_and_vis(tokens: &[TokenTree], mut i: usize) -> usize {
+    loop {
+        match tokens.get(i) {
+            Some(TokenTree::Punct(p)) if p.as_char() == '#' => i += 2,
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "pub" => {
+                i += 1;
+                if let Some(TokenTree::Group(group)) = tokens.get(i) {
+                    if group.delimiter() == Delimiter::Parenthesis { i += 1; }
+                }
+            },
+            _ => return i,
+        }
+    }
+}
+
+
+/// Parses the fields of a struct body.
+fn parse_fields(struct_name: &str, body: TokenStream, named: bool)
+        -> Result<Vec<Field>, String> {
+    let mut fields = Vec::new();
+
+    for (idx, tokens) in split_fields(body.into_iter().collect()).into_iter().enumerate() {
+        let start = skip_attrs_and_vis(&tokens, 0);
+
+        let (ident, ty_start) = if named {
+            match (tokens.get(start), tokens.get(start + 1)) {
+                (Some(TokenTree::Ident(ident)), Some(TokenTree::Punct(p)))
+                        if p.as_char() == ':' => (ident.to_string(), start + 2),
+                _ => return Err(format!("Unable to parse the fields of '{}'.", struct_name)),
+            }
+        } else {
+            (idx.to_string(), start)
+        };
+
+        let ty = field_type(struct_name, &ident, &tokens[ty_start..])?;
+        let field = Field { ident, ty };
+
+        if is_cl_reserved(&field.cl_name()) {
+            return Err(format!("Field '{}' of '{}' is named after an OpenCL C keyword or type \
+                and can not be declared in OpenCL C. Rename the field.", field.ident,
+                struct_name));
+        }
+        fields.push(field);
+    }
+
+    if fields.is_empty() {
+        return Err(format!("'{}' has no fields. OpenCL C does not allow empty structs.",
+            struct_name));
+    }
+
+    Ok(fields)
+}
+
+
+/// Generates the `OclPrm` implementation, typedef, and layout assertions for
+/// the struct `input`.
+pub fn derive(input: TokenStream) -> Result<String, String> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let mut repr_c = false;
+    let mut repr_align = None;
+    let mut i = 0;
+
+    // Attributes and visibility:
+    loop {
+        match tokens.get(i) {
+            Some(TokenTree::Punct(p)) if p.as_char() == '#' => {
+                if let Some(TokenTree::Group(group)) = tokens.get(i + 1) {
+                    let options = repr_options(group.stream()).unwrap_or_default();
+                    let mut opts = options.iter().peekable();
+
+                    while let Some(opt) = opts.next() {
+                        let opt = match *opt {
+                            TokenTree::Ident(ref ident) => ident.to_string(),
+                            _ => continue,
+                        };
+                        match opt.as_str() {
+                            "C" => repr_c = true,
+                            "align" => {
+                                repr_align = match opts.next() {
+                                    Some(TokenTree::Group(g)) => {
+                                        parse_usize(&g.stream().to_string())
+                                    },
+                                    _ => None,
+                                };
+                            },
+                            "packed" => return Err("'#[repr(packed)]' is not supported by \
+                                '#[derive(OclPrm)]'.".to_owned()),
+                            _ => (),
+                        }
+                    }
+                }
+                i += 2;
+            },
+            Some(TokenTree::Ident(ident)) if ident.to_string() == "pub" => {
+                i += 1;
+                if let Some(TokenTree::Group(group)) = tokens.get(i) {
+                    if group.delimiter() == Delimiter::Parenthesis { i += 1; }
+                }
+            },
+            _ => break,
+        }
+    }
+
+    let name = match (tokens.get(i), tokens.get(i + 1)) {
+        (Some(TokenTree::Ident(kw)), Some(TokenTree::Ident(name)))
+            if kw.to_string() == "struct" => name.to_string(),
+        _ => return Err("'#[derive(OclPrm)]' may only be used on structs.".to_owned()),
+    };
+
+    if !repr_c {
+        return Err(format!("'{}' must be '#[repr(C)]' to derive 'OclPrm'.", name));
+    }
+
+    let fields = match tokens.get(i + 2) {
+        Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Brace => {
+            parse_fields(&name, body.stream(), true)?
+        },
+        Some(TokenTree::Group(body)) if body.delimiter() == Delimiter::Parenthesis => {
+            parse_fields(&name, body.stream(), false)?
+        },
+        Some(TokenTree::Punct(p)) if p.as_char() == '<' => {
+            return Err(format!("'{}' is generic. '#[derive(OclPrm)]' does not support generic \
+                structs.", name));
+        },
+        _ => return Err(format!("'{}' has no fields. OpenCL C does not allow empty structs.",
+            name)),
+    };
+
+    let layout = layout(&fields, repr_align.unwrap_or(1));
+    let typedef = cl_typedef(&name, &fields, repr_align);
+
+    let mut assertions = String::new();
+
+    for (field, offset) in fields.iter().zip(layout.offsets.iter()) {
+        assertions.push_str(&format!("assert!(::std::mem::offset_of!({name}, {field}) == \
+            {offset}, {msg:?});\n", name = name, field = field.ident, offset = offset,
+            msg = format!("Field '{}' of '{}' ({}) must be at offset {} to match its OpenCL C \
+            layout (OpenCL vectors are aligned to their own size). Add padding before the \
+            field or reorder fields.", field.ident, name, field.ty.name, offset)));
+    }
+
+    assertions.push_str(&format!("assert!(::std::mem::size_of::<{name}>() == {size}, {msg:?});\n",
+        name = name, size = layout.size, msg = format!("'{}' must be {} bytes in size to match \
+        its OpenCL C layout. Add padding to the end of the struct.", name, layout.size)));
+
+    Ok(format!(r#"
+        unsafe impl ::ocl::OclPrm for {name} {{}}
+
+        impl {name} {{
+            /// The OpenCL C declaration of this type, to be added to program
+            /// source (ex.: using `ProgramBuilder::src`) before any use.
+            pub const CL_TYPEDEF: &'static str = {typedef:?};
+        }}
+
+        const _: () = {{
+            {assertions}
+        }};
+        "#,
+        name = name,
+        typedef = typedef,
+        assertions = assertions))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(ident: &str, ty: &str, array_len: Option<usize>) -> Field {
+        let mut ty = ClType::from_rust(ty).unwrap();
+        ty.array_len = array_len;
+        Field { ident: ident.to_owned(), ty }
+    }
+
+    #[test]
+    fn cl_types() {
+        let float3 = ClType::from_rust("Float3").unwrap();
+        assert_eq!(float3.name, "float3");
+        assert_eq!((float3.size, float3.align), (16, 16));
+        assert_eq!(ClType::from_rust("Double16").unwrap().size, 128);
+        assert_eq!(ClType::from_rust("Uchar").unwrap().name, "uchar");
+        assert_eq!(ClType::from_rust("u64").unwrap().name, "ulong");
+        assert!(ClType::from_rust("usize").is_none());
+        assert!(ClType::from_rust("Float5").is_none());
+    }
+
+    #[test]
+    fn layouts() {
+        let fields = [field("id", "u32", None), field("pos", "Float3", None),
+            field("mass", "f32", None)];
+        let layout = layout(&fields, 1);
+        assert_eq!(layout.offsets, [0, 16, 32]);
+        assert_eq!((layout.size, layout.align), (48, 16));
+
+        let fields = [field("a", "u8", None), field("b", "Short2", Some(3)),
+            field("c", "u8", None)];
+        let layout = super::layout(&fields, 1);
+        assert_eq!(layout.offsets, [0, 4, 16]);
+        assert_eq!((layout.size, layout.align), (20, 4));
+
+        assert_eq!(super::layout(&fields, 32).size, 32);
+    }
+
+    #[test]
+    fn cl_reserved() {
+        for name in &["global", "kernel", "int", "float4", "uchar16", "half", "sampler_t",
+                "struct"] {
+            assert!(is_cl_reserved(name), "'{}'", name);
+        }
+        for name in &["pos", "int5", "if2", "globals", "_1", "mass"] {
+            assert!(!is_cl_reserved(name), "'{}'", name);
+        }
+    }
+
+    #[test]
+    fn typedefs() {
+        let fields = [field("pos", "Float4", None), field("1", "i32", Some(2))];
+        assert_eq!(cl_typedef("Particle", &fields, None),
+            "typedef struct {\n    float4 pos;\n    int _1[2];\n} Particle;\n");
+        assert_eq!(cl_typedef("Particle", &fields[..1], Some(32)),
+            "typedef struct {\n    float4 pos;\n} __attribute__((aligned(32))) Particle;\n");
+    }
+}
@@ -0,0 +1,345 @@
+//! A minimal parser for kernel signatures within OpenCL C source.
+//!
+//! Only as much of the language as is necessary to locate kernel functions
+//! and read their parameter lists is understood. Comments, preprocessor
+//! directives, and kernel bodies are skipped.
+
+use std::fmt;
+
+
+/// The address space of a kernel argument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressSpace {
+    Global,
+    Local,
+    Constant,
+    Private,
+}
+
+
+/// A kernel parameter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KernelParam {
+    pub name: String,
+    /// The type name without qualifiers (ex.: `float4`, `image2d_t`, or
+    /// `uint` for `unsigned int`).
+    pub type_name: String,
+    pub address_space: AddressSpace,
+    pub is_ptr: bool,
+    pub is_pipe: bool,
+    /// True if declared as a `struct`, `union`, or `enum`.
+    pub is_aggregate: bool,
+}
+
+
+/// A kernel function signature.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KernelSig {
+    pub name: String,
+    pub params: Vec<KernelParam>,
+}
+
+
+/// A source parsing error.
+#[derive(Debug)]
+pub struct ParseError(String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Punct(char),
+    Literal,
+}
+
+impl Token {
+    fn is_ident(&self, ident: &str) -> bool {
+        match *self {
+            Token::Ident(ref s) => s == ident,
+            _ => false,
+        }
+    }
+
+    fn is_punct(&self, punct: char) -> bool {
+        *self == Token::Punct(punct)
+    }
+}
+
+
+/// Splits `src` into tokens, skipping comments and preprocessor directives.
+fn tokenize(src: &str) -> Vec<Token> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line_start = true;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '\n' {
+            line_start = true;
+            i += 1;
+        } else if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && chars.get(i + 1) == Some(&'/') {
+            while i < chars.len() && chars[i] != '\n' { i += 1; }
+        } else if c == '/' && chars.get(i + 1) == Some(&'*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '#' && line_start {
+            // Directives continue onto the next line after a backslash:
+            while i < chars.len() && chars[i] != '\n' {
+                if chars[i] == '\\' && chars.get(i + 1) == Some(&'\n') { i += 1; }
+                i += 1;
+            }
+        } else if c == '"' || c == '\'' {
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                if chars[i] == '\\' { i += 1; }
+                i += 1;
+            }
+            i += 1;
+            tokens.push(Token::Literal);
+            line_start = false;
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') { i += 1; }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            line_start = false;
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '.') { i += 1; }
+            tokens.push(Token::Literal);
+            line_start = false;
+        } else {
+            tokens.push(Token::Punct(c));
+            line_start = false;
+            i += 1;
+        }
+    }
+
+    tokens
+}
+
+
+/// Returns the index following the group opened by the bracket at `start`.
+fn skip_group(tokens: &[Token], start: usize, open: char, close: char) -> usize {
+    let mut depth = 0;
+
+    for (i, token) in tokens.iter().enumerate().skip(start) {
+        if token.is_punct(open) {
+            depth += 1;
+        } else if token.is_punct(close) {
+            depth -= 1;
+            if depth == 0 { return i + 1; }
+        }
+    }
+
+    tokens.len()
+}
+
+
+/// Returns the index following any `__attribute__((...))` specifiers at `i`.
+fn skip_attributes(tokens: &[Token], mut i: usize) -> usize {
+    while i < tokens.len() && tokens[i].is_ident("__attribute__") {
+        if tokens.get(i + 1).map(|t| t.is_punct('(')).unwrap_or(false) {
+            i = skip_group(tokens, i + 1, '(', ')');
+        } else {
+            i += 1;
+        }
+    }
+    i
+}
+
+
+/// Parses a single kernel parameter declaration.
+fn parse_param(kernel: &str, tokens: &[Token]) -> Result<KernelParam, ParseError> {
+    let mut address_space = AddressSpace::Private;
+    let mut is_ptr = false;
+    let mut is_pipe = false;
+    let mut is_aggregate = false;
+    let mut unsigned = false;
+    let mut idents = Vec::new();
+
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::Ident(ref ident) => match ident.as_str() {
+                "__global" | "global" => address_space = AddressSpace::Global,
+                "__local" | "local" => address_space = AddressSpace::Local,
+                "__constant" | "constant" => address_space = AddressSpace::Constant,
+                "__private" | "private" => address_space = AddressSpace::Private,
+                "const" | "restrict" | "__restrict" | "volatile" | "signed" | "__read_only"
+                    | "read_only" | "__write_only" | "write_only" | "__read_write"
+                    | "read_write" => (),
+                "unsigned" => unsigned = true,
+                "pipe" => is_pipe = true,
+                "struct" | "union" | "enum" => is_aggregate = true,
+                "__attribute__" => {
+                    i = skip_attributes(tokens, i);
+                    continue;
+                },
+                _ => idents.push(ident.clone()),
+            },
+            Token::Punct('*') | Token::Punct('[') => is_ptr = true,
+            _ => (),
+        }
+        i += 1;
+    }
+
+    let name = match idents.pop() {
+        Some(name) => name,
+        None => return Err(ParseError(format!("Unable to parse a parameter of kernel '{}'.",
+            kernel))),
+    };
+
+    let type_name = match (idents.pop(), unsigned) {
+        (Some(ty), true) => format!("u{}", ty),
+        (Some(ty), false) => ty,
+        // A lone `unsigned` is an `unsigned int`:
+        (None, true) => "uint".to_owned(),
+        (None, false) => return Err(ParseError(format!("Unable to determine the type of \
+            parameter '{}' of kernel '{}'.", name, kernel))),
+    };
+
+    Ok(KernelParam {
+        name,
+        type_name,
+        address_space,
+        is_ptr,
+        is_pipe,
+        is_aggregate,
+    })
+}
+
+
+/// Parses the tokens of a parameter list (within the parentheses).
+fn parse_params(kernel: &str, tokens: &[Token]) -> Result<Vec<KernelParam>, ParseError> {
+    // `(void)` or `()`:
+    if tokens.is_empty() || (tokens.len() == 1 && tokens[0].is_ident("void")) {
+        return Ok(Vec::new());
+    }
+
+    let mut params = Vec::new();
+    let mut depth = 0;
+    let mut param_start = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match *token {
+            Token::Punct('(') | Token::Punct('[') => depth += 1,
+            Token::Punct(')') | Token::Punct(']') => depth -= 1,
+            Token::Punct(',') if depth == 0 => {
+                params.push(parse_param(kernel, &tokens[param_start..i])?);
+                param_start = i + 1;
+            },
+            _ => (),
+        }
+    }
+
+    params.push(parse_param(kernel, &tokens[param_start..])?);
+    Ok(params)
+}
+
+
+/// Returns the signature of each kernel function defined in `src`, in order.
+///
+/// Kernel prototypes (declarations without a body) are ignored.
+pub fn parse_kernels(src: &str) -> Result<Vec<KernelSig>, ParseError> {
+    let tokens = tokenize(src);
+    let mut kernels = Vec::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        if !(tokens[i].is_ident("__kernel") || tokens[i].is_ident("kernel")) {
+            i += 1;
+            continue;
+        }
+
+        let mut j = skip_attributes(&tokens, i + 1);
+        if !tokens.get(j).map(|t| t.is_ident("void")).unwrap_or(false) {
+            i += 1;
+            continue;
+        }
+        j = skip_attributes(&tokens, j + 1);
+
+        let name = match (tokens.get(j), tokens.get(j + 1)) {
+            (Some(Token::Ident(name)), Some(Token::Punct('('))) => name.clone(),
+            _ => return Err(ParseError("Unable to parse a kernel signature.".to_owned())),
+        };
+
+        let params_end = skip_group(&tokens, j + 1, '(', ')');
+        if !tokens[params_end - 1].is_punct(')') || params_end < j + 3 {
+            return Err(ParseError(format!("Unterminated parameter list for kernel '{}'.", name)));
+        }
+        let params = parse_params(&name, &tokens[(j + 2)..(params_end - 1)])?;
+
+        let body_start = skip_attributes(&tokens, params_end);
+        match tokens.get(body_start) {
+            Some(t) if t.is_punct('{') => {
+                kernels.push(KernelSig { name, params });
+                i = skip_group(&tokens, body_start, '{', '}');
+            },
+            _ => i = body_start,
+        }
+    }
+
+    Ok(kernels)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static SRC: &str = r#"
+        #define SCALE(x) \
+            ((x) * 2)
+
+        // __kernel void commented_out(__global float* a) {}
+
+        /* A helper. */
+        float helper(float a) { return a; }
+
+        __kernel void proto(__global int* a);
+
+        __kernel __attribute__((reqd_work_group_size(64, 1, 1)))
+        void add(__global float* buffer, const float addend, unsigned int count,
+                __local float4* scratch, __read_only image2d_t image, sampler_t sampler)
+        {
+            buffer[get_global_id(0)] += addend;
+        }
+
+        kernel void empty(void) {
+            { }
+        }
+    "#;
+
+    #[test]
+    fn parse_kernels() {
+        let kernels = super::parse_kernels(SRC).unwrap();
+        assert_eq!(kernels.len(), 2);
+
+        let add = &kernels[0];
+        assert_eq!(add.name, "add");
+        let names: Vec<_> = add.params.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["buffer", "addend", "count", "scratch", "image", "sampler"]);
+        let types: Vec<_> = add.params.iter().map(|p| p.type_name.as_str()).collect();
+        assert_eq!(types, ["float", "float", "uint", "float4", "image2d_t", "sampler_t"]);
+
+        assert_eq!(add.params[0].address_space, AddressSpace::Global);
+        assert!(add.params[0].is_ptr);
+        assert!(!add.params[1].is_ptr);
+        assert_eq!(add.params[3].address_space, AddressSpace::Local);
+
+        assert_eq!(kernels[1].name, "empty");
+        assert!(kernels[1].params.is_empty());
+    }
+}
@@ -0,0 +1,40 @@
+// The mapping of OpenCL scalar and vector type names to Rust types.
+//
+// This file is included (using `include!`) by both `arg_type` and the `ocl`
+// crate's tests (`tests::derive_prm_types`), which check each entry against
+// `ArgType::is_match`. It must not depend on anything else in this crate.
+
+/// The OpenCL scalar type names along with the Rust type of each and the
+/// `ocl::prm` vector type name prefix (ex.: `Float` for `float4`).
+pub static PRM_BASE_TYPES: [(&str, &str, &str); 10] = [
+    ("char", "i8", "Char"),
+    ("uchar", "u8", "Uchar"),
+    ("short", "i16", "Short"),
+    ("ushort", "u16", "Ushort"),
+    ("int", "i32", "Int"),
+    ("uint", "u32", "Uint"),
+    ("long", "i64", "Long"),
+    ("ulong", "u64", "Ulong"),
+    ("float", "f32", "Float"),
+    ("double", "f64", "Double"),
+];
+
+/// The cardinalities of OpenCL vector types.
+pub static PRM_VECTOR_CARDINALITIES: [&str; 5] = ["2", "3", "4", "8", "16"];
+
+
+/// Returns the Rust type of an OpenCL scalar or vector type name (ex.:
+/// `f32` for `float`, `::ocl::prm::Float4` for `float4`).
+pub fn prm_type(type_name: &str) -> Option<String> {
+    let split = type_name.find(|c: char| c.is_ascii_digit()).unwrap_or(type_name.len());
+    let (base, card) = type_name.split_at(split);
+    let &(_, scalar, vector) = PRM_BASE_TYPES.iter().find(|&&(name, _, _)| name == base)?;
+
+    if card.is_empty() {
+        Some(scalar.to_owned())
+    } else if PRM_VECTOR_CARDINALITIES.contains(&card) {
+        Some(format!("::ocl::prm::{}{}", vector, card))
+    } else {
+        None
+    }
+}
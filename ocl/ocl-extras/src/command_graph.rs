@@ -16,11 +16,97 @@
 //! command or trigger some other action which will affect commands which are
 //! **already** in a command queue.
 //!
+//! ### Recording
+//!
+//! Rather than describing each command by hand (`CommandDetails`) and
+//! enqueuing it using `::get_req_events` and `::set_cmd_event`, kernel,
+//! buffer, and image command builders can be recorded into a graph
+//! (`::record`) then run (`::run`):
+//!
+//! ```rust,ignore
+//! let mut graph = CommandGraph::new();
+//! graph.record(src_buf.write(&src_vec))?;
+//! graph.record(kernel.cmd())?;
+//! graph.record(dst_buf.read(&mut dst_vec))?;
+//! println!("{}", graph.to_dot());
+//! let finish_events = unsafe { graph.run(&[queue_0, queue_1])? };
+//! ```
+//!
+//! The memory objects each command reads from and writes to are determined
+//! automatically. For kernels these are its buffer and image arguments:
+//! arguments declared `const` or `__constant` (or `read_only` images) are
+//! read from and all others are both read from and written to.
 //!
 
+use std;
 use std::cell::{Cell, RefCell, Ref};
 use std::collections::{HashMap, BTreeSet};
-use ocl::{Event, EventList};
+use ocl::{Event, EventList, Queue, Kernel, OclPrm, Error as OclError, Result as OclResult};
+use ocl::core::{Mem as MemCore, ClWaitListPtr};
+use ocl::builders::{KernelCmd, BufferCmd, BufferReadCmd, BufferWriteCmd, ImageCmd, ImageCmdKind,
+    BufferCmdKind, ClWaitListPtrEnum};
+use ocl::enums::{KernelArgInfo, KernelArgInfoResult, KernelArgAddressQualifier,
+    KernelArgAccessQualifier};
+use ocl::flags::KernelArgTypeQualifier;
+
+/// A command graph error.
+#[derive(Debug)]
+pub enum CommandGraphError {
+    /// The buffer command is not a copy, fill, GL acquire, or GL release.
+    UnsupportedBufferCmd { name: String },
+    /// The image command has no operation specified or is a map.
+    UnsupportedImageCmd { name: String },
+    /// A command was recorded after `::populate_requisites` was called.
+    Locked,
+    /// No queues were passed to `::run`.
+    NoQueues,
+    /// The command was added without being recorded or has already been run.
+    CommandNotRecorded { cmd_idx: usize },
+    Ocl(OclError),
+}
+
+impl std::fmt::Display for CommandGraphError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            CommandGraphError::UnsupportedBufferCmd { ref name } => write!(f, "Unable to record \
+                {} command: Only copy, fill, and GL acquire and release buffer commands (or \
+                reads and writes) can be recorded.", name),
+            CommandGraphError::UnsupportedImageCmd { ref name } => write!(f, "Unable to record \
+                {} command: Image command has no operation specified or is a map.", name),
+            CommandGraphError::Locked => write!(f, "Commands can not be recorded after \
+                '::populate_requisites' has been called."),
+            CommandGraphError::NoQueues => write!(f, "At least one queue must be specified."),
+            CommandGraphError::CommandNotRecorded { cmd_idx } => write!(f, "Command {} was not \
+                recorded (using '::record') or has already been run.", cmd_idx),
+            CommandGraphError::Ocl(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for CommandGraphError {
+    fn source(&self) -> Option<&(std::error::Error + 'static)> {
+        match *self {
+            CommandGraphError::Ocl(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<OclError> for CommandGraphError {
+    fn from(err: OclError) -> CommandGraphError {
+        CommandGraphError::Ocl(err)
+    }
+}
+
+impl From<CommandGraphError> for OclError {
+    fn from(err: CommandGraphError) -> OclError {
+        match err {
+            CommandGraphError::Ocl(err) => err,
+            err => OclError::external(err),
+        }
+    }
+}
+
 
 pub struct RwCmdIdxs {
     writers: Vec<usize>,
@@ -79,6 +165,231 @@ impl CommandDetails {
             },
         }
     }
+
+    /// Returns the name of the kind of command.
+    pub fn kind_name(&self) -> &'static str {
+        match *self {
+            CommandDetails::Fill { .. } => "fill",
+            CommandDetails::Read { .. } => "read",
+            CommandDetails::Write { .. } => "write",
+            CommandDetails::Copy { .. } => "copy",
+            CommandDetails::Kernel { .. } => "kernel",
+        }
+    }
+}
+
+
+/// Returns the identifier used for the memory object `mem` by recorded
+/// commands (its pointer).
+pub fn mem_id(mem: &MemCore) -> usize {
+    mem.as_ptr() as usize
+}
+
+
+/// Returns whether kernel argument `arg_idx` is read from and/or written to.
+///
+/// Arguments are assumed to be both read from and written to unless declared
+/// otherwise or if argument info is unavailable.
+fn kernel_arg_access(kernel: &Kernel, arg_idx: u32) -> (bool, bool) {
+    match kernel.arg_info(arg_idx, KernelArgInfo::AccessQualifier) {
+        Ok(KernelArgInfoResult::AccessQualifier(KernelArgAccessQualifier::ReadOnly)) => {
+            return (true, false)
+        },
+        Ok(KernelArgInfoResult::AccessQualifier(KernelArgAccessQualifier::WriteOnly)) => {
+            return (false, true)
+        },
+        _ => (),
+    }
+
+    if let Ok(KernelArgInfoResult::AddressQualifier(KernelArgAddressQualifier::Constant)) =
+            kernel.arg_info(arg_idx, KernelArgInfo::AddressQualifier) {
+        return (true, false);
+    }
+
+    match kernel.arg_info(arg_idx, KernelArgInfo::TypeQualifier) {
+        Ok(KernelArgInfoResult::TypeQualifier(qualifier))
+            if qualifier.contains(KernelArgTypeQualifier::CONST) => (true, false),
+        _ => (true, true),
+    }
+}
+
+
+/// Returns the indexes of all commands, ordered by the length of the
+/// longest chain of `dependencies` preceding each, then by index.
+fn topological_order(dependencies: &[Vec<usize>]) -> Vec<usize> {
+    let mut levels: Vec<usize> = Vec::with_capacity(dependencies.len());
+
+    // Dependencies always precede the commands depending on them:
+    for cmd_deps in dependencies.iter() {
+        let level = cmd_deps.iter().map(|&dep_idx| levels[dep_idx] + 1).max().unwrap_or(0);
+        levels.push(level);
+    }
+
+    let mut order: Vec<usize> = (0..dependencies.len()).collect();
+    order.sort_by_key(|&cmd_idx| (levels[cmd_idx], cmd_idx));
+    order
+}
+
+
+/// Returns a wait list which owns `events`.
+fn wait_list<'a>(events: EventList) -> ClWaitListPtrEnum<'a> {
+    ClWaitListPtrEnum::from(Box::new(events) as Box<ClWaitListPtr>)
+}
+
+
+/// A command builder which can be recorded into a `CommandGraph`.
+pub trait Recordable {
+    /// Returns the details of the command, identifying memory objects using
+    /// `mem_id`. Kernel commands use `cmd_idx` as their id.
+    fn details(&self, cmd_idx: usize) -> Result<CommandDetails, CommandGraphError>;
+
+    /// Returns a short description of the command.
+    fn name(&self) -> String;
+
+    /// Enqueues the command on `queue`, waiting on `ewait`, and stores its
+    /// event in `enew`.
+    ///
+    /// # Safety
+    ///
+    /// Kernel commands run untrusted code (see `KernelCmd::enq`).
+    unsafe fn enq_recorded(self: Box<Self>, queue: &Queue, ewait: EventList, enew: &mut Event)
+        -> OclResult<()>;
+}
+
+impl<'k> Recordable for KernelCmd<'k> {
+    fn details(&self, cmd_idx: usize) -> Result<CommandDetails, CommandGraphError> {
+        let kernel = self.kernel();
+        let mut sources = Vec::new();
+        let mut targets = Vec::new();
+
+        for (arg_idx, mem) in kernel.mem_args().iter().enumerate() {
+            let buffer_id = match *mem {
+                Some(ref mem) => mem_id(mem),
+                None => continue,
+            };
+
+            let (reads, writes) = kernel_arg_access(kernel, arg_idx as u32);
+            if reads { sources.push(KernelArgBuffer::new(arg_idx, buffer_id)); }
+            if writes { targets.push(KernelArgBuffer::new(arg_idx, buffer_id)); }
+        }
+
+        Ok(CommandDetails::Kernel { id: cmd_idx, sources: sources, targets: targets })
+    }
+
+    fn name(&self) -> String {
+        self.kernel().name().unwrap_or_else(|_| "kernel".to_owned())
+    }
+
+    unsafe fn enq_recorded(self: Box<Self>, queue: &Queue, ewait: EventList, enew: &mut Event)
+            -> OclResult<()> {
+        (*self).queue(queue).ewait(wait_list(ewait)).enew(enew).enq()
+    }
+}
+
+impl<'c, T: OclPrm> Recordable for BufferCmd<'c, T> {
+    fn details(&self, _: usize) -> Result<CommandDetails, CommandGraphError> {
+        let buffer_id = mem_id(self.buffer().as_core());
+
+        match *self.kind() {
+            BufferCmdKind::Copy { dst_buffer, .. } => {
+                Ok(CommandDetails::Copy { source: buffer_id, target: mem_id(dst_buffer) })
+            },
+            BufferCmdKind::Fill { .. } => Ok(CommandDetails::Fill { target: buffer_id }),
+            // Acquiring or releasing a GL object must be ordered like a write:
+            BufferCmdKind::GLAcquire | BufferCmdKind::GLRelease => {
+                Ok(CommandDetails::Write { target: buffer_id })
+            },
+            _ => Err(CommandGraphError::UnsupportedBufferCmd { name: self.name() }),
+        }
+    }
+
+    fn name(&self) -> String {
+        match *self.kind() {
+            BufferCmdKind::Copy { .. } => "buffer copy",
+            BufferCmdKind::Fill { .. } => "buffer fill",
+            BufferCmdKind::GLAcquire => "buffer GL acquire",
+            BufferCmdKind::GLRelease => "buffer GL release",
+            _ => "buffer",
+        }.to_owned()
+    }
+
+    unsafe fn enq_recorded(self: Box<Self>, queue: &Queue, ewait: EventList, enew: &mut Event)
+            -> OclResult<()> {
+        (*self).queue(queue).ewait(wait_list(ewait)).enew(enew).enq()
+    }
+}
+
+impl<'c, 'd, T: OclPrm> Recordable for BufferReadCmd<'c, 'd, T> {
+    fn details(&self, _: usize) -> Result<CommandDetails, CommandGraphError> {
+        Ok(CommandDetails::Read { source: mem_id(self.buffer().as_core()) })
+    }
+
+    fn name(&self) -> String {
+        "buffer read".to_owned()
+    }
+
+    unsafe fn enq_recorded(self: Box<Self>, queue: &Queue, ewait: EventList, enew: &mut Event)
+            -> OclResult<()> {
+        (*self).queue(queue).ewait(wait_list(ewait)).enew(enew).enq()
+    }
+}
+
+impl<'c, 'd, T: OclPrm> Recordable for BufferWriteCmd<'c, 'd, T> {
+    fn details(&self, _: usize) -> Result<CommandDetails, CommandGraphError> {
+        Ok(CommandDetails::Write { target: mem_id(self.buffer().as_core()) })
+    }
+
+    fn name(&self) -> String {
+        "buffer write".to_owned()
+    }
+
+    unsafe fn enq_recorded(self: Box<Self>, queue: &Queue, ewait: EventList, enew: &mut Event)
+            -> OclResult<()> {
+        (*self).queue(queue).ewait(wait_list(ewait)).enew(enew).enq()
+    }
+}
+
+impl<'c, T: OclPrm> Recordable for ImageCmd<'c, T> {
+    fn details(&self, _: usize) -> Result<CommandDetails, CommandGraphError> {
+        let image_id = mem_id(self.mem());
+
+        match *self.kind() {
+            ImageCmdKind::Read { .. } => Ok(CommandDetails::Read { source: image_id }),
+            ImageCmdKind::Write { .. } => Ok(CommandDetails::Write { target: image_id }),
+            ImageCmdKind::Fill { .. } => Ok(CommandDetails::Fill { target: image_id }),
+            ImageCmdKind::Copy { dst_image, .. } => {
+                Ok(CommandDetails::Copy { source: image_id, target: mem_id(dst_image) })
+            },
+            ImageCmdKind::CopyToBuffer { buffer, .. } => {
+                Ok(CommandDetails::Copy { source: image_id, target: mem_id(buffer) })
+            },
+            // Acquiring or releasing a GL object must be ordered like a write:
+            ImageCmdKind::GLAcquire | ImageCmdKind::GLRelease => {
+                Ok(CommandDetails::Write { target: image_id })
+            },
+            ImageCmdKind::Unspecified | ImageCmdKind::Map => {
+                Err(CommandGraphError::UnsupportedImageCmd { name: self.name() })
+            },
+        }
+    }
+
+    fn name(&self) -> String {
+        match *self.kind() {
+            ImageCmdKind::Read { .. } => "image read",
+            ImageCmdKind::Write { .. } => "image write",
+            ImageCmdKind::Fill { .. } => "image fill",
+            ImageCmdKind::Copy { .. } => "image copy",
+            ImageCmdKind::CopyToBuffer { .. } => "image copy to buffer",
+            ImageCmdKind::GLAcquire => "image GL acquire",
+            ImageCmdKind::GLRelease => "image GL release",
+            _ => "image",
+        }.to_owned()
+    }
+
+    unsafe fn enq_recorded(self: Box<Self>, queue: &Queue, ewait: EventList, enew: &mut Event)
+            -> OclResult<()> {
+        (*self).queue(queue).ewait(wait_list(ewait)).enew(enew).enq()
+    }
 }
 
 
@@ -133,34 +444,212 @@ impl Command {
 /// could also use ranges of values and do an overlap check and have
 /// byte-level precision.
 ///
-pub struct CommandGraph {
+/// Command builders recorded using `::record` are identified by the memory
+/// objects they use (see `mem_id`) and can be enqueued by the graph itself
+/// using `::run`.
+///
+pub struct CommandGraph<'c> {
     commands: Vec<Command>,
     command_requisites: Vec<Vec<usize>>,
     ends: (Vec<usize>, Vec<usize>),
     locked: bool,
     next_cmd_idx: Cell<usize>,
+    ops: Vec<Option<Box<Recordable + 'c>>>,
+    names: Vec<String>,
 }
 
-impl CommandGraph {
+impl<'c> CommandGraph<'c> {
     /// Returns a new, empty graph.
-    pub fn new() -> CommandGraph {
+    pub fn new() -> CommandGraph<'c> {
         CommandGraph {
             commands: Vec::new(),
             command_requisites: Vec::new(),
             ends: (Vec::new(), Vec::new()),
             locked: false,
             next_cmd_idx: Cell::new(0),
+            ops: Vec::new(),
+            names: Vec::new(),
         }
     }
 
     /// Adds a new command and returns the command index if successful.
     pub fn add(&mut self, command: Command) -> Result<usize, ()> {
         if self.locked { return Err(()); }
+        self.names.push(command.details.kind_name().to_owned());
+        self.ops.push(None);
         self.commands.push(command);
         self.command_requisites.push(Vec::new());
         Ok(self.commands.len() - 1)
     }
 
+    /// Records a kernel, buffer, or image command builder and returns its
+    /// command index.
+    ///
+    /// The memory objects the command reads from and writes to are
+    /// determined when recorded: kernel arguments must be set beforehand.
+    /// The command is enqueued by `::run`.
+    pub fn record<R: Recordable + 'c>(&mut self, cmd: R) -> Result<usize, CommandGraphError> {
+        if self.locked { return Err(CommandGraphError::Locked); }
+
+        let cmd_idx = self.commands.len();
+        let details = cmd.details(cmd_idx)?;
+        self.names.push(cmd.name());
+        self.ops.push(Some(Box::new(cmd)));
+        self.commands.push(Command::new(details));
+        self.command_requisites.push(Vec::new());
+        Ok(cmd_idx)
+    }
+
+    /// Returns the indexes of the commands which each command depends on.
+    ///
+    /// Commands are ordered as they were added: a command depends on the
+    /// last preceding command to write to each memory object it reads from
+    /// or writes to, and on each command which has read from a memory object
+    /// it writes to since that object was last written.
+    pub fn dependencies(&self) -> Vec<Vec<usize>> {
+        let mut last_writers: HashMap<usize, usize> = HashMap::new();
+        let mut readers: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut dependencies = Vec::with_capacity(self.commands.len());
+
+        for (cmd_idx, cmd) in self.commands.iter().enumerate() {
+            let sources = cmd.details.sources();
+            let targets = cmd.details.targets();
+            let mut cmd_deps = BTreeSet::new();
+
+            for src in sources.iter() {
+                cmd_deps.extend(last_writers.get(src).cloned());
+            }
+
+            for tar in targets.iter() {
+                cmd_deps.extend(last_writers.get(tar).cloned());
+                if let Some(tar_readers) = readers.get(tar) {
+                    cmd_deps.extend(tar_readers.iter().cloned());
+                }
+            }
+
+            for &src in sources.iter() {
+                readers.entry(src).or_insert_with(Vec::new).push(cmd_idx);
+            }
+
+            for &tar in targets.iter() {
+                last_writers.insert(tar, cmd_idx);
+                readers.remove(&tar);
+            }
+
+            dependencies.push(cmd_deps.into_iter().collect());
+        }
+
+        dependencies
+    }
+
+    /// Returns the indexes of all commands, ordered such that each command
+    /// follows every command it depends on (see `::dependencies`).
+    ///
+    /// Commands are ordered by the length of the longest chain of
+    /// dependencies preceding them, then by index.
+    pub fn topological_order(&self) -> Vec<usize> {
+        topological_order(&self.dependencies())
+    }
+
+    /// Enqueues every recorded command in topological order, each waiting
+    /// on the commands it depends on, and returns the events of the commands
+    /// which no other command depends on.
+    ///
+    /// Commands are distributed among `queues`: a command is enqueued on the
+    /// same queue as the first of its dependencies not already continued by
+    /// another command, or otherwise on the next queue in turn. Each
+    /// command's event is stored in its `Command`.
+    ///
+    /// Recorded commands are consumed and can only be run once.
+    ///
+    /// # Safety
+    ///
+    /// Runs kernels (see `KernelCmd::enq`).
+    pub unsafe fn run(&mut self, queues: &[Queue]) -> Result<EventList, CommandGraphError> {
+        if queues.is_empty() { return Err(CommandGraphError::NoQueues); }
+        if let Some(cmd_idx) = self.ops.iter().position(|op| op.is_none()) {
+            return Err(CommandGraphError::CommandNotRecorded { cmd_idx: cmd_idx });
+        }
+
+        let dependencies = self.dependencies();
+        let mut queue_idxs = vec![0; self.commands.len()];
+        let mut continued = vec![false; self.commands.len()];
+        let mut has_dependents = vec![false; self.commands.len()];
+        let mut next_queue_idx = 0;
+
+        for cmd_idx in topological_order(&dependencies) {
+            let cmd_deps = &dependencies[cmd_idx];
+
+            let queue_idx = match cmd_deps.iter().find(|&&dep_idx| !continued[dep_idx]) {
+                Some(&dep_idx) => {
+                    continued[dep_idx] = true;
+                    queue_idxs[dep_idx]
+                },
+                None => {
+                    let queue_idx = next_queue_idx;
+                    next_queue_idx = (next_queue_idx + 1) % queues.len();
+                    queue_idx
+                },
+            };
+            queue_idxs[cmd_idx] = queue_idx;
+
+            let mut ewait = EventList::new();
+            for &dep_idx in cmd_deps.iter() {
+                has_dependents[dep_idx] = true;
+                if let Some(ref event) = *self.commands[dep_idx].event.borrow() {
+                    ewait.push(event.clone());
+                }
+            }
+
+            let op = self.ops[cmd_idx].take().expect("CommandGraph::run: Command missing.");
+            let mut event = Event::empty();
+            op.enq_recorded(&queues[queue_idx], ewait, &mut event)?;
+            *self.commands[cmd_idx].event.borrow_mut() = Some(event);
+        }
+
+        let mut finish_events = EventList::new();
+        for (cmd_idx, cmd) in self.commands.iter().enumerate() {
+            if has_dependents[cmd_idx] { continue; }
+            if let Some(ref event) = *cmd.event.borrow() {
+                finish_events.push(event.clone());
+            }
+        }
+        Ok(finish_events)
+    }
+
+    /// Returns the graph, with an edge from each command to the commands
+    /// depending on it (see `::dependencies`), in the Graphviz DOT language.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph CommandGraph {\n");
+
+        for (cmd_idx, name) in self.names.iter().enumerate() {
+            let label = format!("{}: {}", cmd_idx, name).replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            dot.push_str(&format!("    n{} [label=\"{}\"];\n", cmd_idx, label));
+        }
+
+        for (cmd_idx, cmd_deps) in self.dependencies().iter().enumerate() {
+            for dep_idx in cmd_deps.iter() {
+                dot.push_str(&format!("    n{} -> n{};\n", dep_idx, cmd_idx));
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Removes all commands, unlocking the graph.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+        self.command_requisites.clear();
+        self.ends.0.clear();
+        self.ends.1.clear();
+        self.locked = false;
+        self.next_cmd_idx.set(0);
+        self.ops.clear();
+        self.names.clear();
+    }
+
     /// Returns a sub-buffer map which contains every command that reads from
     /// or writes to each sub-buffer.
     fn readers_and_writers_by_buffer(&self) -> HashMap<usize, RwCmdIdxs> {
@@ -5,8 +5,9 @@ extern crate num_traits;
 pub mod sub_buffer_pool;
 pub mod command_graph;
 
-pub use self::sub_buffer_pool::SubBufferPool;
-pub use self::command_graph::{CommandGraph, Command, CommandDetails, KernelArgBuffer, RwCmdIdxs};
+pub use self::sub_buffer_pool::{SubBufferPool, SubBufferPoolError, SubBufferHandle, PoolStats};
+pub use self::command_graph::{CommandGraph, CommandGraphError, Command, CommandDetails, KernelArgBuffer, RwCmdIdxs,
+    Recordable, mem_id};
 
 // use rand;
 use rand::distributions::{IndependentSample, Range as RandRange};
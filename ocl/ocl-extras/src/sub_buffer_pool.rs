@@ -1,50 +1,188 @@
 //! A sub-buffer allocator.
+//!
+//! Sub-buffers are placed using a best-fit search of the free space between
+//! existing sub-buffers and are aligned to the base address alignment of the
+//! pool's device (`Device::mem_base_addr_align`).
+//!
+//! Sub-buffers are identified by a buffer id which remains valid for as long
+//! as the sub-buffer is allocated. Defragmenting (`::defrag`) or resizing
+//! (`::resize`) the pool relocates sub-buffers, replacing each relocated
+//! sub-buffer with a new one. Kernels (or anything else) holding a relocated
+//! sub-buffer must be updated. A `SubBufferHandle` (`::handle`) records the
+//! generation of a sub-buffer and can be used to determine whether or not it
+//! has since been relocated (`::is_current`).
 
-use std::collections::{LinkedList, HashMap};
-use ocl::{Queue, Buffer};
+use std;
+use std::cmp;
+use std::mem;
+use std::collections::HashMap;
+use ocl::{Queue, Buffer, Error as OclError, Result as OclResult};
 use ocl::traits::OclPrm;
 use ocl::flags::MemFlags;
 
 
+/// A sub-buffer pool error.
+#[derive(Debug)]
+pub enum SubBufferPoolError {
+    ZeroLength,
+    OutOfSpace { len: u32, largest_free: u32 },
+    LenTooSmall { len: u32, required: u32 },
+    Ocl(OclError),
+}
+
+impl std::fmt::Display for SubBufferPoolError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            SubBufferPoolError::ZeroLength => write!(f, "Sub-buffer length must be non-zero."),
+            SubBufferPoolError::OutOfSpace { len, largest_free } => write!(f, "Insufficient \
+                contiguous free space for a sub-buffer of length {} (largest free region: {}).",
+                len, largest_free),
+            SubBufferPoolError::LenTooSmall { len, required } => write!(f, "Pool length ({}) is \
+                less than the length required by the allocated sub-buffers ({}).", len, required),
+            SubBufferPoolError::Ocl(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for SubBufferPoolError {
+    fn source(&self) -> Option<&(std::error::Error + 'static)> {
+        match *self {
+            SubBufferPoolError::Ocl(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<OclError> for SubBufferPoolError {
+    fn from(err: OclError) -> SubBufferPoolError {
+        SubBufferPoolError::Ocl(err)
+    }
+}
+
+impl From<SubBufferPoolError> for OclError {
+    fn from(err: SubBufferPoolError) -> OclError {
+        match err {
+            SubBufferPoolError::Ocl(err) => err,
+            err => OclError::external(err),
+        }
+    }
+}
+
+
+/// The region of the pool's buffer occupied by a sub-buffer.
 pub struct PoolRegion {
     buffer_id: usize,
     origin: u32,
     len: u32,
+    flags: Option<MemFlags>,
+    generation: usize,
+}
+
+
+/// Identifies a particular generation of a sub-buffer.
+///
+/// A sub-buffer's generation is incremented each time it is relocated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SubBufferHandle {
+    buffer_id: usize,
+    generation: usize,
+}
+
+impl SubBufferHandle {
+    /// Returns the buffer id of the sub-buffer.
+    pub fn buffer_id(&self) -> usize { self.buffer_id }
+
+    /// Returns the generation of the sub-buffer when this handle was created.
+    pub fn generation(&self) -> usize { self.generation }
 }
 
 
-/// A simple (linear search) sub-buffer allocator.
+/// Usage statistics for a `SubBufferPool`. Lengths are in elements.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// The length of the pool's buffer.
+    pub len: u32,
+    /// The total length of all allocated sub-buffers.
+    pub used: u32,
+    /// The highest total length of allocated sub-buffers.
+    pub peak_used: u32,
+    /// The total length of all (aligned) free regions.
+    pub free: u32,
+    /// The length of the largest (aligned) free region.
+    pub largest_free: u32,
+    /// The number of allocated sub-buffers.
+    pub sub_buffer_count: usize,
+    /// The number of successful allocations.
+    pub alloc_count: usize,
+    /// The number of failed allocations.
+    pub failed_alloc_count: usize,
+    /// The number of sub-buffers freed.
+    pub free_count: usize,
+    /// The number of times a sub-buffer has been relocated.
+    pub relocation_count: usize,
+}
+
+impl PoolStats {
+    /// Returns the portion of free space not part of the largest free region
+    /// (from `0.0`, unfragmented, up to `1.0`).
+    pub fn fragmentation(&self) -> f64 {
+        if self.free == 0 { return 0.0; }
+        1.0 - (self.largest_free as f64 / self.free as f64)
+    }
+}
+
+
+/// Returns the greatest common divisor of `a` and `b`.
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+
+/// Returns the flags used to create the pool's buffer.
+fn pool_flags() -> MemFlags {
+    MemFlags::new().alloc_host_ptr().read_write()
+}
+
+
+/// A best-fit sub-buffer allocator.
 pub struct SubBufferPool<T: OclPrm> {
     buffer: Buffer<T>,
-    regions: LinkedList<PoolRegion>,
+    queue: Queue,
+    regions: Vec<PoolRegion>,
     sub_buffers: HashMap<usize, Buffer<T>>,
     align: u32,
+    stats: PoolStats,
     _next_uid: usize,
 }
 
 impl<T: OclPrm> SubBufferPool<T> {
     /// Returns a new buffer pool.
-    pub fn new(len: u32, default_queue: Queue) -> SubBufferPool<T> {
-        let align = default_queue.device().mem_base_addr_align().unwrap();
-        let flags = MemFlags::new().alloc_host_ptr().read_write();
+    pub fn new(len: u32, default_queue: Queue) -> OclResult<SubBufferPool<T>> {
+        // The alignment is in bits. Sub-buffer origins are in elements:
+        let align_bytes = cmp::max(default_queue.device().mem_base_addr_align()? / 8, 1) as usize;
+        let align = align_bytes / gcd(align_bytes, mem::size_of::<T>());
 
         let buffer = Buffer::<T>::builder()
-            .queue(default_queue)
-            .flags(flags)
+            .queue(default_queue.clone())
+            .flags(pool_flags())
             .len(len as usize)
-            .build().unwrap();
+            .build()?;
 
-        SubBufferPool {
+        Ok(SubBufferPool {
             buffer: buffer,
-            regions: LinkedList::new(),
+            queue: default_queue,
+            regions: Vec::new(),
             sub_buffers: HashMap::new(),
-            align: align,
+            align: align as u32,
+            stats: PoolStats::default(),
             _next_uid: 0,
-        }
+        })
     }
 
     fn next_valid_align(&self, unaligned_origin: u32) -> u32 {
-        ((unaligned_origin / self.align) + 1) * self.align
+        let align = self.align as u64;
+        cmp::min((unaligned_origin as u64 + align - 1) / align * align, u32::max_value() as u64)
+            as u32
     }
 
     fn next_uid(&mut self) -> usize {
@@ -52,54 +190,99 @@ impl<T: OclPrm> SubBufferPool<T> {
         self._next_uid - 1
     }
 
-    fn insert_region(&mut self, region: PoolRegion, region_idx: usize) {
-        let mut tail = self.regions.split_off(region_idx);
-        self.regions.push_back(region);
-        self.regions.append(&mut tail);
+    /// Returns the origin and length of each free region along with the
+    /// index of the region which follows it.
+    fn free_regions(&self) -> Vec<(usize, u32, u32)> {
+        let mut free_regions = Vec::with_capacity(self.regions.len() + 1);
+        let mut end_prev = 0;
+
+        for (region_idx, region) in self.regions.iter().enumerate() {
+            let origin = self.next_valid_align(end_prev);
+            if region.origin > origin {
+                free_regions.push((region_idx, origin, region.origin - origin));
+            }
+            end_prev = region.origin + region.len;
+        }
+
+        let origin = self.next_valid_align(end_prev);
+        let buffer_len = self.buffer.len() as u32;
+        if buffer_len > origin {
+            free_regions.push((self.regions.len(), origin, buffer_len - origin));
+        }
+
+        free_regions
+    }
+
+    /// Returns the new origin of each region if all were packed together.
+    fn packed_origins(&self) -> Vec<u32> {
+        let mut end_prev = 0;
+
+        self.regions.iter().map(|region| {
+            let origin = self.next_valid_align(end_prev);
+            end_prev = origin + region.len;
+            origin
+        }).collect()
     }
 
     fn create_sub_buffer(&mut self, region_idx: usize, flags: Option<MemFlags>,
-            origin: u32, len: u32) -> usize {
+            origin: u32, len: u32) -> OclResult<usize> {
+        let sbuf = self.buffer.create_sub_buffer(flags, origin as usize, len as usize)?;
         let buffer_id = self.next_uid();
-        let region = PoolRegion { buffer_id: buffer_id, origin: origin, len: len };
-        let sbuf = self.buffer.create_sub_buffer(flags, region.origin as usize,
-            region.len as usize).unwrap();
+        let region = PoolRegion { buffer_id: buffer_id, origin: origin, len: len, flags: flags,
+            generation: 0 };
         if let Some(idx) = self.sub_buffers.insert(region.buffer_id, sbuf) {
             panic!("Duplicate indexes: {}", idx); }
-        self.insert_region(region, region_idx);
-        buffer_id
+        self.regions.insert(region_idx, region);
+        Ok(buffer_id)
+    }
+
+    /// Replaces the sub-buffer for the region at `region_idx` (after it has
+    /// been moved), keeping its default queue.
+    fn recreate_sub_buffer(&mut self, region_idx: usize) -> OclResult<()> {
+        let mut sbuf = {
+            let region = &self.regions[region_idx];
+            self.buffer.create_sub_buffer(region.flags, region.origin as usize,
+                region.len as usize)?
+        };
+
+        let region = &mut self.regions[region_idx];
+        if let Some(old_sbuf) = self.sub_buffers.get(&region.buffer_id) {
+            if let Some(queue) = old_sbuf.default_queue() {
+                sbuf.set_default_queue(queue.clone());
+            }
+        }
+
+        self.sub_buffers.insert(region.buffer_id, sbuf);
+        region.generation += 1;
+        self.stats.relocation_count += 1;
+        Ok(())
     }
 
     /// Allocates space for and creates a new sub-buffer then returns the
     /// buffer id which can be used to `::get` or `::free` it.
-    pub fn alloc(&mut self, len: u32, flags: Option<MemFlags>) -> Result<usize, ()> {
-        assert!(self.regions.len() == self.sub_buffers.len());
-
-        match self.regions.front() {
-            Some(_) => {
-                let mut end_prev = 0;
-                let mut create_at = None;
-
-                for (region_idx, region) in self.regions.iter().enumerate() {
-                    if region.origin - end_prev >= len {
-                        create_at = Some(region_idx);
-                        break;
-                    } else {
-                        end_prev = self.next_valid_align(region.origin + region.len);
-                    }
-                }
-
-                if let Some(region_idx) = create_at {
-                    Ok(self.create_sub_buffer(region_idx, flags, end_prev, len))
-                } else if self.buffer.len() as u32 - end_prev >= len {
-                    let region_idx = self.regions.len();
-                    Ok(self.create_sub_buffer(region_idx, flags, end_prev, len))
-                } else {
-                    Err(())
-                }
+    ///
+    /// The smallest free region large enough is used.
+    pub fn alloc(&mut self, len: u32, flags: Option<MemFlags>) -> Result<usize, SubBufferPoolError> {
+        debug_assert!(self.regions.len() == self.sub_buffers.len());
+
+        if len == 0 { return Err(SubBufferPoolError::ZeroLength); }
+
+        let free_regions = self.free_regions();
+        let best_fit = free_regions.iter()
+            .filter(|&&(_, _, free_len)| free_len >= len)
+            .min_by_key(|&&(_, origin, free_len)| (free_len, origin));
+
+        match best_fit {
+            Some(&(region_idx, origin, _)) => {
+                let buffer_id = self.create_sub_buffer(region_idx, flags, origin, len)?;
+                self.stats.alloc_count += 1;
+                self.stats.peak_used = cmp::max(self.stats.peak_used, self.used());
+                Ok(buffer_id)
             },
             None => {
-                Ok(self.create_sub_buffer(0, flags, 0, len))
+                self.stats.failed_alloc_count += 1;
+                Err(SubBufferPoolError::OutOfSpace { len: len, largest_free: free_regions.iter()
+                    .map(|&(_, _, free_len)| free_len).max().unwrap_or(0) })
             },
         }
     }
@@ -107,20 +290,16 @@ impl<T: OclPrm> SubBufferPool<T> {
     /// Deallocates the buffer identified by `buffer_id` or returns it back in
     /// the event of a failure.
     pub fn free(&mut self, buffer_id: usize) -> Result<(), usize> {
-        let mut region_idx = None;
-
         // The `SubBuffer` drops here when it goes out of scope:
-        if let Some(_) = self.sub_buffers.remove(&buffer_id) {
-            region_idx = self.regions.iter().position(|r| r.buffer_id == buffer_id);
-        }
+        if self.sub_buffers.remove(&buffer_id).is_none() { return Err(buffer_id); }
 
-        if let Some(r_idx) = region_idx {
-            let mut tail = self.regions.split_off(r_idx);
-            tail.pop_front().ok_or(buffer_id)   ?;
-            self.regions.append(&mut tail);
-            Ok(())
-        } else {
-            Err(buffer_id)
+        match self.regions.iter().position(|r| r.buffer_id == buffer_id) {
+            Some(region_idx) => {
+                self.regions.remove(region_idx);
+                self.stats.free_count += 1;
+                Ok(())
+            },
+            None => Err(buffer_id),
         }
     }
 
@@ -135,29 +314,154 @@ impl<T: OclPrm> SubBufferPool<T> {
         self.sub_buffers.get_mut(&buffer_id)
     }
 
-    /// Defragments the buffer. Be sure to `::finish()` any and all command
-    /// queues you may be using before doing this.
+    /// Returns a handle to the current generation of the sub-buffer
+    /// identified by `buffer_id`.
+    pub fn handle(&self, buffer_id: usize) -> Option<SubBufferHandle> {
+        self.regions.iter().find(|r| r.buffer_id == buffer_id).map(|r| {
+            SubBufferHandle { buffer_id: buffer_id, generation: r.generation }
+        })
+    }
+
+    /// Returns true if the sub-buffer identified by `handle` is still
+    /// allocated and has not been relocated since the handle was created.
+    pub fn is_current(&self, handle: SubBufferHandle) -> bool {
+        self.handle(handle.buffer_id) == Some(handle)
+    }
+
+    /// Returns a reference to the sub-buffer identified by `handle` if it is
+    /// current (see `::is_current`).
+    pub fn get_current(&self, handle: SubBufferHandle) -> Option<&Buffer<T>> {
+        if self.is_current(handle) { self.get(handle.buffer_id) } else { None }
+    }
+
+    /// Returns the total length of all allocated sub-buffers.
+    fn used(&self) -> u32 {
+        self.regions.iter().map(|r| r.len).sum()
+    }
+
+    /// Returns usage statistics.
+    pub fn stats(&self) -> PoolStats {
+        let free_regions = self.free_regions();
+
+        PoolStats {
+            len: self.buffer.len() as u32,
+            used: self.used(),
+            free: free_regions.iter().map(|&(_, _, free_len)| free_len).sum(),
+            largest_free: free_regions.iter().map(|&(_, _, free_len)| free_len).max()
+                .unwrap_or(0),
+            sub_buffer_count: self.regions.len(),
+            .. self.stats
+        }
+    }
+
+    /// Returns the alignment of sub-buffer origins, in elements.
+    pub fn align(&self) -> u32 {
+        self.align
+    }
+
+    /// Returns the length of the pool's buffer.
+    pub fn len(&self) -> u32 {
+        self.buffer.len() as u32
+    }
+
+    /// Defragments the buffer, moving each sub-buffer (and its contents) to
+    /// the lowest available address, then returns the number of sub-buffers
+    /// relocated. Be sure to `::finish()` any and all command queues you may
+    /// be using before doing this. Blocks until the contents have been
+    /// copied.
     ///
-    /// All kernels with a buffer argument set to any of the sub-buffers in
-    /// this pool will need to be created anew or arguments refreshed (use
+    /// All kernels with a buffer argument set to any relocated sub-buffer
+    /// will need to be created anew or arguments refreshed (use
     /// `Kernel::arg_..._named` when initializing arguments in order to change
-    /// them later).
-    #[allow(dead_code)]
-    pub fn defrag(&mut self) {
-        // - Iterate through sub-buffers, dropping and recreating at the
-        //   leftmost (lowest) available address within the buffer, optionally
-        //   copying old data to the new position in device memory.
-        // - Rebuild regions list (or just update offsets).
-        unimplemented!();
-    }
-
-    /// Shrinks or grows and defragments the main buffer. Invalidates all
-    /// kernels referencing sub-buffers in this pool. See `::defrag` for more
-    /// information.
-    #[allow(dead_code, unused_variables)]
-    pub fn resize(&mut self, len: u32) {
-        // Allocate a new buffer then copy old buffer contents one sub-buffer
-        // at a time, defragmenting in the process.
-        unimplemented!();
+    /// them later). Use `::is_current` to determine whether a sub-buffer has
+    /// been relocated.
+    ///
+    /// Sub-buffer contents may be partially moved in the event of an error.
+    pub fn defrag(&mut self) -> Result<usize, SubBufferPoolError> {
+        let moves: Vec<(usize, u32)> = self.packed_origins().into_iter().enumerate()
+            .filter(|&(region_idx, origin)| self.regions[region_idx].origin != origin)
+            .collect();
+
+        if moves.is_empty() { return Ok(0); }
+
+        // Regions only ever move toward the start of the buffer. Those moving
+        // by less than their length overlap their new position and must be
+        // copied through a staging buffer:
+        let staging_len = moves.iter()
+            .map(|&(region_idx, origin)| (&self.regions[region_idx], origin))
+            .filter(|&(region, origin)| region.origin - origin < region.len)
+            .map(|(region, _)| region.len)
+            .max();
+
+        let staging = match staging_len {
+            Some(len) => Some(Buffer::<T>::builder()
+                .queue(self.queue.clone())
+                .len(len as usize)
+                .build()?),
+            None => None,
+        };
+
+        for &(region_idx, origin) in moves.iter() {
+            let (src_origin, len) = (self.regions[region_idx].origin, self.regions[region_idx].len);
+
+            match staging {
+                Some(ref staging) if src_origin - origin < len => {
+                    self.buffer.copy(staging, Some(0), Some(len as usize))
+                        .offset(src_origin as usize).enq()?;
+                    staging.copy(&self.buffer, Some(origin as usize), Some(len as usize)).enq()?;
+                },
+                _ => {
+                    self.buffer.copy(&self.buffer, Some(origin as usize), Some(len as usize))
+                        .offset(src_origin as usize).enq()?;
+                },
+            }
+        }
+
+        self.queue.finish()?;
+
+        for &(region_idx, origin) in moves.iter() {
+            self.regions[region_idx].origin = origin;
+            self.recreate_sub_buffer(region_idx)?;
+        }
+
+        Ok(moves.len())
+    }
+
+    /// Shrinks or grows and defragments the main buffer, copying the contents
+    /// of each sub-buffer. Blocks until the contents have been copied.
+    ///
+    /// Every sub-buffer is relocated. See `::defrag` for more information.
+    pub fn resize(&mut self, len: u32) -> Result<(), SubBufferPoolError> {
+        if len == 0 { return Err(SubBufferPoolError::ZeroLength); }
+
+        let origins = self.packed_origins();
+        let required = match self.regions.last() {
+            Some(region) => origins[origins.len() - 1] + region.len,
+            None => 0,
+        };
+        if len < required {
+            return Err(SubBufferPoolError::LenTooSmall { len: len, required: required });
+        }
+
+        let buffer = Buffer::<T>::builder()
+            .queue(self.queue.clone())
+            .flags(pool_flags())
+            .len(len as usize)
+            .build()?;
+
+        for (region, &origin) in self.regions.iter().zip(origins.iter()) {
+            self.buffer.copy(&buffer, Some(origin as usize), Some(region.len as usize))
+                .offset(region.origin as usize).enq()?;
+        }
+
+        self.queue.finish()?;
+        self.buffer = buffer;
+
+        for (region_idx, origin) in origins.into_iter().enumerate() {
+            self.regions[region_idx].origin = origin;
+            self.recreate_sub_buffer(region_idx)?;
+        }
+
+        Ok(())
     }
 }
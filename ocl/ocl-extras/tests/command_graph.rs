@@ -0,0 +1,153 @@
+//! Tests recording and running commands using a `CommandGraph`.
+
+extern crate ocl;
+extern crate ocl_extras;
+
+use ocl::{ProQue, Program, Queue, Buffer};
+use ocl_extras::{CommandGraph, CommandGraphError, Command, CommandDetails};
+
+const WORK_SIZE: usize = 1 << 20;
+
+static SRC: &'static str = r#"
+    __kernel void add(__global const float* src, __global float* dst, float addend) {
+        uint idx = get_global_id(0);
+        dst[idx] = src[idx] + addend;
+    }
+"#;
+
+/// Returns a `ProQue` with argument info available for its kernels.
+fn pro_que() -> ProQue {
+    ProQue::builder()
+        .prog_bldr(Program::builder().src(SRC).cmplr_opt("-cl-kernel-arg-info"))
+        .dims(WORK_SIZE)
+        .build().unwrap()
+}
+
+/// Returns two queues sharing the context and device of `pro_que`.
+fn two_queues(pro_que: &ProQue) -> Vec<Queue> {
+    (0..2).map(|_| Queue::new(pro_que.context(), pro_que.queue().device(), None).unwrap())
+        .collect()
+}
+
+#[test]
+fn command_graph_dependencies() {
+    let pro_que = pro_que();
+    let buf_a = pro_que.create_buffer::<f32>().unwrap();
+    let buf_b = pro_que.create_buffer::<f32>().unwrap();
+    let buf_c = pro_que.create_buffer::<f32>().unwrap();
+    let src = vec![1.0f32; WORK_SIZE];
+    let (mut dst_b, mut dst_a) = (vec![0.0f32; WORK_SIZE], vec![0.0f32; WORK_SIZE]);
+
+    let mut graph = CommandGraph::new();
+    assert_eq!(graph.record(buf_a.write(&src)).unwrap(), 0);
+    graph.record(buf_a.copy(&buf_b, None, None)).unwrap();
+    graph.record(buf_b.read(&mut dst_b)).unwrap();
+    // Must follow both the write (write-after-write) and the copy
+    // (write-after-read):
+    graph.record(buf_a.write(&src)).unwrap();
+    graph.record(buf_a.read(&mut dst_a)).unwrap();
+    // Independent of every other command:
+    graph.record(buf_c.cmd().fill(0.0, None)).unwrap();
+
+    assert_eq!(graph.dependencies(),
+        vec![vec![], vec![0], vec![1], vec![0, 1], vec![3], vec![]]);
+    assert_eq!(graph.topological_order(), vec![0, 5, 1, 2, 3, 4]);
+
+    assert_eq!(graph.to_dot(), "digraph CommandGraph {\n\
+        \x20   n0 [label=\"0: buffer write\"];\n\
+        \x20   n1 [label=\"1: buffer copy\"];\n\
+        \x20   n2 [label=\"2: buffer read\"];\n\
+        \x20   n3 [label=\"3: buffer write\"];\n\
+        \x20   n4 [label=\"4: buffer read\"];\n\
+        \x20   n5 [label=\"5: buffer fill\"];\n\
+        \x20   n0 -> n1;\n\
+        \x20   n1 -> n2;\n\
+        \x20   n0 -> n3;\n\
+        \x20   n1 -> n3;\n\
+        \x20   n3 -> n4;\n\
+        }\n");
+}
+
+#[test]
+fn command_graph_run() {
+    let pro_que = pro_que();
+    let buf_a = pro_que.create_buffer::<f32>().unwrap();
+    let buf_b = pro_que.create_buffer::<f32>().unwrap();
+    let kernel = pro_que.create_kernel("add").unwrap()
+        .arg_buf(&buf_a)
+        .arg_buf(&buf_b)
+        .arg_scl(10.0f32);
+
+    let (src_0, src_1) = (vec![1.0f32; WORK_SIZE], vec![2.0f32; WORK_SIZE]);
+
+    for queues in &[vec![pro_que.queue().clone()], two_queues(&pro_que)] {
+        let (mut dst_b, mut dst_a) = (vec![0.0f32; WORK_SIZE], vec![0.0f32; WORK_SIZE]);
+
+        {
+            let mut graph = CommandGraph::new();
+            graph.record(buf_a.write(&src_0)).unwrap();
+            // Reads `buf_a` after it is written (read-after-write):
+            let kernel_idx = graph.record(kernel.cmd()).unwrap();
+            graph.record(buf_b.read(&mut dst_b)).unwrap();
+            // Must not overwrite `buf_a` until the kernel has read it
+            // (write-after-read):
+            graph.record(buf_a.write(&src_1)).unwrap();
+            graph.record(buf_a.read(&mut dst_a)).unwrap();
+
+            let dependencies = graph.dependencies();
+            assert!(dependencies[kernel_idx].contains(&0));
+            assert!(dependencies[3].contains(&kernel_idx));
+
+            let finish_events = unsafe { graph.run(queues).unwrap() };
+            finish_events.wait_for().unwrap();
+
+            // Recorded commands can only be run once:
+            match unsafe { graph.run(queues) } {
+                Err(CommandGraphError::CommandNotRecorded { cmd_idx: 0 }) => (),
+                res => panic!("Unexpected result: {:?}", res.map(|_| ())),
+            }
+        }
+
+        assert!(dst_b.iter().all(|&v| v == 11.0));
+        assert!(dst_a.iter().all(|&v| v == 2.0));
+    }
+}
+
+#[test]
+fn command_graph_errors() {
+    let pro_que = pro_que();
+    let buffer: Buffer<f32> = pro_que.create_buffer().unwrap();
+
+    let mut graph = CommandGraph::new();
+
+    // Buffer commands without an operation can not be recorded:
+    match graph.record(buffer.cmd()) {
+        Err(CommandGraphError::UnsupportedBufferCmd { .. }) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+
+    match unsafe { graph.run(&[]) } {
+        Err(CommandGraphError::NoQueues) => (),
+        res => panic!("Unexpected result: {:?}", res.map(|_| ())),
+    }
+
+    // Commands added (rather than recorded) can not be run:
+    graph.add(Command::new(CommandDetails::Fill { target: 0 })).unwrap();
+    let err = match unsafe { graph.run(&[pro_que.queue().clone()]) } {
+        Err(err @ CommandGraphError::CommandNotRecorded { cmd_idx: 0 }) => err,
+        res => panic!("Unexpected result: {:?}", res.map(|_| ())),
+    };
+
+    // The error is preserved when converted into an `ocl::Error`:
+    let err = ocl::Error::from(err);
+    match err.external_ref::<CommandGraphError>() {
+        Some(&CommandGraphError::CommandNotRecorded { cmd_idx: 0 }) => (),
+        other => panic!("Unexpected error: {:?}", other),
+    }
+
+    graph.populate_requisites();
+    match graph.record(buffer.cmd().fill(0.0, None)) {
+        Err(CommandGraphError::Locked) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+}
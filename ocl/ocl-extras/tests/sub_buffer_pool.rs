@@ -0,0 +1,202 @@
+//! Tests allocation, defragmentation, and resizing using a `SubBufferPool`.
+
+extern crate ocl;
+extern crate ocl_extras;
+
+use ocl::{Context, Queue};
+use ocl_extras::{SubBufferPool, SubBufferPoolError, PoolStats};
+
+/// Returns a new pool with a length of `len_aligns` times its alignment.
+fn pool(len_aligns: u32) -> SubBufferPool<f32> {
+    let context = Context::builder().build().unwrap();
+    let queue = Queue::new(&context, context.devices()[0], None).unwrap();
+    let align = SubBufferPool::<f32>::new(1, queue.clone()).unwrap().align();
+    SubBufferPool::new(len_aligns * align, queue).unwrap()
+}
+
+/// Fills the sub-buffer identified by `buffer_id` with values beginning at
+/// `start`.
+fn fill(pool: &SubBufferPool<f32>, buffer_id: usize, start: f32) {
+    let buffer = pool.get(buffer_id).unwrap();
+    let vec: Vec<f32> = (0..buffer.len()).map(|i| start + i as f32).collect();
+    buffer.write(&vec).enq().unwrap();
+}
+
+/// Verifies the contents of a sub-buffer filled using `fill`.
+fn verify(pool: &SubBufferPool<f32>, buffer_id: usize, start: f32) {
+    let buffer = pool.get(buffer_id).unwrap();
+    let mut vec = vec![0.0f32; buffer.len()];
+    buffer.read(&mut vec).enq().unwrap();
+    for (i, &val) in vec.iter().enumerate() {
+        assert_eq!(val, start + i as f32);
+    }
+}
+
+/// Returns the origin of a sub-buffer in units of the pool's alignment.
+fn origin(pool: &SubBufferPool<f32>, buffer_id: usize) -> u32 {
+    pool.get(buffer_id).unwrap().offset().unwrap() as u32 / pool.align()
+}
+
+#[test]
+fn sub_buffer_pool_alloc() {
+    let mut pool = pool(16);
+    let a = pool.align();
+
+    let ids: Vec<usize> = [2, 1, 4, 1].iter().map(|&len| pool.alloc(len * a, None).unwrap())
+        .collect();
+    let origins: Vec<u32> = ids.iter().map(|&id| origin(&pool, id)).collect();
+    assert_eq!(origins, [0, 2, 3, 7]);
+
+    // The smallest free region large enough is used:
+    pool.free(ids[1]).unwrap();
+    pool.free(ids[3]).unwrap();
+    let id = pool.alloc(a, None).unwrap();
+    assert_eq!(origin(&pool, id), 2);
+    let id = pool.alloc(2 * a, None).unwrap();
+    assert_eq!(origin(&pool, id), 7);
+
+    match pool.alloc(0, None) {
+        Err(SubBufferPoolError::ZeroLength) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn sub_buffer_pool_defrag() {
+    let mut pool = pool(16);
+    let a = pool.align();
+
+    let p = pool.alloc(4 * a, None).unwrap();
+    let q = pool.alloc(a, None).unwrap();
+    let r = pool.alloc(6 * a, None).unwrap();
+    fill(&pool, q, 100.0);
+    fill(&pool, r, 200.0);
+    let (q_handle, r_handle) = (pool.handle(q).unwrap(), pool.handle(r).unwrap());
+
+    pool.free(p).unwrap();
+    assert_eq!(pool.stats().largest_free, 5 * a);
+
+    // `q` moves further than its length and is copied directly. `r` overlaps
+    // its new position and is copied through a staging buffer:
+    assert_eq!(pool.defrag().unwrap(), 2);
+    assert_eq!((origin(&pool, q), origin(&pool, r)), (0, 1));
+    verify(&pool, q, 100.0);
+    verify(&pool, r, 200.0);
+
+    assert!(!pool.is_current(q_handle) && !pool.is_current(r_handle));
+    assert_eq!(pool.handle(q).unwrap().generation(), q_handle.generation() + 1);
+    assert_eq!(pool.stats().largest_free, 9 * a);
+    assert_eq!(pool.stats().relocation_count, 2);
+
+    // Already packed:
+    assert_eq!(pool.defrag().unwrap(), 0);
+}
+
+#[test]
+fn sub_buffer_pool_handles() {
+    let mut pool = pool(4);
+    let a = pool.align();
+
+    let id = pool.alloc(a, None).unwrap();
+    let handle = pool.handle(id).unwrap();
+    assert_eq!(handle.buffer_id(), id);
+    assert!(pool.is_current(handle));
+    assert!(pool.get_current(handle).is_some());
+
+    pool.free(id).unwrap();
+    assert!(!pool.is_current(handle));
+    assert!(pool.get_current(handle).is_none());
+    assert!(pool.handle(id).is_none());
+    assert_eq!(pool.free(id), Err(id));
+
+    // Space is reused but buffer ids are not:
+    let new_id = pool.alloc(a, None).unwrap();
+    assert!(new_id != id);
+    assert!(!pool.is_current(handle));
+    assert!(pool.get_current(handle).is_none());
+}
+
+#[test]
+fn sub_buffer_pool_resize() {
+    let mut pool = pool(4);
+    let a = pool.align();
+
+    let p = pool.alloc(a, None).unwrap();
+    let q = pool.alloc(2 * a, None).unwrap();
+    fill(&pool, p, 100.0);
+    fill(&pool, q, 200.0);
+
+    // Grown with sub-buffers already packed (origins unchanged):
+    let q_handle = pool.handle(q).unwrap();
+    pool.resize(32 * a).unwrap();
+    assert_eq!(pool.len(), 32 * a);
+    assert_eq!((origin(&pool, p), origin(&pool, q)), (0, 1));
+    verify(&pool, p, 100.0);
+    verify(&pool, q, 200.0);
+    // The sub-buffers are recreated within the new buffer:
+    assert!(!pool.is_current(q_handle));
+
+    // Shrunk, relocating `q`:
+    pool.free(p).unwrap();
+    match pool.resize(a) {
+        Err(SubBufferPoolError::LenTooSmall { len, required }) => {
+            assert_eq!((len, required), (a, 2 * a));
+        },
+        res => panic!("Unexpected result: {:?}", res),
+    }
+    pool.resize(2 * a).unwrap();
+    assert_eq!(pool.len(), 2 * a);
+    assert_eq!(origin(&pool, q), 0);
+    verify(&pool, q, 200.0);
+
+    match pool.resize(0) {
+        Err(SubBufferPoolError::ZeroLength) => (),
+        res => panic!("Unexpected result: {:?}", res),
+    }
+}
+
+#[test]
+fn sub_buffer_pool_stats() {
+    let mut pool = pool(16);
+    let a = pool.align();
+
+    let x = pool.alloc(2 * a, None).unwrap();
+    let y = pool.alloc(a, None).unwrap();
+    let z = pool.alloc(4 * a, None).unwrap();
+    pool.free(y).unwrap();
+
+    let err = pool.alloc(32 * a, None).unwrap_err();
+    match err {
+        SubBufferPoolError::OutOfSpace { len, largest_free } => {
+            assert_eq!((len, largest_free), (32 * a, 9 * a));
+        },
+        ref err => panic!("Unexpected error: {:?}", err),
+    }
+
+    // The error is preserved when converted into an `ocl::Error`:
+    match ocl::Error::from(err).external_ref::<SubBufferPoolError>() {
+        Some(&SubBufferPoolError::OutOfSpace { .. }) => (),
+        other => panic!("Unexpected error: {:?}", other),
+    }
+
+    let stats = pool.stats();
+    assert_eq!(stats, PoolStats {
+        len: 16 * a,
+        used: 6 * a,
+        peak_used: 7 * a,
+        free: 10 * a,
+        largest_free: 9 * a,
+        sub_buffer_count: 2,
+        alloc_count: 3,
+        failed_alloc_count: 1,
+        free_count: 1,
+        relocation_count: 0,
+    });
+    assert!((stats.fragmentation() - 0.1).abs() < 1e-9);
+
+    pool.free(x).unwrap();
+    pool.free(z).unwrap();
+    let stats = pool.stats();
+    assert_eq!((stats.used, stats.peak_used, stats.free_count), (0, 7 * a, 3));
+    assert_eq!(stats.fragmentation(), 0.0);
+}
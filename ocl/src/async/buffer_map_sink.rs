@@ -3,11 +3,22 @@
 #![allow(unused_imports, dead_code)]
 
 use std::ops::{Deref, DerefMut};
+#[cfg(feature = "std_future")]
+use std::future::Future as StdFuture;
+#[cfg(feature = "std_future")]
+use std::pin::Pin;
+#[cfg(feature = "std_future")]
+use std::task::{Context as TaskContext, Poll as StdPoll};
 use futures::{Future, Poll, Async};
 use core::{self, OclPrm, Mem as MemCore, MemMap as MemMapCore,
     MemFlags, MapFlags, ClNullEventPtr, ClWaitListPtr, AsMem};
 use standard::{Event, EventList, Queue, Buffer, ClWaitListPtrEnum, ClNullEventPtrEnum};
-use async::{Error as OclError, Result as OclResult};
+use async::AsyncError;
+#[cfg(feature = "std_future")]
+use async::poll_compat;
+use error::{Error as OclError, Result as OclResult};
+#[cfg(not(feature = "async_block"))]
+use standard::EventReactor;
 
 
 
@@ -117,7 +128,6 @@ pub struct FutureSinkMapGuard<T: OclPrm> {
     unmap_event: Option<Event>,
     buffer: Option<MemCore>,
     queue: Option<Queue>,
-    callback_is_set: bool,
 }
 
 impl<T: OclPrm> FutureSinkMapGuard<T> {
@@ -131,7 +141,6 @@ impl<T: OclPrm> FutureSinkMapGuard<T> {
             unmap_event: None,
             buffer: Some(buffer),
             queue: Some(queue),
-            callback_is_set: false,
         }
     }
 
@@ -153,7 +162,7 @@ impl<T: OclPrm> FutureSinkMapGuard<T> {
             self.unmap_event = Some(uev);
             Ok(self.unmap_event.as_mut().unwrap())
         } else {
-            Err("FutureSinkMapGuard::create_unmap_event: No queue found!".into())
+            Err(AsyncError::UnmapNoQueue { future: "FutureSinkMapGuard" }.into())
         }
     }
 
@@ -200,7 +209,7 @@ impl<T: OclPrm> FutureSinkMapGuard<T> {
                 unsafe { Ok(SinkMapGuard::new(mem_map, self.len, self.unmap_event.take(),
                     buffer, queue)) }
             },
-            _ => Err("FutureSinkMapGuard::create_unmap_event: No queue and/or buffer found!".into()),
+            _ => Err(AsyncError::FutureCompleted { future: "FutureSinkMapGuard" }.into()),
         }
     }
 }
@@ -222,18 +231,15 @@ impl<T> Future for FutureSinkMapGuard<T> where T: OclPrm + 'static {
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
         // println!("Polling FutureSinkMapGuard...");
-        match self.map_event.is_complete() {
+        match self.map_event.poll_complete() {
             Ok(true) => {
                 self.resolve().map(|mm| Async::Ready(mm))
             }
             Ok(false) => {
-                if !self.callback_is_set {
-                    self.map_event.set_unpark_callback()?;
-                    self.callback_is_set = true;
-                }
+                EventReactor::global().register(&self.map_event)?;
                 Ok(Async::NotReady)
             },
-            Err(err) => Err(err.into()),
+            Err(err) => Err(err),
         }
     }
 }
@@ -246,12 +252,21 @@ impl<T: OclPrm> Future for FutureSinkMapGuard<T> {
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
         // println!("Polling FutureSinkMapGuard...");
-        let _ = self.callback_is_set;
         self.map_event.wait_for()?;
         self.resolve().map(|mm| Async::Ready(mm))
     }
 }
 
+#[cfg(feature = "std_future")]
+impl<T: OclPrm> StdFuture for FutureSinkMapGuard<T> {
+    type Output = OclResult<SinkMapGuard<T>>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext) -> StdPoll<Self::Output> {
+        poll_compat(self.get_mut(), cx)
+    }
+}
+
 
 /// A flush command builder.
 ///
@@ -354,7 +369,7 @@ impl<'c, T> SinkMapCmd<'c, T> where T: OclPrm {
 
     /// Enqueues this command.
     pub fn enq(mut self) -> OclResult<FutureSinkMapGuard<T>> {
-        let buffer_core = self.sink.buffer.core().clone();
+        let buffer_core = self.sink.buffer.as_core().clone();
 
         let map_queue = match self.map_queue {
             Some(q) => q,
@@ -414,7 +429,7 @@ impl<T: OclPrm> BufferMapSink<T> {
         let buffer = Buffer::<T>::builder()
             .queue(queue.clone())
             .flags(MemFlags::new().alloc_host_ptr().host_write_only())
-            .dims(len)
+            .len(len)
             .fill_val(T::default())
             .build()?;
 
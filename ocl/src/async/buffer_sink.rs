@@ -7,6 +7,14 @@ use core::{self, OclPrm, MemMap as MemMapCore,
 use standard::{Event, EventList, Queue, Buffer, ClWaitListPtrEnum, ClNullEventPtrEnum};
 use async::{OrderLock, FutureGuard, ReadGuard, WriteGuard};
 use error::{Error as OclError, Result as OclResult};
+#[cfg(feature = "std_future")]
+use std::future::Future as StdFuture;
+#[cfg(feature = "std_future")]
+use std::pin::Pin;
+#[cfg(feature = "std_future")]
+use std::task::{Context as TaskContext, Poll as StdPoll};
+#[cfg(feature = "std_future")]
+use async::poll_compat;
 
 
 #[must_use = "futures do nothing unless polled"]
@@ -36,6 +44,16 @@ impl<T: OclPrm> Future for FutureFlush<T> {
     }
 }
 
+#[cfg(feature = "std_future")]
+impl<T: OclPrm> StdFuture for FutureFlush<T> {
+    type Output = OclResult<()>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext) -> StdPoll<Self::Output> {
+        poll_compat(self.get_mut(), cx)
+    }
+}
+
 
 /// A flush command builder.
 ///
@@ -1,10 +1,18 @@
 // use std::sync::Arc;
 // use std::sync::atomic::AtomicBool;
 use futures::{Future, Poll, Async};
+#[cfg(feature = "std_future")]
+use std::future::Future as StdFuture;
+#[cfg(feature = "std_future")]
+use std::pin::Pin;
+#[cfg(feature = "std_future")]
+use std::task::{Context as TaskContext, Poll as StdPoll};
 use core::{OclPrm, MemMap as MemMapCore, Mem, ClNullEventPtr};
-use async::MemMap;
+use async::{MemMap, AsyncError};
 use error::{Error as OclError, Result as OclResult};
 use ::{Event, Queue, EventList};
+#[cfg(not(feature = "async_block"))]
+use ::EventReactor;
 
 
 /// A future which resolves to a `MemMap` as soon as its creating command
@@ -21,7 +29,6 @@ pub struct FutureMemMap<T: OclPrm> {
     unmap_event: Option<Event>,
     buffer: Option<Mem>,
     queue: Option<Queue>,
-    callback_is_set: bool,
     // buffer_is_mapped: Option<Arc<AtomicBool>>,
 }
 
@@ -37,7 +44,6 @@ impl<T: OclPrm> FutureMemMap<T> {
             unmap_event: None,
             buffer: Some(buffer),
             queue: Some(queue),
-            callback_is_set: false,
             // buffer_is_mapped: Some(buffer_is_mapped),
         }
     }
@@ -78,7 +84,7 @@ impl<T: OclPrm> FutureMemMap<T> {
             self.unmap_event = Some(uev);
             Ok(self.unmap_event.as_mut().unwrap())
         } else {
-            Err("FutureMemMap::create_unmap_event: No queue found!".into())
+            Err(AsyncError::UnmapNoQueue { future: "FutureMemMap" }.into())
         }
     }
 
@@ -131,7 +137,7 @@ impl<T: OclPrm> FutureMemMap<T> {
                     self.unmap_event.take(), buffer, queue,
                     /*self.buffer_is_mapped.take().unwrap()*/)) }
             },
-            _ => Err("FutureMemMap::create_unmap_event: No queue and/or buffer found!".into()),
+            _ => Err(AsyncError::FutureCompleted { future: "FutureMemMap" }.into()),
         }
     }
 }
@@ -143,19 +149,15 @@ impl<T> Future for FutureMemMap<T> where T: OclPrm + 'static {
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
         // println!("Polling FutureMemMap...");
-        match self.map_event.is_complete() {
+        match self.map_event.poll_complete() {
             Ok(true) => {
                 self.to_mapped_mem().map(|mm| Async::Ready(mm))
             }
             Ok(false) => {
-                if !self.callback_is_set {
-                    self.map_event.set_unpark_callback()?;
-                    self.callback_is_set = true;
-                }
-
+                EventReactor::global().register(&self.map_event)?;
                 Ok(Async::NotReady)
             },
-            Err(err) => Err(err.into()),
+            Err(err) => Err(err),
         }
     }
 }
@@ -168,12 +170,29 @@ impl<T: OclPrm> Future for FutureMemMap<T> {
 
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
         // println!("Polling FutureMemMap...");
-        let _ = self.callback_is_set;
         self.map_event.wait_for()?;
         self.to_mapped_mem().map(|mm| Async::Ready(mm))
     }
 }
 
+#[cfg(feature = "std_future")]
+impl<T: OclPrm> StdFuture for FutureMemMap<T> {
+    type Output = OclResult<MemMap<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext) -> StdPoll<Self::Output> {
+        let this = self.get_mut();
+
+        match StdFuture::poll(Pin::new(&mut this.map_event), cx) {
+            StdPoll::Ready(Ok(())) => StdPoll::Ready(this.to_mapped_mem()),
+            StdPoll::Ready(Err(err)) => StdPoll::Ready(Err(err)),
+            StdPoll::Pending => StdPoll::Pending,
+        }
+    }
+}
+
+// `FutureMemMap` is never structurally pinned.
+impl<T: OclPrm> Unpin for FutureMemMap<T> {}
+
 unsafe impl<T: OclPrm> Send for FutureMemMap<T> {}
 unsafe impl<T: OclPrm> Sync for FutureMemMap<T> {}
 
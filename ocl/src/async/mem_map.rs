@@ -4,6 +4,7 @@ use std::ops::{Deref, DerefMut};
 use core::{self, OclPrm, ClWaitListPtr, ClNullEventPtr, MemMap as MemMapCore, Mem as MemCore, AsMem};
 use standard::{ClWaitListPtrEnum, ClNullEventPtrEnum, Event, EventList, Queue};
 use error::{Result as OclResult};
+use async::AsyncError;
 
 
 /// An unmap command builder.
@@ -202,7 +203,7 @@ impl<T> MemMap<T>  where T: OclPrm {
 
             Ok(())
         } else {
-            Err("ocl_core::- ::unmap: Already unmapped.".into())
+            Err(AsyncError::AlreadyUnmapped.into())
         }
     }
 
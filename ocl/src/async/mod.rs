@@ -8,6 +8,7 @@ mod rw_vec;
 mod mem_map;
 mod buffer_sink;
 mod buffer_stream;
+mod buffer_map_sink;
 
 pub use self::order_lock::{OrderLock, ReadGuard, WriteGuard, FutureGuard, FutureReadGuard,
     FutureWriteGuard, OrderGuard};
@@ -16,6 +17,91 @@ pub use self::mem_map::MemMap;
 pub use self::future_mem_map::FutureMemMap;
 pub use self::buffer_sink::{BufferSink, FutureFlush, Inner as BufferSinkInner};
 pub use self::buffer_stream::{BufferStream, FutureFlood, Inner as BufferStreamInner};
+pub use self::buffer_map_sink::{BufferMapSink, SinkMapCmd, FutureSinkMapGuard, SinkMapGuard};
+
+
+use std::fmt;
+use std::error::Error as StdError;
+#[cfg(feature = "std_future")]
+use std::sync::Arc;
+#[cfg(feature = "std_future")]
+use std::task::{Context as TaskContext, Poll as StdPoll, Waker};
+#[cfg(feature = "std_future")]
+use futures::{Future, Async};
+#[cfg(feature = "std_future")]
+use futures::executor::{self, Notify};
+use error::Error as OclError;
+#[cfg(feature = "std_future")]
+use error::Result as OclResult;
+
+
+/// An error related to futures, guards, or mapped memory.
+#[derive(Debug)]
+pub enum AsyncError {
+    /// A future (named by `future`) was polled after it had already
+    /// completed.
+    FutureCompleted { future: &'static str },
+    /// An unmap event was requested from a future (named by `future`) which
+    /// has no queue available.
+    UnmapNoQueue { future: &'static str },
+    /// Memory was unmapped more than once.
+    AlreadyUnmapped,
+    /// A lock could not be obtained.
+    Lock(Box<OclError>),
+}
+
+impl fmt::Display for AsyncError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AsyncError::FutureCompleted { future } => write!(f, "{} already completed.",
+                future),
+            AsyncError::UnmapNoQueue { future } => write!(f, "{}: No queue found for the unmap \
+                command.", future),
+            AsyncError::AlreadyUnmapped => write!(f, "Already unmapped."),
+            AsyncError::Lock(_) => write!(f, "Unable to obtain lock."),
+        }
+    }
+}
+
+impl StdError for AsyncError {
+    fn source(&self) -> Option<&(StdError + 'static)> {
+        match *self {
+            AsyncError::Lock(ref err) => Some(&**err),
+            _ => None,
+        }
+    }
+}
+
+
+/// Wakes a `std::task::Waker` whenever the futures 0.1 task it stands in
+/// for is notified.
+#[cfg(feature = "std_future")]
+struct WakerNotify(Waker);
+
+#[cfg(feature = "std_future")]
+impl Notify for WakerNotify {
+    fn notify(&self, _id: usize) {
+        self.0.wake_by_ref();
+    }
+}
+
+
+/// Polls a futures 0.1 `future` from within a `std::future::Future`
+/// implementation.
+///
+/// The future is polled within a temporary task which wakes the waker of
+/// `cx` when notified.
+#[cfg(feature = "std_future")]
+fn poll_compat<F>(future: &mut F, cx: &mut TaskContext) -> StdPoll<OclResult<F::Item>>
+        where F: Future<Error = OclError> {
+    let notify = Arc::new(WakerNotify(cx.waker().clone()));
+
+    match executor::spawn(future).poll_future_notify(&notify, 0) {
+        Ok(Async::Ready(item)) => StdPoll::Ready(Ok(item)),
+        Ok(Async::NotReady) => StdPoll::Pending,
+        Err(err) => StdPoll::Ready(Err(err)),
+    }
+}
 
 
 // * TODO: Implement this:
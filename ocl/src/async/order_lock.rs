@@ -14,6 +14,15 @@ use core::{ClContextPtr, ClNullEventPtr};
 use error::{Error as OclError, Result as OclResult};
 use ::{Event, EventList};
 use async::qutex::{QrwLock, QrwRequest, RequestKind};
+use async::AsyncError;
+#[cfg(feature = "std_future")]
+use std::future::Future as StdFuture;
+#[cfg(feature = "std_future")]
+use std::pin::Pin;
+#[cfg(feature = "std_future")]
+use std::task::{Context as TaskContext, Poll as StdPoll};
+#[cfg(feature = "std_future")]
+use async::poll_compat;
 
 
 const PRINT_DEBUG: bool = false;
@@ -689,11 +698,24 @@ impl<V, G> Future for FutureGuard<V, G> where G: OrderGuard<V> {
                 Stage::Upgrade => self.poll_upgrade(),
             }
         } else {
-            Err("FutureGuard::poll: Task already completed.".into())
+            Err(AsyncError::FutureCompleted { future: "FutureGuard" }.into())
         }
     }
 }
 
+#[cfg(feature = "std_future")]
+impl<V, G> StdFuture for FutureGuard<V, G> where G: OrderGuard<V> {
+    type Output = OclResult<G>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext) -> StdPoll<Self::Output> {
+        poll_compat(self.get_mut(), cx)
+    }
+}
+
+// `FutureGuard` is never structurally pinned.
+impl<V, G> Unpin for FutureGuard<V, G> where G: OrderGuard<V> {}
+
 impl<V, G> Drop for FutureGuard<V, G> where G: OrderGuard<V> {
     /// Drops this FutureGuard.
     ///
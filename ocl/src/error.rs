@@ -1,49 +1,127 @@
 //! Standard error type for ocl futures.
 //!
+//! Errors originating within this crate are described by one of several
+//! enums, each wrapped by a variant of `ErrorKind`. Errors originating within
+//! `ocl-core` (including OpenCL API errors) are wrapped by `ErrorKind::OclCore`.
+//! Errors from other crates (such as `ocl-extras`) may be wrapped by
+//! `ErrorKind::External` using `Error::external`.
+//!
 
 use std;
+use std::error::Error as StdError;
 // use std::sync::mpsc::{SendError as StdMpscSendError, RecvError as StdMpscRecvError};
-use failure::{Context, Fail, Backtrace};
 use futures::sync::oneshot::Canceled as OneshotCanceled;
 use futures::sync::mpsc::SendError;
 use core::error::{Error as OclCoreError};
 use core::Status;
-use standard::{DeviceError, PlatformError};
-use ::BufferCmdError;
+
+pub use core::error::{ErrorKind as OclCoreErrorKind, ApiError, ApiWrapperError, VersionLowError,
+    ProgramBuildError, DeviceBuildLogs};
+pub use standard::{DeviceError, PlatformError, ContextError, ProgramError, KernelError,
+    QueueError, EventError, SpatialDimsError, ProQueError, ImageCmdError, ArgType, BaseType,
+    Cardinality};
+#[cfg(feature = "opencl_version_2_0")]
+pub use standard::SvmError;
+pub use async::AsyncError;
+pub use ::BufferCmdError;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
 
-/// An enum containing either a `String` or one of several other error types.
+/// An enum containing one of several error types.
 ///
 /// Implements the usual error traits.
-#[derive(Debug, Fail)]
+#[derive(Debug)]
 pub enum ErrorKind {
-    #[fail(display = "{}", _0)]
     OclCore(OclCoreError),
-    #[fail(display = "{}", _0)]
     FuturesMpscSend(String),
-    // #[fail(display = "{}", _0)]
     // StdMpscSend(String),
-    // #[fail(display = "{}", _0)]
     // StdMpscRecv(StdMpscRecvError),
-    #[fail(display = "{}", _0)]
-    OneshotCanceled(#[cause] OneshotCanceled),
-    #[fail(display = "{}", _0)]
+    OneshotCanceled(OneshotCanceled),
     BufferCmd(BufferCmdError),
-    #[fail(display = "{}", _0)]
+    ImageCmd(ImageCmdError),
+    Kernel(KernelError),
+    Program(ProgramError),
+    ProQue(ProQueError),
+    Context(ContextError),
+    Queue(QueueError),
+    Event(EventError),
+    SpatialDims(SpatialDimsError),
+    #[cfg(feature = "opencl_version_2_0")]
+    Svm(SvmError),
+    Async(AsyncError),
     Device(DeviceError),
-    #[fail(display = "{}", _0)]
-    Platform(PlatformError)
+    Platform(PlatformError),
+    /// An error originating outside of this crate (such as from `ocl-extras`).
+    External(Box<StdError + Send + Sync + 'static>),
+}
+
+impl ErrorKind {
+    /// Returns the wrapped error, if any.
+    fn inner(&self) -> Option<&(StdError + 'static)> {
+        match *self {
+            ErrorKind::OclCore(ref err) => Some(err),
+            ErrorKind::FuturesMpscSend(_) => None,
+            ErrorKind::OneshotCanceled(ref err) => Some(err),
+            ErrorKind::BufferCmd(ref err) => Some(err),
+            ErrorKind::ImageCmd(ref err) => Some(err),
+            ErrorKind::Kernel(ref err) => Some(err),
+            ErrorKind::Program(ref err) => Some(err),
+            ErrorKind::ProQue(ref err) => Some(err),
+            ErrorKind::Context(ref err) => Some(err),
+            ErrorKind::Queue(ref err) => Some(err),
+            ErrorKind::Event(ref err) => Some(err),
+            ErrorKind::SpatialDims(ref err) => Some(err),
+            #[cfg(feature = "opencl_version_2_0")]
+            ErrorKind::Svm(ref err) => Some(err),
+            ErrorKind::Async(ref err) => Some(err),
+            ErrorKind::Device(ref err) => Some(err),
+            ErrorKind::Platform(ref err) => Some(err),
+            ErrorKind::External(ref err) => Some(&**err),
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ErrorKind::FuturesMpscSend(ref desc) => write!(f, "{}", desc),
+            _ => std::fmt::Display::fmt(self.inner().unwrap(), f),
+        }
+    }
+}
+
+impl StdError for ErrorKind {
+    // Each kind is displayed as the error it wraps, so the chain continues
+    // with that error's source.
+    fn source(&self) -> Option<&(StdError + 'static)> {
+        self.inner().and_then(|err| err.source())
+    }
 }
 
 
 /// An Error.
 pub struct Error {
-    inner: Context<ErrorKind>,
+    kind: Box<ErrorKind>,
 }
 
 impl Error {
+    /// Wraps an error originating outside of this crate.
+    ///
+    /// The original error can be recovered using `Error::external_ref` or by
+    /// matching on `ErrorKind::External`.
+    pub fn external<E>(err: E) -> Error where E: StdError + Send + Sync + 'static {
+        ErrorKind::External(Box::new(err)).into()
+    }
+
+    /// Returns a reference to the wrapped external error if it is of type `E`.
+    pub fn external_ref<E: StdError + 'static>(&self) -> Option<&E> {
+        match *self.kind() {
+            ErrorKind::External(ref err) => err.downcast_ref::<E>(),
+            _ => None,
+        }
+    }
+
     /// Returns the error status code for `OclCore` variants.
     pub fn api_status(&self) -> Option<Status> {
         match *self.kind() {
@@ -54,41 +132,42 @@ impl Error {
 
     /// Returns the error variant and contents.
     pub fn kind(&self) -> &ErrorKind {
-        self.inner.get_context()
+        &self.kind
     }
 
-    /// Returns the immediate cause of this error (e.g. the next error in the
-    /// chain).
-    pub fn cause(&self) -> Option<&Fail> {
-        self.inner.cause()
+    /// Consumes this error, returning its variant and contents.
+    pub fn into_kind(self) -> ErrorKind {
+        *self.kind
     }
 }
 
-impl Fail for Error {
-    fn cause(&self) -> Option<&Fail> {
-        self.inner.cause()
-    }
-
-    fn backtrace(&self) -> Option<&Backtrace> {
-        self.inner.backtrace()
+impl StdError for Error {
+    fn source(&self) -> Option<&(StdError + 'static)> {
+        self.kind.source()
     }
 }
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        std::fmt::Display::fmt(&self.inner, f)
+        std::fmt::Display::fmt(&self.kind, f)
     }
 }
 
 impl std::fmt::Debug for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        std::fmt::Display::fmt(&self.inner, f)
+        std::fmt::Display::fmt(&self.kind, f)
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Error {
+        Error { kind: Box::new(kind) }
     }
 }
 
 impl From<OclCoreError> for Error {
     fn from(err: OclCoreError) -> Error {
-        Error { inner: Context::new(ErrorKind::OclCore(err)) }
+        ErrorKind::OclCore(err).into()
     }
 }
 
@@ -96,8 +175,7 @@ impl<T> From<SendError<T>> for Error {
     fn from(err: SendError<T>) -> Error {
         let debug = format!("{:?}", err);
         let display = format!("{}", err);
-        Error { inner: Context::new(ErrorKind::FuturesMpscSend(
-            format!("{}: '{}'", debug, display))) }
+        ErrorKind::FuturesMpscSend(format!("{}: '{}'", debug, display)).into()
     }
 }
 
@@ -118,51 +196,114 @@ impl<T> From<SendError<T>> for Error {
 
 impl From<OneshotCanceled> for Error {
     fn from(err: OneshotCanceled) -> Error {
-        Error { inner: Context::new(ErrorKind::OneshotCanceled(err)) }
+        ErrorKind::OneshotCanceled(err).into()
     }
 }
 
-// TODO: Remove eventually
+// Allows user code to convert strings into an `Error`. Errors created by
+// this crate never use this conversion.
 impl From<String> for Error {
     fn from(desc: String) -> Error {
-        Error { inner: Context::new(ErrorKind::OclCore(desc.into())) }
+        ErrorKind::OclCore(desc.into()).into()
     }
 }
 
-// TODO: Remove eventually
+// Allows user code to convert strings into an `Error`. Errors created by
+// this crate never use this conversion.
 impl<'a> From<&'a str> for Error {
     fn from(desc: &'a str) -> Error {
-        Error { inner: Context::new(ErrorKind::OclCore(desc.into())) }
+        ErrorKind::OclCore(desc.into()).into()
     }
 }
 
 impl From<std::ffi::NulError> for Error {
     fn from(err: std::ffi::NulError) -> Error {
-        Error { inner: Context::new(ErrorKind::OclCore(err.into())) }
+        ErrorKind::OclCore(err.into()).into()
     }
 }
 
 impl From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Error {
-        Error { inner: Context::new(ErrorKind::OclCore(err.into())) }
+        ErrorKind::OclCore(err.into()).into()
     }
 }
 
 impl From<BufferCmdError> for Error {
     fn from(err: BufferCmdError) -> Error {
-        Error { inner: Context::new(ErrorKind::BufferCmd(err)) }
+        ErrorKind::BufferCmd(err).into()
+    }
+}
+
+impl From<ImageCmdError> for Error {
+    fn from(err: ImageCmdError) -> Error {
+        ErrorKind::ImageCmd(err).into()
+    }
+}
+
+impl From<KernelError> for Error {
+    fn from(err: KernelError) -> Error {
+        ErrorKind::Kernel(err).into()
+    }
+}
+
+impl From<ProgramError> for Error {
+    fn from(err: ProgramError) -> Error {
+        ErrorKind::Program(err).into()
+    }
+}
+
+impl From<ProQueError> for Error {
+    fn from(err: ProQueError) -> Error {
+        ErrorKind::ProQue(err).into()
+    }
+}
+
+impl From<ContextError> for Error {
+    fn from(err: ContextError) -> Error {
+        ErrorKind::Context(err).into()
+    }
+}
+
+impl From<QueueError> for Error {
+    fn from(err: QueueError) -> Error {
+        ErrorKind::Queue(err).into()
+    }
+}
+
+impl From<EventError> for Error {
+    fn from(err: EventError) -> Error {
+        ErrorKind::Event(err).into()
+    }
+}
+
+impl From<SpatialDimsError> for Error {
+    fn from(err: SpatialDimsError) -> Error {
+        ErrorKind::SpatialDims(err).into()
+    }
+}
+
+#[cfg(feature = "opencl_version_2_0")]
+impl From<SvmError> for Error {
+    fn from(err: SvmError) -> Error {
+        ErrorKind::Svm(err).into()
+    }
+}
+
+impl From<AsyncError> for Error {
+    fn from(err: AsyncError) -> Error {
+        ErrorKind::Async(err).into()
     }
 }
 
 impl From<DeviceError> for Error {
     fn from(err: DeviceError) -> Error {
-        Error { inner: Context::new(ErrorKind::Device(err)) }
+        ErrorKind::Device(err).into()
     }
 }
 
 impl From<PlatformError> for Error {
     fn from(err: PlatformError) -> Error {
-        Error { inner: Context::new(ErrorKind::Platform(err)) }
+        ErrorKind::Platform(err).into()
     }
 }
 
@@ -174,4 +315,4 @@ impl From<Error> for String {
 
 
 unsafe impl Send for Error {}
-unsafe impl Sync for Error {}
\ No newline at end of file
+unsafe impl Sync for Error {}
@@ -50,8 +50,7 @@
 
 extern crate num_traits;
 extern crate futures;
-#[macro_use]
-extern crate failure;
+#[cfg_attr(not(feature = "async_block"), macro_use)] extern crate lazy_static;
 pub extern crate ocl_core as core;
 
 
@@ -61,8 +60,12 @@ mod standard;
 pub mod error;
 pub mod async;
 
-pub use self::standard::{Platform, Extensions, Device, Context, Program, Queue, Kernel, Buffer, Image, Event,
-    EventList, EventArray, Sampler, SpatialDims, ProQue, BufferCmdError};
+pub use self::standard::{Platform, Extensions, Device, SubDevice, Context, Program, Queue, Kernel,
+    Buffer, Image, Event, EventList, EventArray, Sampler, SpatialDims, ProQue, BufferCmdError,
+    DeviceClock, ProgramCache, Profiler, ProfileReport, CommandStats, DurationStats,
+    TraceRecorder, ArgVal};
+#[cfg(not(feature = "async_block"))]
+pub use self::standard::{EventReactor, ReactorMode};
 pub use self::async::{MemMap, FutureMemMap, RwVec, ReadGuard, WriteGuard,
     FutureReadGuard, FutureWriteGuard};
 pub use error::{Error, Result};
@@ -72,6 +75,11 @@ pub use core::ffi;
 pub use core::util;
 #[doc(no_inline)]
 pub use core::{OclPrm, OclScl, OclVec, DeviceType, CommandQueueProperties, MemFlags, MapFlags};
+#[cfg(feature = "opencl_version_2_0")]
+pub use self::standard::{SvmVec, Pipe};
+#[cfg(feature = "opencl_version_2_0")]
+#[doc(no_inline)]
+pub use core::SvmMemFlags;
 
 
 pub mod prm {
@@ -133,9 +141,11 @@ pub mod builders {
 
     pub use standard::{ContextBuilder, BuildOpt, ProgramBuilder, ImageBuilder, ProQueBuilder,
         DeviceSpecifier, BufferCmdKind, BufferCmdDataShape, BufferCmd, BufferReadCmd,
-        BufferWriteCmd, BufferMapCmd, ImageCmdKind, ImageCmd, KernelCmd, BufferBuilder};
+        BufferWriteCmd, BufferMapCmd, ImageCmdKind, ImageCmd, KernelCmd, NativeKernelCmd,
+        BufferBuilder, QueueBuilder};
     pub use standard::{ClNullEventPtrEnum, ClWaitListPtrEnum};
-    pub use core::{ImageFormat, ImageDescriptor, ContextProperties};
+    pub use core::{ImageFormat, ImageDescriptor, ContextProperties, DevicePartition,
+        QueueProperties};
     // #[cfg(not(release))] pub use standard::BufferTest;
 }
 
@@ -177,7 +187,7 @@ pub mod flags {
 pub mod enums {
     //! Enumerators for settings and information requests.
 
-    pub use standard::{DeviceSpecifier, BufferCmdKind, BufferCmdDataShape};
+    pub use standard::{DeviceSpecifier, BufferCmdKind, BufferCmdDataShape, ProfileKey};
 
     // API enums.
     pub use core::{ImageChannelOrder, ImageChannelDataType, Cbool, Polling, PlatformInfo,
@@ -186,13 +196,15 @@ pub mod enums {
         MemObjectType, MemInfo, ImageInfo, AddressingMode, FilterMode, SamplerInfo, ProgramInfo,
         ProgramBuildInfo, ProgramBuildStatus, KernelInfo, KernelArgInfo, KernelArgAddressQualifier,
         KernelArgAccessQualifier, KernelWorkGroupInfo, EventInfo, CommandType,
-        CommandExecutionStatus, BufferCreateType, ProfilingInfo};
+        CommandExecutionStatus, BufferCreateType, ProfilingInfo, QueuePriority, QueueThrottle,
+        KernelSubGroupInfo};
 
     // Custom enums.
     pub use core::{KernelArg, ContextPropertyValue, PlatformInfoResult, DeviceInfoResult,
         ContextInfoResult, CommandQueueInfoResult, MemInfoResult, ImageInfoResult,
         SamplerInfoResult, ProgramInfoResult, ProgramBuildInfoResult, KernelInfoResult,
-        KernelArgInfoResult, KernelWorkGroupInfoResult, EventInfoResult, ProfilingInfoResult};
+        KernelArgInfoResult, KernelWorkGroupInfoResult, KernelSubGroupInfoResult, EventInfoResult,
+        ProfilingInfoResult};
 
     // Error status.
     pub use core::Status;
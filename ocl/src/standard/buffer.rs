@@ -8,7 +8,8 @@ use core::{self, Error as OclCoreError, Result as OclCoreResult, OclPrm, Mem as
     ClNullEventPtr};
 use ::{Context, Queue, FutureMemMap, MemMap, Event, RwVec, FutureReadGuard, FutureWriteGuard,
     SpatialDims};
-use standard::{ClNullEventPtrEnum, ClWaitListPtrEnum};
+use async::AsyncError;
+use standard::{ClNullEventPtrEnum, ClWaitListPtrEnum, Profiler, ProfileKey, ProfiledEnew};
 use error::{Error as OclError, Result as OclResult};
 
 #[cfg(not(feature="opencl_vendor_mesa"))]
@@ -17,10 +18,9 @@ use ffi::cl_GLuint;
 
 fn check_len(mem_len: usize, data_len: usize, offset: usize) -> OclResult<()> {
     if offset >= mem_len {
-        Err(format!("ocl::Buffer::enq(): Offset out of range. \
-            (mem_len: {}, data_len: {}, offset: {}", mem_len, data_len, offset).into())
+        Err(BufferCmdError::OffsetOutOfRange { mem_len, data_len, offset }.into())
     } else if data_len > (mem_len - offset) {
-        Err("ocl::Buffer::enq(): Data length exceeds buffer length.".into())
+        Err(BufferCmdError::DataLenExceedsBuffer { mem_len, data_len, offset }.into())
     } else {
         Ok(())
     }
@@ -28,20 +28,71 @@ fn check_len(mem_len: usize, data_len: usize, offset: usize) -> OclResult<()> {
 
 
 /// A buffer command error.
-#[derive(Debug, Fail)]
+#[derive(Debug)]
 pub enum BufferCmdError {
-    #[fail(display = "A rectangular map is not a valid operation. \
-        Please use the default shape, linear.")]
     RectUnavailable,
-    #[fail(display = "No queue specified.")]
     NoQueue,
-    #[fail(display = "Buffer already mapped.")]
     AlreadyMapped,
-    #[fail(display = "Unable to map this buffer. Must create with either the \
-        MEM_USE_HOST_PTR or MEM_ALLOC_HOST_PTR flag.")]
     MapUnavailable,
-    #[fail(display = "ocl-core error: {}", _0)]
-    Ocl(#[cause] OclCoreError)
+    OffsetOutOfRange { mem_len: usize, data_len: usize, offset: usize },
+    DataLenExceedsBuffer { mem_len: usize, data_len: usize, offset: usize },
+    RectCopyOffsetLen,
+    RectFillUnavailable,
+    NoOperation,
+    DataRangeOutOfBounds { range_end: usize, data_len: usize },
+    AsyncDataNotRwVec,
+    SubBufferOriginOutOfRange { origin: usize, buffer_len: usize },
+    SubBufferRegionOutOfRange { origin: usize, len: usize, buffer_len: usize },
+    Ocl(OclCoreError)
+}
+
+impl std::fmt::Display for BufferCmdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            BufferCmdError::RectUnavailable => write!(f, "A rectangular map is not a valid \
+                operation. Please use the default shape, linear."),
+            BufferCmdError::NoQueue => write!(f, "No queue specified."),
+            BufferCmdError::AlreadyMapped => write!(f, "Buffer already mapped."),
+            BufferCmdError::MapUnavailable => write!(f, "Unable to map this buffer. Must create \
+                with either the MEM_USE_HOST_PTR or MEM_ALLOC_HOST_PTR flag."),
+            BufferCmdError::OffsetOutOfRange { mem_len, data_len, offset } => write!(f,
+                "Offset out of range. (mem_len: {}, data_len: {}, offset: {})",
+                mem_len, data_len, offset),
+            BufferCmdError::DataLenExceedsBuffer { mem_len, data_len, offset } => write!(f,
+                "Data length exceeds buffer length. (mem_len: {}, data_len: {}, offset: {})",
+                mem_len, data_len, offset),
+            BufferCmdError::RectCopyOffsetLen => write!(f, "For 'rect' shaped copies, \
+                destination offset and length must be 'None'. Ex.: \
+                'cmd().copy(&{{buf_name}}, None, None)..'."),
+            BufferCmdError::RectFillUnavailable => write!(f, "Rectangular fill is not a valid \
+                operation. Please use the default shape, linear."),
+            BufferCmdError::NoOperation => write!(f, "No operation specified. Use '.read(...)', \
+                'write(...)', etc. before calling '.enq()'."),
+            BufferCmdError::DataRangeOutOfBounds { range_end, data_len } => write!(f,
+                "Invalid src_offset and/or len. (range end: {}, data len: {})",
+                range_end, data_len),
+            BufferCmdError::AsyncDataNotRwVec => write!(f, "Invalid data container kind for an \
+                asynchronous enqueue. The data must be a 'RwVec'."),
+            BufferCmdError::SubBufferOriginOutOfRange { origin, buffer_len } => write!(f,
+                "Sub-buffer origin ({}) is outside of the dimensions of the source buffer ({}).",
+                origin, buffer_len),
+            BufferCmdError::SubBufferRegionOutOfRange { origin, len, buffer_len } => write!(f,
+                "Sub-buffer region (origin: '{}', len: '{}') exceeds the dimensions of the \
+                source buffer ({}).", origin, len, buffer_len),
+            BufferCmdError::Ocl(ref err) => write!(f, "ocl-core error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for BufferCmdError {
+    fn source(&self) -> Option<&(std::error::Error + 'static)> {
+        match *self {
+            // Displayed along with this error, so the chain continues with
+            // its source.
+            BufferCmdError::Ocl(ref err) => err.source(),
+            _ => None,
+        }
+    }
 }
 
 
@@ -169,6 +220,8 @@ pub struct BufferCmd<'c, T> where T: 'c + OclPrm {
     shape: BufferCmdDataShape,
     ewait: Option<ClWaitListPtrEnum<'c>>,
     enew: Option<ClNullEventPtrEnum<'c>>,
+    profiler: Option<&'c Profiler>,
+    label: Option<String>,
     mem_len: usize,
 }
 
@@ -187,10 +240,18 @@ impl<'c, T> BufferCmd<'c, T> where T: 'c + OclPrm {
             shape: BufferCmdDataShape::Lin { offset: 0 },
             ewait: None,
             enew: None,
+            profiler: None,
+            label: None,
             mem_len,
         }
     }
 
+    /// Returns the profiler to record this command with: the one specified
+    /// using `::profiler`, if any, otherwise that of the queue.
+    fn active_profiler(&self) -> Option<&'c Profiler> {
+        self.profiler.or_else(|| self.queue.and_then(|q| q.profiler()))
+    }
+
     /// Specifies that this command will be a read operation.
     ///
     /// After calling this method, the blocking state of this command will
@@ -515,14 +576,47 @@ impl<'c, T> BufferCmd<'c, T> where T: 'c + OclPrm {
         self
     }
 
+    /// Specifies a profiler to record this command with.
+    ///
+    /// Overrides the profiler of the queue (see `Queue::set_profiler`) if
+    /// one is set. The queue used must have been created with profiling
+    /// enabled.
+    pub fn profiler<'p>(mut self, profiler: &'p Profiler) -> BufferCmd<'c, T>
+            where 'p: 'c {
+        self.profiler = Some(profiler);
+        self
+    }
+
+    /// Specifies a label identifying this command in traces (see
+    /// `TraceRecorder`).
+    ///
+    /// Defaults to the kind of command (`"buffer:read"`, etc.).
+    pub fn label<S: Into<String>>(mut self, label: S) -> BufferCmd<'c, T> {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Returns the buffer this command operates on.
+    pub fn buffer(&self) -> &'c Buffer<T> {
+        self.buffer
+    }
+
+    /// Returns the type of operation this command will perform.
+    pub fn kind(&self) -> &BufferCmdKind<'c, T> {
+        &self.kind
+    }
+
     /// Enqueues this command.
     pub fn enq(self) -> OclResult<()> {
         let queue = match self.queue {
             Some(q) => q,
-            None => return Err("BufferCmd::enq: No queue set.".into()),
+            None => return Err(BufferCmdError::NoQueue.into()),
         };
 
-        match self.kind {
+        let mut profiled = ProfiledEnew::new(self.active_profiler(), self.enew)
+            .trace(queue.trace_recorder(), queue.as_core(), self.label, self.ewait.as_ref());
+
+        let kind = match self.kind {
             BufferCmdKind::Copy { dst_buffer, dst_offset, len } => {
                 match self.shape {
                     BufferCmdDataShape::Lin { offset } => {
@@ -532,22 +626,22 @@ impl<'c, T> BufferCmd<'c, T> where T: 'c + OclPrm {
 
                         core::enqueue_copy_buffer::<T, _, _, _>(queue,
                             &self.buffer.obj_core, dst_buffer, offset, dst_offset, len,
-                            self.ewait, self.enew).map_err(OclError::from)
+                            self.ewait, profiled.enew())?;
+                        "copy"
                     },
                     BufferCmdDataShape::Rect { src_origin, dst_origin, region,
                         src_row_pitch_bytes, src_slc_pitch_bytes, dst_row_pitch_bytes,
                         dst_slc_pitch_bytes } =>
                     {
-                        if dst_offset.is_some() || len.is_some() { return Err(
-                            "ocl::BufferCmd::enq(): For 'rect' shaped copies, destination \
-                            offset and length must be 'None'. Ex.: \
-                            'cmd().copy(&{{buf_name}}, None, None)..'.".into());
+                        if dst_offset.is_some() || len.is_some() {
+                            return Err(BufferCmdError::RectCopyOffsetLen.into());
                         }
 
                         core::enqueue_copy_buffer_rect::<T, _, _, _>(queue, &self.buffer.obj_core,
                             dst_buffer, src_origin, dst_origin, region, src_row_pitch_bytes,
                             src_slc_pitch_bytes, dst_row_pitch_bytes, dst_slc_pitch_bytes,
-                            self.ewait, self.enew).map_err(OclError::from)
+                            self.ewait, profiled.enew())?;
+                        "copy_rect"
                     },
                 }
             },
@@ -564,32 +658,34 @@ impl<'c, T> BufferCmd<'c, T> where T: 'c + OclPrm {
                         check_len(self.mem_len, len, offset)?;
 
                         core::enqueue_fill_buffer(queue, &self.buffer.obj_core, pattern,
-                            offset, len, self.ewait, self.enew, Some(&queue.device_version()))
-                            .map_err(OclError::from)
+                            offset, len, self.ewait, profiled.enew(), Some(&queue.device_version()))?;
+                        "fill"
                     },
-                    BufferCmdDataShape::Rect { .. } => Err(
-                        "ocl::BufferCmd::enq(): Rectangular fill is not a valid operation. \
-                        Please use the default shape, linear.".into())
+                    BufferCmdDataShape::Rect { .. } => return Err(BufferCmdError::RectFillUnavailable
+                        .into())
                 }
             },
             #[cfg(not(feature="opencl_vendor_mesa"))]
             BufferCmdKind::GLAcquire => {
                 let buf_slc = unsafe { std::slice::from_raw_parts(&self.buffer.obj_core, 1) };
-                core::enqueue_acquire_gl_objects(queue, buf_slc, self.ewait, self.enew).map_err(OclError::from)
+                core::enqueue_acquire_gl_objects(queue, buf_slc, self.ewait, profiled.enew())?;
+                "gl_acquire"
             },
 
             #[cfg(not(feature="opencl_vendor_mesa"))]
             BufferCmdKind::GLRelease => {
                 let buf_slc = unsafe { std::slice::from_raw_parts(&self.buffer.obj_core, 1) };
-                core::enqueue_release_gl_objects(queue, buf_slc, self.ewait, self.enew).map_err(OclError::from)
+                core::enqueue_release_gl_objects(queue, buf_slc, self.ewait, profiled.enew())?;
+                "gl_release"
             },
 
-            BufferCmdKind::Unspecified => Err("ocl::BufferCmd::enq(): \
-                No operation specified. Use '.read(...)', 'write(...)', etc. before calling \
-                '.enq()'.".into()),
+            BufferCmdKind::Unspecified => return Err(BufferCmdError::NoOperation.into()),
             BufferCmdKind::Map { .. } => unreachable!(),
             _ => unimplemented!(),
-        }
+        };
+
+        profiled.finish(ProfileKey::Buffer(kind));
+        Ok(())
     }
 }
 
@@ -835,6 +931,31 @@ impl<'c, 'd, T> BufferReadCmd<'c, 'd, T> where T: OclPrm {
         self
     }
 
+    /// Specifies a profiler to record this command with.
+    ///
+    /// Overrides the profiler of the queue (see `Queue::set_profiler`) if
+    /// one is set. The queue used must have been created with profiling
+    /// enabled.
+    pub fn profiler<'p>(mut self, profiler: &'p Profiler) -> BufferReadCmd<'c, 'd, T>
+            where 'p: 'c {
+        self.cmd.profiler = Some(profiler);
+        self
+    }
+
+    /// Specifies a label identifying this command in traces (see
+    /// `TraceRecorder`).
+    ///
+    /// Defaults to the kind of command (`"buffer:read"`, etc.).
+    pub fn label<S: Into<String>>(mut self, label: S) -> BufferReadCmd<'c, 'd, T> {
+        self.cmd.label = Some(label.into());
+        self
+    }
+
+    /// Returns the buffer this command operates on.
+    pub fn buffer(&self) -> &'c Buffer<T> {
+        self.cmd.buffer
+    }
+
     /// Enqueues this command, blocking the current thread until it is complete.
     ///
     /// If an `RwVec` is being used as the data destination, the current
@@ -845,16 +966,21 @@ impl<'c, 'd, T> BufferReadCmd<'c, 'd, T> where T: OclPrm {
     pub fn enq(mut self) -> OclResult<()> {
         let read_dst = self.dst.take();
         let range = self.range.clone();
-        if range.end > read_dst.len() { return Err(OclError::from(
-            "Unable to enqueue buffer read command: Invalid src_offset and/or len.")) }
+        if range.end > read_dst.len() { return Err(BufferCmdError::DataRangeOutOfBounds {
+            range_end: range.end, data_len: read_dst.len() }.into()) }
 
         let mut enqueue_with_data = |dst: &mut [T]| {
             let queue = match self.cmd.queue {
                 Some(q) => q,
-                None => return Err("BufferReadCmd::enq: No queue set.".into()),
+                None => return Err(BufferCmdError::NoQueue.into()),
             };
 
-            match self.cmd.kind {
+            let mut profiled = ProfiledEnew::new(self.cmd.active_profiler(),
+                    self.cmd.enew.take())
+                .trace(queue.trace_recorder(), queue.as_core(), self.cmd.label.take(),
+                    self.cmd.ewait.as_ref());
+
+            let kind = match self.cmd.kind {
                 BufferCmdKind::Read => {
                     match self.cmd.shape {
                         BufferCmdDataShape::Lin { offset } => {
@@ -862,8 +988,9 @@ impl<'c, 'd, T> BufferReadCmd<'c, 'd, T> where T: OclPrm {
                             unsafe {
                                 core::enqueue_read_buffer(queue, &self.cmd.buffer.obj_core,
                                     self.cmd.block, offset, dst, self.cmd.ewait.take(),
-                                    self.cmd.enew.take()).map_err(OclError::from)
+                                    profiled.enew())?;
                             }
+                            "read"
                         },
                         BufferCmdDataShape::Rect { src_origin, dst_origin, region, src_row_pitch_bytes,
                                 src_slc_pitch_bytes, dst_row_pitch_bytes, dst_slc_pitch_bytes } =>
@@ -874,13 +1001,17 @@ impl<'c, 'd, T> BufferReadCmd<'c, 'd, T> where T: OclPrm {
                                     self.cmd.block, src_origin, dst_origin, region,
                                     src_row_pitch_bytes, src_slc_pitch_bytes, dst_row_pitch_bytes,
                                     dst_slc_pitch_bytes, dst, self.cmd.ewait.take(),
-                                    self.cmd.enew.take()).map_err(OclError::from)
+                                    profiled.enew())?;
                             }
+                            "read_rect"
                         }
                     }
                 },
                 _ => unreachable!(),
-            }
+            };
+
+            profiled.finish(ProfileKey::Buffer(kind));
+            Ok(())
         };
 
         match read_dst {
@@ -889,12 +1020,12 @@ impl<'c, 'd, T> BufferReadCmd<'c, 'd, T> where T: OclPrm {
             },
             ReadDst::RwVec(rw_vec) => {
                 let mut guard = rw_vec.write().wait()
-                    .map_err(|_| OclError::from("Unable to obtain lock."))?;
+                    .map_err(|err| AsyncError::Lock(Box::new(err)))?;
                 enqueue_with_data(&mut guard.as_mut_slice()[range])
             },
             ReadDst::Writer(writer) => {
                 let mut guard = writer.wait()
-                    .map_err(|_| OclError::from("Unable to obtain lock."))?;
+                    .map_err(|err| AsyncError::Lock(Box::new(err)))?;
                 enqueue_with_data(&mut guard.as_mut_slice()[range])
             }
             ReadDst::None => panic!("Invalid read destination."),
@@ -911,21 +1042,24 @@ impl<'c, 'd, T> BufferReadCmd<'c, 'd, T> where T: OclPrm {
     pub fn enq_async(mut self) -> OclResult<FutureWriteGuard<Vec<T>>> {
         let queue = match self.cmd.queue {
             Some(q) => q,
-            None => return Err("BufferCmd::enq: No queue set.".into()),
+            None => return Err(BufferCmdError::NoQueue.into()),
         };
 
+        let profiled = ProfiledEnew::new(self.cmd.active_profiler(), None)
+            .trace(queue.trace_recorder(), queue.as_core(), self.cmd.label.take(),
+                self.cmd.ewait.as_ref());
+
         match self.cmd.kind {
             BufferCmdKind::Read => {
                 let mut writer = match self.dst {
                     ReadDst::RwVec(rw_vec) => rw_vec.write(),
                     ReadDst::Writer(writer) => writer,
-                    _ => return Err("BufferReadCmd::enq_async: Invalid data destination kind for an
-                        asynchronous enqueue. The read destination must be a 'RwVec'.".into()),
+                    _ => return Err(BufferCmdError::AsyncDataNotRwVec.into()),
                 };
                 let writer_len = unsafe { (*writer.as_ptr()).len() };
                 if self.range.end > writer_len {
-                    return Err(OclError::from("Unable to enqueue buffer read command: \
-                        Invalid src_offset and/or len."))
+                    return Err(BufferCmdError::DataRangeOutOfBounds {
+                        range_end: self.range.end, data_len: writer_len }.into())
                 }
 
                 writer.create_lock_event(queue.context_ptr()?)?;
@@ -943,12 +1077,13 @@ impl<'c, 'd, T> BufferReadCmd<'c, 'd, T> where T: OclPrm {
 
                 let mut read_event = Event::empty();
 
-                match self.cmd.shape {
+                let kind = match self.cmd.shape {
                     BufferCmdDataShape::Lin { offset } => {
                         check_len(self.cmd.mem_len, dst.len(), offset)?;
 
                         unsafe { core::enqueue_read_buffer(queue, &self.cmd.buffer.obj_core, false,
                             offset, dst, writer.lock_event(), Some(&mut read_event))?; }
+                        "read"
                     },
                     BufferCmdDataShape::Rect { src_origin, dst_origin, region,
                         src_row_pitch_bytes, src_slc_pitch_bytes,
@@ -958,13 +1093,16 @@ impl<'c, 'd, T> BufferReadCmd<'c, 'd, T> where T: OclPrm {
                             false, src_origin, dst_origin, region, src_row_pitch_bytes,
                             src_slc_pitch_bytes, dst_row_pitch_bytes, dst_slc_pitch_bytes,
                             dst, writer.lock_event(), Some(&mut read_event))?; }
+                        "read_rect"
                     }
-                }
+                };
 
                 if let Some(ref mut enew) = self.cmd.enew.take() {
                     unsafe { enew.clone_from(&read_event) }
                 }
 
+                profiled.record(ProfileKey::Buffer(kind), &read_event);
+
                 writer.set_command_wait_event(read_event);
                 Ok(writer)
             },
@@ -1215,6 +1353,31 @@ impl<'c, 'd, T> BufferWriteCmd<'c, 'd, T> where T: OclPrm {
         self
     }
 
+    /// Specifies a profiler to record this command with.
+    ///
+    /// Overrides the profiler of the queue (see `Queue::set_profiler`) if
+    /// one is set. The queue used must have been created with profiling
+    /// enabled.
+    pub fn profiler<'p>(mut self, profiler: &'p Profiler) -> BufferWriteCmd<'c, 'd, T>
+            where 'p: 'c {
+        self.cmd.profiler = Some(profiler);
+        self
+    }
+
+    /// Specifies a label identifying this command in traces (see
+    /// `TraceRecorder`).
+    ///
+    /// Defaults to the kind of command (`"buffer:read"`, etc.).
+    pub fn label<S: Into<String>>(mut self, label: S) -> BufferWriteCmd<'c, 'd, T> {
+        self.cmd.label = Some(label.into());
+        self
+    }
+
+    /// Returns the buffer this command operates on.
+    pub fn buffer(&self) -> &'c Buffer<T> {
+        self.cmd.buffer
+    }
+
     /// Enqueues this command, blocking the current thread until it is complete.
     ///
     /// If an `RwVec` is being used as the data destination, the current
@@ -1225,16 +1388,21 @@ impl<'c, 'd, T> BufferWriteCmd<'c, 'd, T> where T: OclPrm {
     pub fn enq(mut self) -> OclResult<()> {
         let write_src = self.src.take();
         let range = self.range.clone();
-        if range.end > write_src.len() { return Err(OclError::from(
-            "Unable to enqueue buffer write command: Invalid src_offset and/or len.")) }
+        if range.end > write_src.len() { return Err(BufferCmdError::DataRangeOutOfBounds {
+            range_end: range.end, data_len: write_src.len() }.into()) }
 
         let mut enqueue_with_data = |src: &[T]| {
             let queue = match self.cmd.queue {
                 Some(q) => q,
-                None => return Err("BufferCmd::enq: No queue set.".into()),
+                None => return Err(BufferCmdError::NoQueue.into()),
             };
 
-            match self.cmd.kind {
+            let mut profiled = ProfiledEnew::new(self.cmd.active_profiler(),
+                    self.cmd.enew.take())
+                .trace(queue.trace_recorder(), queue.as_core(), self.cmd.label.take(),
+                    self.cmd.ewait.as_ref());
+
+            let kind = match self.cmd.kind {
                 BufferCmdKind::Write => {
                     match self.cmd.shape {
                         BufferCmdDataShape::Lin { offset } => {
@@ -1242,9 +1410,9 @@ impl<'c, 'd, T> BufferWriteCmd<'c, 'd, T> where T: OclPrm {
 
                             unsafe {
                                 core::enqueue_write_buffer(queue, &self.cmd.buffer.obj_core, self.cmd.block,
-                                    offset, src, self.cmd.ewait.take(), self.cmd.enew.take())
-                                    .map_err(OclError::from)
+                                    offset, src, self.cmd.ewait.take(), profiled.enew())?;
                             }
+                            "write"
                         },
                         BufferCmdDataShape::Rect { src_origin, dst_origin, region,
                             src_row_pitch_bytes, src_slc_pitch_bytes, dst_row_pitch_bytes,
@@ -1254,14 +1422,17 @@ impl<'c, 'd, T> BufferWriteCmd<'c, 'd, T> where T: OclPrm {
                                 core::enqueue_write_buffer_rect(queue, &self.cmd.buffer.obj_core,
                                     self.cmd.block, src_origin, dst_origin, region, src_row_pitch_bytes,
                                     src_slc_pitch_bytes, dst_row_pitch_bytes, dst_slc_pitch_bytes,
-                                    src, self.cmd.ewait.take(), self.cmd.enew.take())
-                                    .map_err(OclError::from)
+                                    src, self.cmd.ewait.take(), profiled.enew())?;
                             }
+                            "write_rect"
                         }
                     }
                 },
                 _ => unreachable!(),
-            }
+            };
+
+            profiled.finish(ProfileKey::Buffer(kind));
+            Ok(())
         };
 
         match write_src {
@@ -1270,12 +1441,12 @@ impl<'c, 'd, T> BufferWriteCmd<'c, 'd, T> where T: OclPrm {
             },
             WriteSrc::RwVec(rw_vec) => {
                 let guard = rw_vec.read().wait()
-                    .map_err(|_| OclError::from("Unable to obtain lock."))?;
+                    .map_err(|err| AsyncError::Lock(Box::new(err)))?;
                 enqueue_with_data(&guard.as_slice()[range])
             },
             WriteSrc::Reader(reader) => {
                 let guard = reader.wait()
-                    .map_err(|_| OclError::from("Unable to obtain lock."))?;
+                    .map_err(|err| AsyncError::Lock(Box::new(err)))?;
                 enqueue_with_data(&guard.as_slice()[range])
             },
             WriteSrc::None => panic!("Invalid read destination."),
@@ -1291,27 +1462,30 @@ impl<'c, 'd, T> BufferWriteCmd<'c, 'd, T> where T: OclPrm {
     /// The returned future must be resolved.
     ///
     pub fn enq_async(mut self) -> OclResult<FutureReadGuard<Vec<T>>> {
+        let queue = match self.cmd.queue {
+            Some(q) => q,
+            None => return Err(BufferCmdError::NoQueue.into()),
+        };
+
+        let profiled = ProfiledEnew::new(self.cmd.active_profiler(), None)
+            .trace(queue.trace_recorder(), queue.as_core(), self.cmd.label.take(),
+                self.cmd.ewait.as_ref());
+
         match self.cmd.kind {
             BufferCmdKind::Write => {
                 let mut reader = match self.src {
                     WriteSrc::RwVec(rw_vec) => rw_vec.read(),
                     WriteSrc::Reader(reader) => reader,
-                    _ => return Err("BufferWriteCmd::enq_async: Invalid data destination kind for an
-                        asynchronous enqueue. The read destination must be a 'RwVec'.".into()),
+                    _ => return Err(BufferCmdError::AsyncDataNotRwVec.into()),
                 };
                 let reader_len = unsafe { (*reader.as_ptr()).len() };
-                if self.range.end > reader_len { return Err(OclError::from(
-                    "Unable to enqueue buffer write command: Invalid src_offset and/or len.")) }
+                if self.range.end > reader_len { return Err(BufferCmdError::DataRangeOutOfBounds {
+                    range_end: self.range.end, data_len: reader_len }.into()) }
 
                 if let Some(wl) = self.cmd.ewait {
                     reader.set_lock_wait_events(wl);
                 }
 
-                let queue = match self.cmd.queue {
-                    Some(q) => q,
-                    None => return Err("BufferCmd::enq: No queue set.".into()),
-                };
-
                 reader.create_lock_event(queue.context_ptr()?)?;
 
                 let src = unsafe {
@@ -1320,13 +1494,14 @@ impl<'c, 'd, T> BufferWriteCmd<'c, 'd, T> where T: OclPrm {
 
                 let mut write_event = Event::empty();
 
-                match self.cmd.shape {
+                let kind = match self.cmd.shape {
                     BufferCmdDataShape::Lin { offset } => {
                         check_len(self.cmd.mem_len, src.len(), offset)?;
                         unsafe {
                             core::enqueue_write_buffer(queue, &self.cmd.buffer.obj_core, false,
                                 offset, src, reader.lock_event(), Some(&mut write_event))?;
                         }
+                        "write"
                     },
                     BufferCmdDataShape::Rect { src_origin, dst_origin, region,
                             src_row_pitch_bytes, src_slc_pitch_bytes,
@@ -1338,13 +1513,16 @@ impl<'c, 'd, T> BufferWriteCmd<'c, 'd, T> where T: OclPrm {
                                 src_slc_pitch_bytes, dst_row_pitch_bytes, dst_slc_pitch_bytes,
                                 src, reader.lock_event(), Some(&mut write_event))?;
                         }
+                        "write_rect"
                     }
-                }
+                };
 
                 if let Some(ref mut enew) = self.cmd.enew.take() {
                     unsafe { enew.clone_from(&write_event) }
                 }
 
+                profiled.record(ProfileKey::Buffer(kind), &write_event);
+
                 reader.set_command_wait_event(write_event);
                 Ok(reader)
             },
@@ -1560,6 +1738,26 @@ impl<'c, T> BufferMapCmd<'c, T> where T: OclPrm {
         self
     }
 
+    /// Specifies a profiler to record this command with.
+    ///
+    /// Overrides the profiler of the queue (see `Queue::set_profiler`) if
+    /// one is set. The queue used must have been created with profiling
+    /// enabled.
+    pub fn profiler<'p>(mut self, profiler: &'p Profiler) -> BufferMapCmd<'c, T>
+            where 'p: 'c {
+        self.cmd.profiler = Some(profiler);
+        self
+    }
+
+    /// Specifies a label identifying this command in traces (see
+    /// `TraceRecorder`).
+    ///
+    /// Defaults to the kind of command (`"buffer:read"`, etc.).
+    pub fn label<S: Into<String>>(mut self, label: S) -> BufferMapCmd<'c, T> {
+        self.cmd.label = Some(label.into());
+        self
+    }
+
     /// Returns operation details.
     #[inline]
     fn enq_details(&mut self) -> OclResult<(usize, usize, &Queue, MapFlags,
@@ -1600,10 +1798,16 @@ impl<'c, T> BufferMapCmd<'c, T> where T: OclPrm {
     /// writes to the same memory region at the same time. Use atomics or some
     /// other synchronization mechanism to ensure this.
     pub unsafe fn enq(mut self) -> OclResult<MemMap<T>> {
+        let profiler = self.cmd.active_profiler();
+        let label = self.cmd.label.take();
         let (offset, len, queue, flags, ewait, enew, /*is_mapped*/) = self.enq_details()?;
+        let mut profiled = ProfiledEnew::new(profiler, enew)
+            .trace(queue.trace_recorder(), queue.as_core(), label, ewait.as_ref());
 
         let mm_core = core::enqueue_map_buffer::<T, _, _, _>(queue,
-            &self.cmd.buffer.obj_core, true, flags, offset, len, ewait, enew)?;
+            &self.cmd.buffer.obj_core, true, flags, offset, len, ewait, profiled.enew())?;
+
+        profiled.finish(ProfileKey::Buffer("map"));
 
         let unmap_event = None;
 
@@ -1624,7 +1828,11 @@ impl<'c, T> BufferMapCmd<'c, T> where T: OclPrm {
     /// writes to the same memory region at the same time. Use atomics or some
     /// other synchronization mechanism to ensure this.
     pub unsafe fn enq_async(mut self) -> OclResult<FutureMemMap<T>> {
+        let profiler = self.cmd.active_profiler();
+        let label = self.cmd.label.take();
         let (offset, len, queue, flags, ewait, enew, /*is_mapped*/) = self.enq_details()?;
+        let profiled = ProfiledEnew::new(profiler, None)
+            .trace(queue.trace_recorder(), queue.as_core(), label, ewait.as_ref());
 
         let mut map_event = Event::empty();
 
@@ -1639,6 +1847,8 @@ impl<'c, T> BufferMapCmd<'c, T> where T: OclPrm {
             self_enew.clone_from(&map_event)
         }
 
+        profiled.record(ProfileKey::Buffer("map"), &map_event);
+
         Ok(FutureMemMap::new(mm_core, len, map_event,
             self.cmd.buffer.obj_core.clone(), queue.clone(), /*is_mapped*/))
 
@@ -1898,6 +2108,22 @@ impl<T: OclPrm> Buffer<T> {
         core::get_mem_object_info(&self.obj_core, info_kind)
     }
 
+    /// Registers a function to be called once the underlying memory object
+    /// has actually been released by the OpenCL runtime.
+    ///
+    /// The function is called after every clone of this buffer (along with any
+    /// sub-buffers, kernels, or commands referencing it) has been dropped or
+    /// completed. This makes it the right place to reclaim host memory used
+    /// by a buffer created with `MemFlags::USE_HOST_PTR`.
+    ///
+    /// Functions registered on the same buffer are called in the reverse order
+    /// of registration, on a thread of the runtime's choosing.
+    pub fn on_release<F>(&self, func: F) -> OclResult<()>
+            where F: FnOnce() + Send + 'static {
+        core::set_mem_object_destructor_callback(&self.obj_core, Box::new(func))
+            .map_err(OclError::from)
+    }
+
     /// Changes the default queue used by this buffer for all subsequent
     /// command enqueue operations (reads, writes, etc.).
     ///
@@ -2033,14 +2259,13 @@ impl<T: OclPrm> Buffer<T> {
         // let len = dims.to_len();
 
         if offset > buffer_len {
-            return Err(format!("Buffer::create_sub_buffer: Origin ({:?}) is outside of the \
-                dimensions of the source buffer ({:?}).", offset, buffer_len).into());
+            return Err(BufferCmdError::SubBufferOriginOutOfRange { origin: offset, buffer_len }
+                .into());
         }
 
         if offset + len > buffer_len {
-            return Err(format!("Buffer::create_sub_buffer: Sub-buffer region (origin: '{:?}', \
-                len: '{:?}') exceeds the dimensions of the source buffer ({:?}).",
-                offset, len, buffer_len).into());
+            return Err(BufferCmdError::SubBufferRegionOutOfRange { origin: offset, len,
+                buffer_len }.into());
         }
 
         let obj_core = core::create_sub_buffer::<T>(self, flags,
@@ -0,0 +1,258 @@
+//! Host and device clock correlation.
+
+use std::time::{Duration, Instant, SystemTime};
+use core::{ProfilingInfo, CommandQueueInfo, CommandQueueInfoResult, CommandQueueProperties};
+use error::Result as OclResult;
+use standard::{Queue, EventList, QueueError, EventError};
+
+/// The number of readings taken each time the clocks are sampled. The
+/// reading with the shortest host round trip is kept.
+const READINGS_PER_SAMPLE: usize = 8;
+
+
+/// A simultaneous reading of the device timer and the host clock.
+#[derive(Clone, Copy, Debug)]
+struct ClockSample {
+    device_ns: u64,
+    instant: Instant,
+    system_time: SystemTime,
+}
+
+
+/// Converts device timestamps (such as those returned by
+/// `Event::profiling_info`) to host time.
+///
+/// The offset between the device timer and the host clock is measured when
+/// a `DeviceClock` is created. Call `::recalibrate` periodically to measure
+/// (and correct for) drift between the two.
+///
+/// On OpenCL 2.1+ devices (with the `opencl_version_2_1` feature enabled)
+/// the clocks are read using `clGetDeviceAndHostTimer`. On other devices
+/// they are correlated by timing marker commands, which requires a queue
+/// created with `CommandQueueProperties::PROFILING_ENABLE`. This fallback is
+/// accurate to within roughly half the round trip time of a marker.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// let clock = DeviceClock::new(&queue)?;
+/// unsafe { kernel.cmd().enew(&mut event).enq()?; }
+/// event.wait_for()?;
+/// let start = event.profiling_instant(ProfilingInfo::Start, &clock)?;
+/// let end = event.profiling_instant(ProfilingInfo::End, &clock)?;
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct DeviceClock {
+    queue: Queue,
+    reference: ClockSample,
+    drift: f64,
+    uses_host_timer: bool,
+}
+
+impl DeviceClock {
+    /// Returns a new `DeviceClock` for the device associated with `queue`.
+    pub fn new(queue: &Queue) -> OclResult<DeviceClock> {
+        let uses_host_timer = host_timer_supported(queue);
+
+        Ok(DeviceClock {
+            queue: queue.clone(),
+            reference: sample(queue, uses_host_timer)?,
+            drift: 1.0,
+            uses_host_timer: uses_host_timer,
+        })
+    }
+
+    /// Samples both clocks again, updating the measured drift and offset.
+    ///
+    /// Drift is measured over the time elapsed since the previous sample.
+    pub fn recalibrate(&mut self) -> OclResult<()> {
+        let sample = sample(&self.queue, self.uses_host_timer)?;
+
+        let host_elapsed_ns = signed_ns(sample.instant, self.reference.instant) as f64;
+        let device_elapsed_ns = sample.device_ns as f64 - self.reference.device_ns as f64;
+
+        if host_elapsed_ns > 0.0 && device_elapsed_ns > 0.0 {
+            self.drift = device_elapsed_ns / host_elapsed_ns;
+        }
+
+        self.reference = sample;
+        Ok(())
+    }
+
+    /// Converts a device timestamp (in nanoseconds) to a host `Instant`.
+    pub fn to_instant(&self, device_ns: u64) -> Instant {
+        offset_instant(self.reference.instant, self.host_offset_ns(device_ns))
+    }
+
+    /// Converts a device timestamp (in nanoseconds) to a host `SystemTime`.
+    pub fn to_system_time(&self, device_ns: u64) -> SystemTime {
+        offset_system_time(self.reference.system_time, self.host_offset_ns(device_ns))
+    }
+
+    /// Returns the measured rate of the device timer relative to the host
+    /// clock (device nanoseconds per host nanosecond).
+    ///
+    /// Always `1.0` until `::recalibrate` has been called.
+    pub fn drift(&self) -> f64 {
+        self.drift
+    }
+
+    /// Returns true if the clocks are sampled with `clGetDeviceAndHostTimer`
+    /// rather than by timing marker commands.
+    pub fn uses_host_timer(&self) -> bool {
+        self.uses_host_timer
+    }
+
+    /// Returns the queue used to sample the device timer.
+    pub fn queue(&self) -> &Queue {
+        &self.queue
+    }
+
+    /// Returns the number of host nanoseconds between the reference sample
+    /// and `device_ns`.
+    fn host_offset_ns(&self, device_ns: u64) -> i64 {
+        let device_offset_ns = device_ns as f64 - self.reference.device_ns as f64;
+        (device_offset_ns / self.drift) as i64
+    }
+}
+
+
+/// Returns true if `clGetDeviceAndHostTimer` can be used with the device
+/// associated with `queue`.
+#[cfg(feature = "opencl_version_2_1")]
+fn host_timer_supported(queue: &Queue) -> bool {
+    use core::OpenclVersion;
+    queue.device_version() >= OpenclVersion::new(2, 1)
+}
+
+/// Returns true if `clGetDeviceAndHostTimer` can be used with the device
+/// associated with `queue`.
+#[cfg(not(feature = "opencl_version_2_1"))]
+fn host_timer_supported(_: &Queue) -> bool {
+    false
+}
+
+/// Samples the device timer and host clock.
+fn sample(queue: &Queue, uses_host_timer: bool) -> OclResult<ClockSample> {
+    if uses_host_timer {
+        #[cfg(feature = "opencl_version_2_1")]
+        return sample_host_timer(queue);
+    }
+    sample_markers(queue)
+}
+
+/// Samples the device timer and host clock using `clGetDeviceAndHostTimer`.
+///
+/// The host timer is related to `Instant` by reading it again, bracketed by
+/// calls to `Instant::now`.
+#[cfg(feature = "opencl_version_2_1")]
+fn sample_host_timer(queue: &Queue) -> OclResult<ClockSample> {
+    use core;
+
+    let device = queue.device();
+    let device_version = queue.device_version();
+    let mut best: Option<(Duration, ClockSample)> = None;
+
+    for _ in 0..READINGS_PER_SAMPLE {
+        let (device_ns, host_ns) = core::get_device_and_host_timer(device.as_core(),
+            Some(&device_version))?;
+        let system_time = SystemTime::now();
+        let before = Instant::now();
+        let host_now_ns = core::get_host_timer(device.as_core(), Some(&device_version))?;
+        let after = Instant::now();
+
+        let round_trip = after.duration_since(before);
+        let host_elapsed_ns = host_now_ns as i64 - host_ns as i64;
+        let sample = ClockSample {
+            device_ns: device_ns,
+            instant: offset_instant(before + round_trip / 2, -host_elapsed_ns),
+            system_time: offset_system_time(system_time + round_trip / 2, -host_elapsed_ns),
+        };
+
+        if best.map(|(rt, _)| round_trip < rt).unwrap_or(true) {
+            best = Some((round_trip, sample));
+        }
+    }
+
+    Ok(best.unwrap().1)
+}
+
+/// Samples the device timer and host clock by timing marker commands.
+///
+/// The device timestamp at which each marker completes is paired with the
+/// midpoint of the host time spent enqueuing and waiting for it.
+fn sample_markers(queue: &Queue) -> OclResult<ClockSample> {
+    match queue.info(CommandQueueInfo::Properties)? {
+        CommandQueueInfoResult::Properties(props) => {
+            if !props.contains(CommandQueueProperties::PROFILING_ENABLE) {
+                // Required on devices without 'clGetDeviceAndHostTimer' (OpenCL 2.1+).
+                return Err(QueueError::ProfilingDisabled.into());
+            }
+        },
+        _ => unreachable!(),
+    }
+
+    let mut best: Option<(Duration, ClockSample)> = None;
+
+    for _ in 0..READINGS_PER_SAMPLE {
+        queue.finish()?;
+
+        let system_time = SystemTime::now();
+        let before = Instant::now();
+        let marker = queue.enqueue_marker(None::<&EventList>)?;
+        marker.wait_for()?;
+        let after = Instant::now();
+
+        let device_ns = marker.profiling_info(ProfilingInfo::End)?.time()?;
+        if device_ns == 0 {
+            return Err(EventError::ProfilingUnavailable.into());
+        }
+
+        let round_trip = after.duration_since(before);
+        let sample = ClockSample {
+            device_ns: device_ns,
+            instant: before + round_trip / 2,
+            system_time: system_time + round_trip / 2,
+        };
+
+        if best.map(|(rt, _)| round_trip < rt).unwrap_or(true) {
+            best = Some((round_trip, sample));
+        }
+    }
+
+    Ok(best.unwrap().1)
+}
+
+/// Returns `ns` nanoseconds as a `Duration`.
+fn duration_from_ns(ns: u64) -> Duration {
+    Duration::new(ns / 1_000_000_000, (ns % 1_000_000_000) as u32)
+}
+
+/// Returns `a - b` in nanoseconds.
+fn signed_ns(a: Instant, b: Instant) -> i64 {
+    if a >= b {
+        let d = a.duration_since(b);
+        (d.as_secs() * 1_000_000_000 + d.subsec_nanos() as u64) as i64
+    } else {
+        -signed_ns(b, a)
+    }
+}
+
+/// Returns `instant` offset by `ns` nanoseconds.
+fn offset_instant(instant: Instant, ns: i64) -> Instant {
+    if ns >= 0 {
+        instant + duration_from_ns(ns as u64)
+    } else {
+        instant - duration_from_ns((-ns) as u64)
+    }
+}
+
+/// Returns `system_time` offset by `ns` nanoseconds.
+fn offset_system_time(system_time: SystemTime, ns: i64) -> SystemTime {
+    if ns >= 0 {
+        system_time + duration_from_ns(ns as u64)
+    } else {
+        system_time - duration_from_ns((-ns) as u64)
+    }
+}
@@ -6,11 +6,30 @@ use ffi::cl_context;
 use core::{self, Context as ContextCore, ContextProperties, ContextPropertyValue, ContextInfo,
     ContextInfoResult, DeviceInfo, DeviceInfoResult, PlatformInfo, PlatformInfoResult,
     CreateContextCallbackFn, UserDataPtr, OpenclVersion, ClContextPtr, ClVersions};
-use core::error::{Result as OclCoreResult, Error as OclCoreError};
-use error::{Result as OclResult};
+use core::error::{Result as OclCoreResult};
+use error::{Error as OclError, Result as OclResult};
 use standard::{Platform, Device, DeviceSpecifier};
 
 
+/// A context related error.
+#[derive(Debug)]
+pub enum ContextError {
+    NoPlatform,
+    InvalidDeviceIndex { index: usize, device_count: usize },
+}
+
+impl std::fmt::Display for ContextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ContextError::NoPlatform => write!(f, "This context has no associated platform."),
+            ContextError::InvalidDeviceIndex { index, device_count } => write!(f, "Invalid \
+                device index: {} (device count: {}).", index, device_count),
+        }
+    }
+}
+
+impl std::error::Error for ContextError {}
+
 
 /// A context for a particular platform and set of device types.
 ///
@@ -98,25 +117,20 @@ impl Context {
     }
 
     /// Returns info about the platform associated with the context.
-    pub fn platform_info(&self, info_kind: PlatformInfo) -> OclCoreResult<PlatformInfoResult> {
-        match self.platform() {
-            Ok(plat_opt) => match plat_opt {
-                Some(ref p) => core::get_platform_info(p, info_kind),
-                None => Err(OclCoreError::from("Context::platform_info: \
-                    This context has no associated platform.")),
-            },
-            Err(err) => Err(OclCoreError::from(err)),
+    pub fn platform_info(&self, info_kind: PlatformInfo) -> OclResult<PlatformInfoResult> {
+        match self.platform()? {
+            Some(ref p) => core::get_platform_info(p, info_kind).map_err(OclError::from),
+            None => Err(ContextError::NoPlatform.into()),
         }
     }
 
     /// Returns info about the device indexed by `index` associated with this
     /// context.
-    pub fn device_info(&self, index: usize, info_kind: DeviceInfo) -> OclCoreResult<DeviceInfoResult> {
+    pub fn device_info(&self, index: usize, info_kind: DeviceInfo) -> OclResult<DeviceInfoResult> {
         match self.devices().get(index) {
-            Some(d) => core::get_device_info(d, info_kind),
-            None => {
-                return Err(OclCoreError::from("Context::device_info: Invalid device index"));
-            },
+            Some(d) => core::get_device_info(d, info_kind).map_err(OclError::from),
+            None => Err(ContextError::InvalidDeviceIndex { index,
+                device_count: self.devices().len() }.into()),
         }
     }
 
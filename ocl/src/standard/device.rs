@@ -4,24 +4,34 @@ use std;
 use std::ops::{Deref, DerefMut};
 use std::borrow::Borrow;
 use ffi::cl_device_id;
-use core::{self, util, DeviceId as DeviceIdCore, DeviceType, DeviceInfo, DeviceInfoResult, ClDeviceIdPtr};
+use core::{self, util, DeviceId as DeviceIdCore, DeviceType, DeviceInfo, DeviceInfoResult,
+    ClDeviceIdPtr, DevicePartition, DeviceAffinityDomain, OpenclVersion};
 use core::error::{Error as OclCoreError, Result as OclCoreResult};
 use error::{Error as OclError, Result as OclResult};
 use standard::Platform;
 
 
 /// A device related error.
-#[derive(Debug, Fail)]
+#[derive(Debug)]
 pub enum DeviceError {
-    #[fail(display = "No devices found on the specified platform.")]
     NoDevices,
-    #[fail(display = "Empty device list provided.")]
     ResolveIdxsEmptyDeviceList,
-    #[fail(display = "An index in the resolve list is out of range (index: {}, max: {})",
-        idx, max)]
     ResolveIdxsInvalidIndex { idx: usize, max: usize },
 }
 
+impl std::fmt::Display for DeviceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            DeviceError::NoDevices => write!(f, "No devices found on the specified platform."),
+            DeviceError::ResolveIdxsEmptyDeviceList => write!(f, "Empty device list provided."),
+            DeviceError::ResolveIdxsInvalidIndex { idx, max } => write!(f, "An index in the \
+                resolve list is out of range (index: {}, max: {})", idx, max),
+        }
+    }
+}
+
+impl std::error::Error for DeviceError {}
+
 // Perhaps add something like this to the `DeviceSpecifier`.
 //
 // Copied from `https://github.com/TyOverby/ocl-repro/blob/master/src/main.rs`:
@@ -69,6 +79,7 @@ pub enum DeviceSpecifier {
     First,
     Single(Device),
     List(Vec<Device>),
+    SubDevices(Vec<SubDevice>),
     Indices(Vec<usize>),
     WrappingIndices(Vec<usize>),
     TypeFlags(DeviceType),
@@ -103,6 +114,15 @@ impl DeviceSpecifier {
         DeviceSpecifier::List(list)
     }
 
+    /// Returns a `DeviceSpecifier::SubDevices` variant which specifies a list
+    /// of sub-devices.
+    ///
+    /// The sub-devices are retained until the specifier is dropped.
+    ///
+    pub fn sub_devices(self, sub_devices: Vec<SubDevice>) -> DeviceSpecifier {
+        DeviceSpecifier::SubDevices(sub_devices)
+    }
+
     /// Returns a `DeviceSpecifier::Indices` variant which specifies a list of
     /// devices by index.
     ///
@@ -166,6 +186,9 @@ impl DeviceSpecifier {
             DeviceSpecifier::List(ref devices) => {
                 Ok(devices.clone())
             },
+            DeviceSpecifier::SubDevices(ref sub_devices) => {
+                Ok(sub_devices.iter().map(|sd| *sd.as_device()).collect())
+            },
             DeviceSpecifier::Indices(ref idx_list) => {
                 Device::list_select(&platform, None, idx_list)
             },
@@ -227,6 +250,36 @@ impl<'a> From<&'a Device> for DeviceSpecifier {
     }
 }
 
+impl From<SubDevice> for DeviceSpecifier {
+    fn from(sub_device: SubDevice) -> DeviceSpecifier {
+        DeviceSpecifier::SubDevices(vec![sub_device])
+    }
+}
+
+impl<'a> From<&'a SubDevice> for DeviceSpecifier {
+    fn from(sub_device: &'a SubDevice) -> DeviceSpecifier {
+        DeviceSpecifier::SubDevices(vec![sub_device.clone()])
+    }
+}
+
+impl From<Vec<SubDevice>> for DeviceSpecifier {
+    fn from(sub_devices: Vec<SubDevice>) -> DeviceSpecifier {
+        DeviceSpecifier::SubDevices(sub_devices)
+    }
+}
+
+impl<'a> From<&'a [SubDevice]> for DeviceSpecifier {
+    fn from(sub_devices: &'a [SubDevice]) -> DeviceSpecifier {
+        DeviceSpecifier::SubDevices(sub_devices.into())
+    }
+}
+
+impl<'a> From<&'a Vec<SubDevice>> for DeviceSpecifier {
+    fn from(sub_devices: &'a Vec<SubDevice>) -> DeviceSpecifier {
+        DeviceSpecifier::SubDevices(sub_devices.clone())
+    }
+}
+
 impl From<DeviceIdCore> for DeviceSpecifier {
     fn from(device: DeviceIdCore) -> DeviceSpecifier {
         DeviceSpecifier::Single(device.into())
@@ -415,6 +468,18 @@ impl Device {
         }
     }
 
+    /// Returns the maximum number of sub-groups in a work-group that a
+    /// kernel can be executed with on this device.
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    pub fn max_num_sub_groups(&self) -> OclCoreResult<u32> {
+        match self.info(DeviceInfo::MaxNumSubGroups) {
+            Ok(DeviceInfoResult::MaxNumSubGroups(r)) => Ok(r),
+            Err(err) => Err(OclCoreError::from(err)),
+            _ => panic!("Device::max_num_sub_groups: Unexpected 'DeviceInfoResult' variant."),
+        }
+    }
+
     /// Returns whether or not the device is available for use.
     pub fn is_available(&self) -> OclCoreResult<bool> {
         match self.info(DeviceInfo::Available) {
@@ -424,6 +489,56 @@ impl Device {
         }
     }
 
+    /// Returns the names of the built-in kernels supported by this device.
+    ///
+    /// [Version Controlled: OpenCL 1.2+]
+    pub fn built_in_kernels(&self) -> OclCoreResult<Vec<String>> {
+        match self.info(DeviceInfo::BuiltInKernels) {
+            Ok(DeviceInfoResult::BuiltInKernels(names)) => {
+                Ok(names.split(';').map(str::trim).filter(|n| !n.is_empty())
+                    .map(String::from).collect())
+            },
+            Err(err) => Err(OclCoreError::from(err)),
+            _ => panic!("Device::built_in_kernels: Unexpected 'DeviceInfoResult' variant."),
+        }
+    }
+
+    /// Partitions this device into as many sub-devices as can be created
+    /// with `compute_units` compute units each.
+    ///
+    /// [Version Controlled: OpenCL 1.2+]
+    pub fn partition_equally(&self, compute_units: u32) -> OclResult<Vec<SubDevice>> {
+        self.partition(&DevicePartition::Equally(compute_units))
+    }
+
+    /// Partitions this device into one sub-device for each element of
+    /// `counts`, each containing the specified number of compute units.
+    ///
+    /// [Version Controlled: OpenCL 1.2+]
+    pub fn partition_by_counts(&self, counts: &[u32]) -> OclResult<Vec<SubDevice>> {
+        self.partition(&DevicePartition::ByCounts(counts.to_vec()))
+    }
+
+    /// Partitions this device into sub-devices which share the cache level or
+    /// NUMA node specified by `domain`.
+    ///
+    /// Use `DeviceAffinityDomain::NEXT_PARTITIONABLE` to split along the
+    /// first level of the hierarchy which can be partitioned.
+    ///
+    /// [Version Controlled: OpenCL 1.2+]
+    pub fn partition_by_affinity(&self, domain: DeviceAffinityDomain) -> OclResult<Vec<SubDevice>> {
+        self.partition(&DevicePartition::ByAffinityDomain(domain))
+    }
+
+    /// Partitions this device into sub-devices as specified by `partition`.
+    ///
+    /// [Version Controlled: OpenCL 1.2+]
+    pub fn partition(&self, partition: &DevicePartition) -> OclResult<Vec<SubDevice>> {
+        let version = self.0.version()?;
+        let sub_devices = core::create_sub_devices(&self.0, partition, Some(&version))?;
+        Ok(sub_devices.into_iter().map(|sd| SubDevice { device: Device(sd), version }).collect())
+    }
+
     /// Returns info about the device.
     pub fn info(&self, info_kind: DeviceInfo) -> OclCoreResult<DeviceInfoResult> {
         core::get_device_info(&self.0, info_kind)
@@ -576,3 +691,74 @@ impl DerefMut for Device {
         &mut self.0
     }
 }
+
+
+
+/// A sub-device created by partitioning a `Device`.
+///
+/// Each `SubDevice` holds a reference to the underlying OpenCL sub-device
+/// which is released when dropped (cloning retains a new reference). Contexts
+/// created using a sub-device hold their own reference so the `SubDevice`
+/// itself need only live until the context has been created.
+///
+/// Dereferences to `Device` and can be used anywhere a device is expected,
+/// including `Context::builder().devices(..)`.
+///
+#[derive(Debug)]
+pub struct SubDevice {
+    device: Device,
+    version: OpenclVersion,
+}
+
+impl SubDevice {
+    /// Returns the sub-device as a `Device`.
+    ///
+    /// The returned `Device` is not reference counted and is only valid for
+    /// as long as this `SubDevice` (or a context using it) exists.
+    pub fn as_device(&self) -> &Device {
+        &self.device
+    }
+}
+
+impl Clone for SubDevice {
+    fn clone(&self) -> SubDevice {
+        unsafe { core::retain_device(&self.device.0, Some(&self.version)).unwrap(); }
+        SubDevice { device: self.device, version: self.version }
+    }
+}
+
+impl Drop for SubDevice {
+    fn drop(&mut self) {
+        unsafe {
+            if let Err(err) = core::release_device(&self.device.0, Some(&self.version)) {
+                panic!("{:?}", err);
+            }
+        }
+    }
+}
+
+unsafe impl<'a> ClDeviceIdPtr for &'a SubDevice {
+    fn as_ptr(&self) -> cl_device_id {
+        self.device.as_ptr()
+    }
+}
+
+impl AsRef<Device> for SubDevice {
+    fn as_ref(&self) -> &Device {
+        &self.device
+    }
+}
+
+impl Deref for SubDevice {
+    type Target = Device;
+
+    fn deref(&self) -> &Device {
+        &self.device
+    }
+}
+
+impl std::fmt::Display for SubDevice {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.device.fmt_info(f)
+    }
+}
@@ -20,22 +20,60 @@ use std::{mem, ptr, fmt};
 use std::borrow::Borrow;
 use std::ops::{Deref, DerefMut};
 use std::cell::Ref;
+use std::time::{Instant, SystemTime};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use futures::{Future, Poll, Async};
 #[cfg(not(feature = "async_block"))]
 use futures::task;
+#[cfg(feature = "std_future")]
+use std::future::Future as StdFuture;
+#[cfg(feature = "std_future")]
+use std::pin::Pin;
+#[cfg(feature = "std_future")]
+use std::task::{Context as TaskContext, Poll as StdPoll};
+#[cfg(all(feature = "std_future", not(feature = "async_block")))]
+use std::task::Waker;
 use ffi::cl_event;
-use core::{self, Event as EventCore, EventInfo, EventInfoResult, ProfilingInfo,
+use core::{self, Event as EventCore, EventInfo, CommandExecutionStatus, EventInfoResult, ProfilingInfo,
     ProfilingInfoResult, ClNullEventPtr, ClWaitListPtr, ClEventPtrRef,
     CommandQueue as CommandQueueCore, ClContextPtr};
 use core::error::{Result as OclCoreResult};
 use error::{Error as OclError, Result as OclResult};
-use standard::{Queue, ClWaitListPtrEnum};
+use standard::{Queue, ClWaitListPtrEnum, DeviceClock};
 #[cfg(not(feature = "async_block"))]
-use standard::{_unpark_task, box_raw_void};
+use standard::{_unpark_task, box_raw_void, EventReactor};
+#[cfg(all(feature = "std_future", not(feature = "async_block")))]
+use standard::_wake_waker;
 use self::nodrop::NoDrop;
 
 const PRINT_DEBUG: bool = false;
 
+
+/// An event related error.
+#[derive(Debug)]
+pub enum EventError {
+    EmptyList,
+    /// A fixed-capacity list (`EventArray`) is full.
+    ListFull { capacity: usize },
+    ProfilingUnavailable,
+}
+
+impl fmt::Display for EventError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EventError::EmptyList => write!(f, "The event list is empty."),
+            EventError::ListFull { capacity } => write!(f, "Unable to add an event to the \
+                event list: the list is full (capacity: {}).", capacity),
+            EventError::ProfilingUnavailable => write!(f, "Profiling info is unavailable for \
+                this event."),
+        }
+    }
+}
+
+impl ::std::error::Error for EventError {}
+
+
 /// An event representing a command or user created event.
 ///
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -80,6 +118,45 @@ impl Event {
         unsafe { self.set_callback(_unpark_task, task_ptr) }
     }
 
+    /// Sets a callback function which will wake `waker` when this event
+    /// completes or terminates abnormally.
+    ///
+    /// To be used within the context of a `std::future::Future`
+    /// implementation. A clone of `waker` is held until the callback runs.
+    #[cfg(all(feature = "std_future", not(feature = "async_block")))]
+    pub fn set_waker_callback(&self, waker: &Waker) -> OclCoreResult<()> {
+        let waker_ptr = box_raw_void(waker.clone());
+        unsafe { self.set_callback(_wake_waker, waker_ptr) }
+    }
+
+    /// Registers a function to be called as soon as this event reaches
+    /// `status` or the associated command terminates abnormally.
+    ///
+    /// The function is passed the status reached or, if the command
+    /// terminated abnormally, an error containing the negative status
+    /// (`ApiWrapperError::EventTerminated`). It is held until called, exactly
+    /// once, on a thread of the runtime's choosing. It must not call blocking
+    /// OpenCL functions (such as `Event::wait_for` or `Queue::finish`).
+    ///
+    /// Only `CommandExecutionStatus::Complete` is supported as `status`
+    /// below OpenCL 1.2 (`::Submitted` and `::Running` are also supported
+    /// from 1.2 on).
+    pub fn on_status<F>(&self, status: CommandExecutionStatus, func: F) -> OclResult<()>
+            where F: FnOnce(OclResult<CommandExecutionStatus>) + Send + 'static {
+        core::set_event_status_callback(&self.0, status,
+            Box::new(move |res| func(res.map_err(OclError::from))))
+            .map_err(OclError::from)
+    }
+
+    /// Registers a function to be called as soon as this event completes or
+    /// the associated command terminates abnormally (`Err`).
+    ///
+    /// See `::on_status`.
+    pub fn on_complete<F>(&self, func: F) -> OclResult<()>
+            where F: FnOnce(OclResult<()>) + Send + 'static {
+        self.on_status(CommandExecutionStatus::Complete, move |res| func(res.map(|_| ())))
+    }
+
     /// Registers a user event to have its status set to complete
     /// (`CommandExecutionStatus::Complete`) immediately upon completion of
     /// this event.
@@ -116,6 +193,28 @@ impl Event {
         core::get_event_profiling_info(&self.0, info_kind)
     }
 
+    /// Returns the time at which the command associated with this event
+    /// reached the stage specified by `info_kind` (queued, submitted,
+    /// started, or ended) as a host `Instant`.
+    ///
+    /// The command's queue must have been created with profiling enabled.
+    pub fn profiling_instant(&self, info_kind: ProfilingInfo, clock: &DeviceClock)
+            -> OclResult<Instant> {
+        let device_ns = self.profiling_info(info_kind)?.time()?;
+        Ok(clock.to_instant(device_ns))
+    }
+
+    /// Returns the time at which the command associated with this event
+    /// reached the stage specified by `info_kind` (queued, submitted,
+    /// started, or ended) as a host `SystemTime`.
+    ///
+    /// The command's queue must have been created with profiling enabled.
+    pub fn profiling_system_time(&self, info_kind: ProfilingInfo, clock: &DeviceClock)
+            -> OclResult<SystemTime> {
+        let device_ns = self.profiling_info(info_kind)?.time()?;
+        Ok(clock.to_system_time(device_ns))
+    }
+
     /// Returns this event's associated command queue.
     pub fn queue_core(&self) -> OclCoreResult<CommandQueueCore> {
         match self.info(EventInfo::CommandQueue)? {
@@ -155,6 +254,19 @@ impl Event {
         EventCore::from_raw(ptr).into()
     }
 
+    /// Returns true if this event is complete, false if not yet complete, or
+    /// an error (`ApiWrapperError::EventTerminated`) if its command has
+    /// terminated abnormally (negative status).
+    ///
+    /// Unlike `::is_complete`, which reports terminated events as not
+    /// complete, this is used by futures to resolve failed commands.
+    pub(crate) fn poll_complete(&self) -> OclResult<bool> {
+        match core::event_status(&self.0)? {
+            CommandExecutionStatus::Complete => Ok(true),
+            _ => Ok(false),
+        }
+    }
+
     fn fmt_info(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("Event")
             .field("CommandQueue", &self.info(EventInfo::CommandQueue))
@@ -256,23 +368,19 @@ impl Future for Event {
 
     // Non-blocking, proper implementation.
     //
-    // * NOTE: There is currently no check to ensure that only one callback is
-    //   created (is this ok?).
-    //   - TODO: Look into possible effects of unparking a task multiple times.
+    // The current task is registered with the global `EventReactor` which
+    // sets at most one callback per event regardless of how many times this
+    // event is polled.
     //
     #[cfg(not(feature = "async_block"))]
     fn poll(&mut self) -> Poll<Self::Item, Self::Error> {
         debug_assert!(self.0.is_valid());
 
-        match self.is_complete() {
-            Ok(true) => {
-                Ok(Async::Ready(()))
-            }
-            Ok(false) => {
-                self.set_unpark_callback()?;
-                Ok(Async::NotReady)
-            },
-            Err(err) => Err(OclError::from(err)),
+        if self.poll_complete()? {
+            Ok(Async::Ready(()))
+        } else {
+            EventReactor::global().register(self)?;
+            Ok(Async::NotReady)
         }
     }
 
@@ -285,6 +393,16 @@ impl Future for Event {
     }
 }
 
+#[cfg(feature = "std_future")]
+impl StdFuture for Event {
+    type Output = OclResult<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext) -> StdPoll<Self::Output> {
+        debug_assert!(self.0.is_valid());
+        poll_events_std(::std::slice::from_ref(&*self), cx)
+    }
+}
+
 
 
 /// Returns an empty, initialized (zeroed) event array.
@@ -319,10 +437,10 @@ fn poll_events(events: &[Event]) -> Poll<(), OclError> {
                 (thread: '{}')", event, ::std::thread::current().name().unwrap_or("<unnamed>")); }
             event.wait_for()?;
         } else {
-            if !event.is_complete()? {
+            if !event.poll_complete()? {
                 #[cfg(not(feature = "async_block"))]
-                event.set_unpark_callback()?;
-                if PRINT_DEBUG { println!("####### EventList/Array::poll: callback set for event: {:?} \
+                EventReactor::global().register(event)?;
+                if PRINT_DEBUG { println!("####### EventList/Array::poll: task registered for event: {:?} \
                     (thread: '{}')", event, ::std::thread::current().name().unwrap_or("<unnamed>")); }
                 return Ok(Async::NotReady);
             } else {
@@ -341,6 +459,39 @@ fn poll_events(events: &[Event]) -> Poll<(), OclError> {
 }
 
 
+/// Polls events for the `std::future::Future` implementations of `Event`,
+/// `EventArray` and `EventList`.
+#[cfg(all(feature = "std_future", not(feature = "async_block")))]
+fn poll_events_std(events: &[Event], cx: &mut TaskContext) -> StdPoll<OclResult<()>> {
+    for event in events.iter() {
+        match event.poll_complete() {
+            Ok(true) => (),
+            Ok(false) => {
+                if let Err(err) = EventReactor::global().register_waker(event, cx.waker()) {
+                    return StdPoll::Ready(Err(err));
+                }
+                return StdPoll::Pending;
+            },
+            Err(err) => return StdPoll::Ready(Err(err)),
+        }
+    }
+    StdPoll::Ready(Ok(()))
+}
+
+
+/// Polls events for the `std::future::Future` implementations of `Event`,
+/// `EventArray` and `EventList` (blocking).
+#[cfg(all(feature = "std_future", feature = "async_block"))]
+fn poll_events_std(events: &[Event], _cx: &mut TaskContext) -> StdPoll<OclResult<()>> {
+    for event in events.iter() {
+        if let Err(err) = event.wait_for() {
+            return StdPoll::Ready(Err(err.into()));
+        }
+    }
+    StdPoll::Ready(Ok(()))
+}
+
+
 /// A list of events for coordinating enqueued commands.
 ///
 /// Events contain status information about the command that
@@ -461,7 +612,7 @@ impl EventArray {
     /// Enqueue a marker event representing the completion of each and every
     /// event in this list.
     pub fn enqueue_marker(&self, queue: &Queue) -> OclResult<Event> {
-        if self.array.is_empty() { return Err("EventArray::enqueue_marker: List empty.".into()); }
+        if self.array.is_empty() { return Err(EventError::EmptyList.into()); }
         queue.enqueue_marker(Some(self)).map_err(OclError::from)
     }
 
@@ -484,11 +635,10 @@ impl EventArray {
     }
 
     #[inline]
-    fn _alloc_new(&mut self) -> Result<*mut cl_event, &'static str> {
+    fn _alloc_new(&mut self) -> OclResult<*mut cl_event> {
         match self.push(Event::empty()) {
             Ok(_) => Ok((&mut self.array[self.len - 1]) as *mut _ as *mut cl_event),
-            Err(_) => Err("EventArray::_alloc_new: Error attempting to add a new event \
-                to the event list: this list is full"),
+            Err(_) => Err(EventError::ListFull { capacity: self.array.len() }.into()),
         }
     }
 
@@ -605,6 +755,16 @@ impl Future for EventArray {
     }
 }
 
+#[cfg(feature = "std_future")]
+impl StdFuture for EventArray {
+    type Output = OclResult<()>;
+
+    /// Polls each event from this list.
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext) -> StdPoll<Self::Output> {
+        poll_events_std(self.as_slice(), cx)
+    }
+}
+
 unsafe impl<'a> ClNullEventPtr for &'a mut EventArray {
     #[inline] fn alloc_new(&mut self) -> *mut cl_event {
         self._alloc_new().expect("<EventArray as ClNullEventPtr>::alloc_new")
@@ -780,6 +940,51 @@ impl EventList {
         }
     }
 
+    /// Registers a function to be called as soon as every event in this list
+    /// completes or as soon as the command associated with any one of them
+    /// terminates abnormally (`Err`).
+    ///
+    /// The function is called exactly once, immediately if this list is
+    /// empty. If an error is returned, the function will not be called.
+    ///
+    /// See `Event::on_status`.
+    pub fn on_all_complete<F>(&self, func: F) -> OclResult<()>
+            where F: FnOnce(OclResult<()>) + Send + 'static {
+        let events = self.as_slice();
+
+        if events.is_empty() {
+            func(Ok(()));
+            return Ok(());
+        }
+
+        let remaining = Arc::new(AtomicUsize::new(events.len()));
+        let func = Arc::new(Mutex::new(Some(func)));
+
+        for event in events {
+            let remaining = remaining.clone();
+            let func_slot = func.clone();
+
+            let res = event.on_complete(move |res| {
+                let res = match res {
+                    Ok(()) => {
+                        if remaining.fetch_sub(1, Ordering::SeqCst) != 1 { return; }
+                        Ok(())
+                    },
+                    Err(err) => Err(err),
+                };
+
+                let func = func_slot.lock().unwrap().take();
+                if let Some(func) = func { func(res); }
+            });
+
+            if let Err(err) = res {
+                func.lock().unwrap().take();
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
     /// Enqueue a marker event representing the completion of each and every
     /// event in this list.
     ///
@@ -796,7 +1001,7 @@ impl EventList {
         match self.inner {
             Inner::Array(ref a) => a.enqueue_marker(queue).map_err(OclError::from),
             Inner::Vec(ref v) => {
-                if v.is_empty() { return Err("EventList::enqueue_marker: List empty.".into()); }
+                if v.is_empty() { return Err(EventError::EmptyList.into()); }
                 queue.enqueue_marker(Some(self)).map_err(OclError::from)
             },
         }
@@ -1207,6 +1412,16 @@ impl Future for EventList {
     }
 }
 
+#[cfg(feature = "std_future")]
+impl StdFuture for EventList {
+    type Output = OclResult<()>;
+
+    /// Polls each event from this list.
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext) -> StdPoll<Self::Output> {
+        poll_events_std(self.as_slice(), cx)
+    }
+}
+
 unsafe impl<'a> ClNullEventPtr for &'a mut EventList {
     #[inline] fn alloc_new(&mut self) -> *mut cl_event { self._alloc_new() }
 
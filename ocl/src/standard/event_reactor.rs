@@ -0,0 +1,361 @@
+//! A reactor which wakes tasks waiting on events.
+
+use std::fmt;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+#[cfg(feature = "std_future")]
+use std::task::Waker;
+use futures::task::{self, Task};
+use ffi::cl_event;
+use core::ffi::c_void;
+use core::{self, CommandExecutionStatus};
+use error::Result as OclResult;
+use standard::{Event, box_raw_void};
+
+
+lazy_static! {
+    static ref GLOBAL_REACTOR: EventReactor = EventReactor::new(ReactorMode::Callback);
+}
+
+
+/// Specifies how an `EventReactor` is notified of event completion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReactorMode {
+    /// A single event callback is set for each pending event.
+    Callback,
+    /// Pending events are polled (`core::event_status`) by a reactor thread
+    /// at the specified interval.
+    ///
+    /// Use this mode with drivers whose event callbacks are unreliable. The
+    /// reactor thread exits whenever no events are pending.
+    Poll(Duration),
+}
+
+
+/// A task waiting on an event.
+enum Waiter {
+    Task(Task),
+    #[cfg(feature = "std_future")]
+    Waker(Waker),
+}
+
+impl Waiter {
+    fn wake(self) {
+        match self {
+            Waiter::Task(task) => task.notify(),
+            #[cfg(feature = "std_future")]
+            Waiter::Waker(waker) => waker.wake(),
+        }
+    }
+}
+
+
+/// A pending event and the tasks waiting on it.
+struct Pending {
+    event: Event,
+    waiters: Vec<Waiter>,
+    callback_is_set: bool,
+}
+
+impl Pending {
+    /// Wakes all waiting tasks.
+    fn wake_all(self) {
+        for waiter in self.waiters {
+            waiter.wake();
+        }
+    }
+}
+
+
+/// The state shared between an `EventReactor`, its event callbacks, and its
+/// polling thread.
+struct State {
+    pending: HashMap<usize, Pending>,
+    mode: ReactorMode,
+    thread_is_running: bool,
+}
+
+
+/// Locks `state`, ignoring poisoning (the state is consistent whenever the
+/// lock is released).
+fn lock(state: &Mutex<State>) -> MutexGuard<State> {
+    match state.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+
+/// Removes the pending event keyed by `key` and wakes its waiting tasks.
+fn complete(state: &Mutex<State>, key: usize) {
+    let pending = lock(state).pending.remove(&key);
+    if let Some(pending) = pending {
+        pending.wake_all();
+    }
+}
+
+
+/// Returns the key of `event` (its pointer).
+fn event_key(event: &Event) -> usize {
+    unsafe { *event.as_core().as_ptr_ref() as usize }
+}
+
+
+/// Returns true if `event` is complete or has terminated abnormally.
+fn is_terminated(event: &Event) -> bool {
+    match core::event_status(event) {
+        Ok(CommandExecutionStatus::Complete) => true,
+        Ok(_) => false,
+        // Woken tasks resolve to the error when polled (`Event::poll_complete`).
+        Err(_) => true,
+    }
+}
+
+
+/// The event callback set for each pending event in `ReactorMode::Callback`.
+extern "C" fn _complete_event(event_ptr: cl_event, event_status: i32, user_data: *mut c_void) {
+    let _ = event_status;
+    let state = unsafe { Box::from_raw(user_data as *mut Arc<Mutex<State>>) };
+    complete(&state, event_ptr as usize);
+}
+
+
+/// The reactor thread loop used in `ReactorMode::Poll`.
+fn poll_loop(state: &Mutex<State>) {
+    loop {
+        let (interval, terminated) = {
+            let mut state = lock(state);
+
+            let interval = match state.mode {
+                ReactorMode::Poll(interval) if !state.pending.is_empty() => interval,
+                _ => {
+                    state.thread_is_running = false;
+                    return;
+                },
+            };
+
+            let keys: Vec<usize> = state.pending.iter()
+                .filter(|&(_, pending)| is_terminated(&pending.event))
+                .map(|(&key, _)| key)
+                .collect();
+
+            let terminated: Vec<Pending> = keys.iter()
+                .filter_map(|key| state.pending.remove(key))
+                .collect();
+
+            (interval, terminated)
+        };
+
+        for pending in terminated {
+            pending.wake_all();
+        }
+
+        thread::sleep(interval);
+    }
+}
+
+
+/// Wakes tasks waiting on events, using a single registration per event.
+///
+/// The `Future` implementations of `Event`, `EventList`, `EventArray`, and
+/// `FutureMemMap` register the current task with the global reactor
+/// (`EventReactor::global`) each time they are polled and not yet complete.
+/// Only the first registration for an event sets an event callback (or, in
+/// `ReactorMode::Poll`, adds it to the set of polled events). Subsequent
+/// registrations add the task to the event's waiters unless already present.
+///
+/// All waiting tasks are woken once the event completes or terminates
+/// abnormally.
+///
+#[derive(Clone)]
+pub struct EventReactor {
+    state: Arc<Mutex<State>>,
+}
+
+impl EventReactor {
+    /// Returns a new `EventReactor`.
+    ///
+    /// Most uses will instead want the global reactor
+    /// (`EventReactor::global`).
+    pub fn new(mode: ReactorMode) -> EventReactor {
+        EventReactor {
+            state: Arc::new(Mutex::new(State {
+                pending: HashMap::new(),
+                mode: mode,
+                thread_is_running: false,
+            })),
+        }
+    }
+
+    /// Returns the global reactor, used by all event-based futures.
+    ///
+    /// The global reactor initially uses `ReactorMode::Callback`.
+    pub fn global() -> &'static EventReactor {
+        &GLOBAL_REACTOR
+    }
+
+    /// Returns the current mode.
+    pub fn mode(&self) -> ReactorMode {
+        lock(&self.state).mode
+    }
+
+    /// Sets the mode.
+    ///
+    /// Events already pending when switching to `ReactorMode::Callback` have
+    /// callbacks set. Events already pending when switching to
+    /// `ReactorMode::Poll` are polled along with any callbacks already set.
+    pub fn set_mode(&self, mode: ReactorMode) -> OclResult<()> {
+        let events: Vec<Event> = {
+            let mut state = lock(&self.state);
+            state.mode = mode;
+
+            match mode {
+                ReactorMode::Callback => {
+                    state.pending.values_mut()
+                        .filter(|pending| !pending.callback_is_set)
+                        .map(|pending| {
+                            pending.callback_is_set = true;
+                            pending.event.clone()
+                        })
+                        .collect()
+                },
+                ReactorMode::Poll(_) => {
+                    if !state.pending.is_empty() {
+                        self.spawn_poll_thread(&mut state)?;
+                    }
+                    Vec::new()
+                },
+            }
+        };
+
+        for event in events.iter() {
+            if let Err(err) = self.set_callback(event) {
+                complete(&self.state, event_key(event));
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of events currently pending.
+    pub fn pending_count(&self) -> usize {
+        lock(&self.state).pending.len()
+    }
+
+    /// Registers the current task to be notified when `event` completes.
+    ///
+    /// ## Panics
+    ///
+    /// This function will panic if a task is not currently being executed.
+    /// That is, this method can be dangerous to call outside of an
+    /// implementation of poll.
+    pub fn register(&self, event: &Event) -> OclResult<()> {
+        let task = task::current();
+        self.register_waiter(event, Waiter::Task(task), |waiter| match *waiter {
+            Waiter::Task(ref task) => task.will_notify_current(),
+            #[cfg(feature = "std_future")]
+            _ => false,
+        })
+    }
+
+    /// Registers `waker` to be woken when `event` completes.
+    #[cfg(feature = "std_future")]
+    pub fn register_waker(&self, event: &Event, waker: &Waker) -> OclResult<()> {
+        self.register_waiter(event, Waiter::Waker(waker.clone()), |waiter| match *waiter {
+            Waiter::Waker(ref w) => w.will_wake(waker),
+            _ => false,
+        })
+    }
+
+    /// Adds `waiter` to the waiters of `event` unless an equivalent waiter
+    /// (`is_registered`) is already present.
+    fn register_waiter<F>(&self, event: &Event, waiter: Waiter, is_registered: F) -> OclResult<()>
+            where F: Fn(&Waiter) -> bool {
+        let key = event_key(event);
+
+        let set_callback = {
+            let mut state = lock(&self.state);
+            let mode = state.mode;
+
+            match state.pending.entry(key) {
+                Entry::Occupied(mut entry) => {
+                    let pending = entry.get_mut();
+                    if !pending.waiters.iter().any(|w| is_registered(w)) {
+                        pending.waiters.push(waiter);
+                    }
+                    return Ok(());
+                },
+                Entry::Vacant(entry) => {
+                    entry.insert(Pending {
+                        event: event.clone(),
+                        waiters: vec![waiter],
+                        callback_is_set: mode == ReactorMode::Callback,
+                    });
+                },
+            }
+
+            match mode {
+                ReactorMode::Callback => true,
+                ReactorMode::Poll(_) => {
+                    if let Err(err) = self.spawn_poll_thread(&mut state) {
+                        // Wake any waiters so that they are not lost:
+                        if let Some(pending) = state.pending.remove(&key) {
+                            drop(state);
+                            pending.wake_all();
+                        }
+                        return Err(err);
+                    }
+                    false
+                },
+            }
+        };
+
+        // The lock must not be held here: a callback set on an event which
+        // has already completed may be called immediately.
+        if set_callback {
+            if let Err(err) = self.set_callback(event) {
+                complete(&self.state, key);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the event callback which completes `event`.
+    fn set_callback(&self, event: &Event) -> OclResult<()> {
+        let state_ptr = box_raw_void(self.state.clone());
+
+        unsafe {
+            event.set_callback(_complete_event, state_ptr).map_err(|err| {
+                drop(Box::from_raw(state_ptr as *mut Arc<Mutex<State>>));
+                err.into()
+            })
+        }
+    }
+
+    /// Spawns the polling thread if it is not already running.
+    fn spawn_poll_thread(&self, state: &mut State) -> OclResult<()> {
+        if state.thread_is_running { return Ok(()); }
+
+        let thread_state = self.state.clone();
+        thread::Builder::new()
+            .name("ocl-event-reactor".to_owned())
+            .spawn(move || poll_loop(&thread_state))?;
+
+        state.thread_is_running = true;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for EventReactor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = lock(&self.state);
+        f.debug_struct("EventReactor")
+            .field("mode", &state.mode)
+            .field("pending_count", &state.pending.len())
+            .finish()
+    }
+}
@@ -10,12 +10,13 @@ use std::mem;
 use std::ops::{Deref, DerefMut};
 use std::marker::PhantomData;
 use core::error::{Result as OclCoreResult};
+use error::{Error as OclError, Result as OclResult};
 use core::{self, OclPrm, Mem as MemCore, MemFlags, MemObjectType, ImageFormatParseResult,
     ImageFormat, ImageDescriptor, ImageInfo, ImageInfoResult, MemInfo, MemInfoResult,
     ImageChannelOrder, ImageChannelDataType, AsMem, MemCmdRw, MemCmdAll,
     MapFlags};
 use standard::{Context, Queue, SpatialDims, ClNullEventPtrEnum, ClWaitListPtrEnum,
-    QueCtx};
+    QueCtx, Profiler, ProfileKey, ProfiledEnew};
 use ::MemMap;
 
 #[cfg(not(feature="opencl_vendor_mesa"))]
@@ -83,6 +84,8 @@ pub struct ImageCmd<'c, T: 'c> {
     kind: ImageCmdKind<'c, T>,
     ewait: Option<ClWaitListPtrEnum<'c>>,
     enew: Option<ClNullEventPtrEnum<'c>>,
+    profiler: Option<&'c Profiler>,
+    label: Option<String>,
     mem_dims: [usize; 3],
 }
 
@@ -104,6 +107,8 @@ impl<'c, T: 'c + OclPrm> ImageCmd<'c, T> {
             kind: ImageCmdKind::Unspecified,
             ewait: None,
             enew: None,
+            profiler: None,
+            label: None,
             mem_dims: dims,
         }
     }
@@ -414,55 +419,120 @@ impl<'c, T: 'c + OclPrm> ImageCmd<'c, T> {
         self
     }
 
+    /// Specifies a profiler to record this command with.
+    ///
+    /// Overrides the profiler of the queue (see `Queue::set_profiler`) if
+    /// one is set. The queue used must have been created with profiling
+    /// enabled.
+    pub fn profiler<'p>(mut self, profiler: &'p Profiler) -> ImageCmd<'c, T>
+            where 'p: 'c {
+        self.profiler = Some(profiler);
+        self
+    }
+
+    /// Specifies a label identifying this command in traces (see
+    /// `TraceRecorder`).
+    ///
+    /// Defaults to the kind of command (`"image:read"`, etc.).
+    pub fn label<S: Into<String>>(mut self, label: S) -> ImageCmd<'c, T> {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Returns the image memory object this command operates on.
+    pub fn mem(&self) -> &'c MemCore {
+        self.obj_core
+    }
+
+    /// Returns the type of operation this command will perform.
+    pub fn kind(&self) -> &ImageCmdKind<'c, T> {
+        &self.kind
+    }
+
     /// Enqueues this command.
     ///
     /// * TODO: FOR COPY, FILL, AND COPYTOBUFFER -- ENSURE PITCHES ARE BOTH
     ///   UNSET.
-    pub fn enq(self) -> OclCoreResult<()> {
+    pub fn enq(self) -> OclResult<()> {
         let queue = match self.queue {
             Some(q) => q,
-            None => return Err("ImageCmd::enq: No queue set.".into()),
+            None => return Err(ImageCmdError::NoQueue.into()),
         };
 
-        match self.kind {
+        let profiler = self.profiler.or_else(|| queue.profiler());
+        let mut profiled = ProfiledEnew::new(profiler, self.enew)
+            .trace(queue.trace_recorder(), queue.as_core(), self.label, self.ewait.as_ref());
+
+        let kind = match self.kind {
             ImageCmdKind::Read { data } => {
                 unsafe { core::enqueue_read_image(queue, self.obj_core, self.block,
                     self.origin, self.region, self.row_pitch_bytes, self.slc_pitch_bytes, data, self.ewait,
-                    self.enew) }
+                    profiled.enew())?; }
+                "read"
             },
             ImageCmdKind::Write { data } => {
                 unsafe {
                     core::enqueue_write_image(queue, self.obj_core, self.block,
                         self.origin, self.region, self.row_pitch_bytes, self.slc_pitch_bytes, data, self.ewait,
-                        self.enew)
+                        profiled.enew())?;
                 }
+                "write"
             },
             ImageCmdKind::Copy { dst_image, dst_origin } => {
                 core::enqueue_copy_image(queue, self.obj_core, dst_image, self.origin,
-                    dst_origin, self.region, self.ewait, self.enew)
+                    dst_origin, self.region, self.ewait, profiled.enew())?;
+                "copy"
             },
 
             #[cfg(not(feature="opencl_vendor_mesa"))]
             ImageCmdKind::GLAcquire => {
                 // core::enqueue_acquire_gl_buffer(queue, self.obj_core, self.ewait, self.enew)
                 let buf_slc = unsafe { std::slice::from_raw_parts(self.obj_core, 1) };
-                core::enqueue_acquire_gl_objects(queue, buf_slc, self.ewait, self.enew)
+                core::enqueue_acquire_gl_objects(queue, buf_slc, self.ewait, profiled.enew())?;
+                "gl_acquire"
             },
 
             #[cfg(not(feature="opencl_vendor_mesa"))]
             ImageCmdKind::GLRelease => {
                 // core::enqueue_release_gl_buffer(queue, self.obj_core, self.ewait, self.enew)
                 let buf_slc = unsafe { std::slice::from_raw_parts(self.obj_core, 1) };
-                core::enqueue_release_gl_objects(queue, buf_slc, self.ewait, self.enew)
+                core::enqueue_release_gl_objects(queue, buf_slc, self.ewait, profiled.enew())?;
+                "gl_release"
             },
 
-            ImageCmdKind::Unspecified => Err("ocl::ImageCmd::enq(): No operation \
-                specified. Use '.read(...)', 'write(...)', etc. before calling '.enq()'.".into()),
+            ImageCmdKind::Unspecified => return Err(ImageCmdError::NoOperation.into()),
             _ => unimplemented!(),
+        };
+
+        profiled.finish(ProfileKey::Image(kind));
+        Ok(())
+    }
+}
+
+
+
+/// An image command error.
+#[derive(Debug)]
+pub enum ImageCmdError {
+    NoQueue,
+    NoOperation,
+    GlTextureBufferMipLevel(i32),
+}
+
+impl std::fmt::Display for ImageCmdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ImageCmdError::NoQueue => write!(f, "No queue specified."),
+            ImageCmdError::NoOperation => write!(f, "No operation specified. Use '.read(...)', \
+                'write(...)', etc. before calling '.enq()'."),
+            ImageCmdError::GlTextureBufferMipLevel(miplevel) => write!(f, "If texture_target is \
+                GL_TEXTURE_BUFFER, miplevel must be 0 (miplevel: {}). Implementations may return \
+                CL_INVALID_OPERATION for miplevel values > 0", miplevel),
         }
     }
 }
 
+impl std::error::Error for ImageCmdError {}
 
 
 /// A buffer command builder used to enqueue maps.
@@ -596,10 +666,10 @@ impl<'c, T> ImageMapCmd<'c, T> where T: OclPrm {
     ///
     /// * TODO: FOR COPY, FILL, AND COPYTOBUFFER -- ENSURE PITCHES ARE BOTH UNSET.
     #[allow(unused_variables, unreachable_code)]
-    pub fn enq(self) -> OclCoreResult<MemMap<T>> {
+    pub fn enq(self) -> OclResult<MemMap<T>> {
         let queue = match self.cmd.queue {
             Some(q) => q,
-            None => return Err("ImageCmd::enq: No queue set.".into()),
+            None => return Err(ImageCmdError::NoQueue.into()),
         };
 
         let flags = self.flags.unwrap_or(MapFlags::empty());
@@ -683,7 +753,7 @@ impl<T: OclPrm> Image<T> {
     // pub fn new(queue: Queue, flags: MemFlags, image_format: ImageFormat,
     //         image_desc: ImageDescriptor, host_data: Option<&[E]>) -> OclCoreResult<Image<E>>
     pub fn new<'o, Q>(que_ctx: Q, flags: MemFlags, image_format: ImageFormat,
-            image_desc: ImageDescriptor, host_data: Option<&[T]>) -> OclCoreResult<Image<T>>
+            image_desc: ImageDescriptor, host_data: Option<&[T]>) -> OclResult<Image<T>>
             where Q: Into<QueCtx<'o>> {
         let que_ctx = que_ctx.into();
         let context = que_ctx.context_cloned();
@@ -700,8 +770,7 @@ impl<T: OclPrm> Image<T> {
 
         let pixel_element_len = match core::get_image_info(&obj_core, ImageInfo::ElementSize)? {
             ImageInfoResult::ElementSize(s) => s / mem::size_of::<T>(),
-            _ => return Err("ocl::Image::element_len(): \
-                Unexpected 'ImageInfoResult' variant.".into()),
+            _ => unreachable!(),
         };
 
         let dims = [image_desc.image_width, image_desc.image_height, image_desc.image_depth].into();
@@ -722,7 +791,7 @@ impl<T: OclPrm> Image<T> {
     #[cfg(not(feature="opencl_vendor_mesa"))]
     pub fn from_gl_texture<'o, Q>(que_ctx: Q, flags: MemFlags, image_desc: ImageDescriptor,
             texture_target: GlTextureTarget, miplevel: cl_GLint, texture: cl_GLuint)
-            -> OclCoreResult<Image<T>>
+            -> OclResult<Image<T>>
             where Q: Into<QueCtx<'o>>
     {
         let que_ctx = que_ctx.into();
@@ -730,8 +799,7 @@ impl<T: OclPrm> Image<T> {
         let device_versions = context.device_versions()?;
 
         if texture_target == GlTextureTarget::GlTextureBuffer && miplevel != 0 {
-            return Err("If texture_target is GL_TEXTURE_BUFFER, miplevel must be 0.\
-                Implementations may return CL_INVALID_OPERATION for miplevel values > 0".into());
+            return Err(ImageCmdError::GlTextureBufferMipLevel(miplevel).into());
         }
 
         let obj_core = unsafe { core::create_from_gl_texture(
@@ -746,8 +814,7 @@ impl<T: OclPrm> Image<T> {
         // FIXME can I do this from a GLTexture ?
         let pixel_element_len = match core::get_image_info(&obj_core, ImageInfo::ElementSize)? {
             ImageInfoResult::ElementSize(s) => s / mem::size_of::<T>(),
-            _ => return Err("ocl::Image::element_len(): Unexpected \
-                'ImageInfoResult' variant.".into()),
+            _ => unreachable!(),
         };
 
         let dims = [image_desc.image_width, image_desc.image_height, image_desc.image_depth].into();
@@ -767,7 +834,7 @@ impl<T: OclPrm> Image<T> {
     // [WORK IN PROGRESS]
     #[cfg(not(feature="opencl_vendor_mesa"))]
     pub fn from_gl_renderbuffer<'o, Q>(que_ctx: Q, flags: MemFlags, image_desc: ImageDescriptor,
-            renderbuffer: cl_GLuint) -> OclCoreResult<Image<T>>
+            renderbuffer: cl_GLuint) -> OclResult<Image<T>>
             where Q: Into<QueCtx<'o>>
     {
         let que_ctx = que_ctx.into();
@@ -782,8 +849,7 @@ impl<T: OclPrm> Image<T> {
         // FIXME can I do this from a renderbuffer ?
         let pixel_element_len = match core::get_image_info(&obj_core, ImageInfo::ElementSize)? {
             ImageInfoResult::ElementSize(s) => s / mem::size_of::<T>(),
-            _ => return Err("ocl::Image::element_len(): \
-                Unexpected 'ImageInfoResult' variant.".into()),
+            _ => unreachable!(),
         };
 
         let dims = [image_desc.image_width, image_desc.image_height].into();
@@ -926,6 +992,22 @@ impl<T: OclPrm> Image<T> {
         core::get_mem_object_info(&self.obj_core, info_kind)
     }
 
+    /// Registers a function to be called once the underlying memory object
+    /// has actually been released by the OpenCL runtime.
+    ///
+    /// The function is called after every clone of this image (along with
+    /// any kernels or commands referencing it) has been dropped or completed.
+    /// This makes it the right place to reclaim host memory used by an image
+    /// created with `MemFlags::USE_HOST_PTR`.
+    ///
+    /// Functions registered on the same image are called in the reverse order
+    /// of registration, on a thread of the runtime's choosing.
+    pub fn on_release<F>(&self, func: F) -> OclResult<()>
+            where F: FnOnce() + Send + 'static {
+        core::set_mem_object_destructor_callback(&self.obj_core, Box::new(func))
+            .map_err(OclError::from)
+    }
+
     /// Returns a reference to the core pointer wrapper, usable by functions in
     /// the `core` module.
     #[inline]
@@ -1317,7 +1399,7 @@ impl<'a, T> ImageBuilder<'a, T> where T: 'a + OclPrm {
 
     /// Builds with no host side image data memory specified and returns a
     /// new `Image`.
-    pub fn build(self) -> OclCoreResult<Image<T>> {
+    pub fn build(self) -> OclResult<Image<T>> {
         match self.queue_option {
             Some(qo) => {
                 Image::new(qo, self.flags, self.image_format.clone(),
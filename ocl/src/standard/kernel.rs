@@ -1,32 +1,149 @@
 //! An `OpenCL` kernel.
 
 use std;
+use std::mem;
+use std::slice;
 use std::ops::{Deref, DerefMut};
 use std::any::Any;
 use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
+use ffi::c_void;
 use core::{self, OclPrm, Kernel as KernelCore, CommandQueue as CommandQueueCore, Mem as MemCore,
-    KernelArg, KernelInfo, KernelInfoResult, KernelArgInfo, KernelArgInfoResult,
+    Sampler as SamplerCore, KernelArg, KernelInfo, KernelInfoResult, KernelArgInfo, KernelArgInfoResult,
     KernelWorkGroupInfo, KernelWorkGroupInfoResult, AsMem, MemCmdAll, ClVersions};
 use core::error::{Result as OclCoreResult, ErrorKind as OclCoreErrorKind};
 use error::{Error as OclError, Result as OclResult};
 use standard::{SpatialDims, Program, Queue, WorkDims, Sampler, Device, ClNullEventPtrEnum,
-    ClWaitListPtrEnum};
+    ClWaitListPtrEnum, Profiler, ProfileKey, ProfiledEnew, TraceRecorder, Buffer, Image};
+#[cfg(feature = "opencl_version_2_0")]
+use core::KernelExecInfo;
+#[cfg(feature = "opencl_version_2_0")]
+use standard::{SvmVec, SvmAlloc, Pipe};
+#[cfg(feature = "opencl_version_2_1")]
+use core::{KernelSubGroupInfo, KernelSubGroupInfoResult};
 pub use self::arg_type::{BaseType, Cardinality, ArgType};
 
 const PRINT_DEBUG: bool = false;
 
+
+/// A kernel related error.
+#[derive(Debug)]
+pub enum KernelError {
+    NoQueue { kernel: String },
+    GwsUnspecified { kernel: String },
+    ArgIndexOutOfRange { kernel: String, arg_index: u32 },
+    ArgCountMismatch { kernel: String, expected: u32, actual: u32 },
+    ArgTypeMismatch { kernel: String, arg_index: u32, type_name: String, arg_type: ArgType },
+    ArgPipeTypeMismatch { kernel: String, arg_index: u32, type_name: String, arg_type: ArgType },
+    ArgNameNotFound { kernel: String, name: String },
+    NoNamedArgs { kernel: String },
+    SubGroupLwsUnspecified,
+    SubGroupCountUnachievable { kernel: String, sub_group_count: usize, dim_count: usize,
+        lens: Vec<usize> },
+}
+
+impl std::fmt::Display for KernelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            KernelError::NoQueue { ref kernel } => write!(f, "No queue specified. (kernel: {})",
+                kernel),
+            KernelError::GwsUnspecified { ref kernel } => write!(f, "Global Work Size ('gws') \
+                cannot be left unspecified. Set a default for the kernel or pass a valid \
+                parameter. (kernel: {})", kernel),
+            KernelError::ArgIndexOutOfRange { ref kernel, arg_index } => write!(f, "Kernel arg \
+                index out of range. (kernel: {}, index: {})", kernel, arg_index),
+            KernelError::ArgCountMismatch { ref kernel, expected, actual } => write!(f, "Kernel \
+                has {} arguments. Expected {}. (kernel: {})", actual, expected, kernel),
+            KernelError::ArgTypeMismatch { ref kernel, arg_index, ref type_name, ref arg_type } =>
+                write!(f, "Kernel argument type mismatch. The argument at index [{}] is a '{}' \
+                ({:?}). (kernel: {})", arg_index, type_name, arg_type, kernel),
+            KernelError::ArgPipeTypeMismatch { ref kernel, arg_index, ref type_name,
+                    ref arg_type } =>
+                write!(f, "Kernel argument type mismatch. The argument at index [{}] is not a \
+                pipe of the specified packet type. It is a '{}' ({:?}). (kernel: {})", arg_index,
+                type_name, arg_type, kernel),
+            KernelError::ArgNameNotFound { ref kernel, ref name } => write!(f, "Invalid \
+                argument name: '{}'. (kernel: {})", name, kernel),
+            KernelError::NoNamedArgs { ref kernel } => write!(f, "No named arguments declared. \
+                (kernel: {})", kernel),
+            KernelError::SubGroupLwsUnspecified => write!(f, "The local work size must be \
+                specified to query sub-group info."),
+            KernelError::SubGroupCountUnachievable { ref kernel, sub_group_count, dim_count,
+                    ref lens } =>
+                write!(f, "No {}-dimensional local work size yields exactly {} sub-groups \
+                (device returned: {:?}). (kernel: {})", dim_count, sub_group_count, lens, kernel),
+        }
+    }
+}
+
+impl std::error::Error for KernelError {}
+
+
+/// Returns the name of a kernel.
+fn kernel_name(kernel: &KernelCore) -> OclResult<String> {
+    core::get_kernel_info(kernel, KernelInfo::FunctionName).map(|r| r.into())
+        .map_err(OclError::from)
+}
+
+/// A kernel argument value, used with `Kernel::set_arg`.
+///
+/// Scalars and vectors (`T`), buffers (`&Buffer<T>`), images (`&Image<T>`),
+/// and samplers (`&Sampler`) convert into the appropriate variant. Use
+/// `ArgVal::Local` to specify the length of a local variable.
+///
+/// The types of image and sampler arguments are not verified.
+pub enum ArgVal<'a, T: OclPrm> {
+    Scalar(T),
+    Vector(T),
+    Buffer(&'a MemCore),
+    Image(&'a MemCore),
+    /// A null buffer or image.
+    MemNull,
+    Sampler(&'a Sampler),
+    SamplerNull,
+    /// The length, in elements of type `T`, of a local variable.
+    Local(usize),
+}
+
+impl<'a, T: OclPrm> From<T> for ArgVal<'a, T> {
+    fn from(scalar: T) -> ArgVal<'a, T> {
+        ArgVal::Scalar(scalar)
+    }
+}
+
+impl<'a, T: OclPrm> From<&'a Buffer<T>> for ArgVal<'a, T> {
+    fn from(buffer: &'a Buffer<T>) -> ArgVal<'a, T> {
+        ArgVal::Buffer(buffer.as_core())
+    }
+}
+
+impl<'a, T: OclPrm> From<&'a Image<T>> for ArgVal<'a, T> {
+    fn from(image: &'a Image<T>) -> ArgVal<'a, T> {
+        ArgVal::Image(image.as_core())
+    }
+}
+
+impl<'a> From<&'a Sampler> for ArgVal<'a, u64> {
+    fn from(sampler: &'a Sampler) -> ArgVal<'a, u64> {
+        ArgVal::Sampler(sampler)
+    }
+}
+
+
 /// A kernel command builder used to queue a kernel with a mix of default
 /// and optionally specified arguments.
 #[must_use = "commands do nothing unless enqueued"]
 pub struct KernelCmd<'k> {
     queue: Option<&'k CommandQueueCore>,
-    kernel: &'k KernelCore,
+    kernel: &'k Kernel,
     gwo: SpatialDims,
     gws: SpatialDims,
     lws: SpatialDims,
     wait_events: Option<ClWaitListPtrEnum<'k>>,
     new_event: Option<ClNullEventPtrEnum<'k>>,
+    profiler: Option<&'k Profiler>,
+    trace_recorder: Option<&'k TraceRecorder>,
+    label: Option<String>,
 }
 
 /// A kernel enqueue command.
@@ -133,6 +250,32 @@ impl<'k> KernelCmd<'k> {
         self
     }
 
+    /// Specifies a profiler to record this command with.
+    ///
+    /// Overrides the profiler of the kernel's default queue (see
+    /// `Queue::set_profiler`) if one is set. The queue used must have been
+    /// created with profiling enabled.
+    pub fn profiler<'p>(mut self, profiler: &'p Profiler) -> KernelCmd<'k>
+            where 'p: 'k {
+        self.profiler = Some(profiler);
+        self
+    }
+
+    /// Specifies a label identifying this command in traces (see
+    /// `TraceRecorder`).
+    ///
+    /// Defaults to the kernel name prefixed by `"kernel:"` (`"kernel:add"`,
+    /// etc.).
+    pub fn label<S: Into<String>>(mut self, label: S) -> KernelCmd<'k> {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Returns the kernel to be enqueued.
+    pub fn kernel(&self) -> &'k Kernel {
+        self.kernel
+    }
+
     /// Enqueues this kernel command.
     ///
     /// # Safety
@@ -143,26 +286,71 @@ impl<'k> KernelCmd<'k> {
     pub unsafe fn enq(self) -> OclResult<()> {
         let queue = match self.queue {
             Some(q) => q,
-            None => return Err("KernelCmd::enq: No queue specified.".into()),
+            None => return Err(KernelError::NoQueue { kernel: kernel_name(self.kernel.as_core())? }.into()),
         };
 
         let dim_count = self.gws.dim_count();
 
         let gws = match self.gws.to_work_size() {
             Some(gws) => gws,
-            None => return Err("ocl::KernelCmd::enqueue: Global Work Size ('gws') \
-                cannot be left unspecified. Set a default for the kernel or pass a \
-                valid parameter.".into()),
+            None => return Err(KernelError::GwsUnspecified { kernel: kernel_name(self.kernel.as_core())? }
+                .into()),
         };
 
         if PRINT_DEBUG {
             println!("Enqueuing kernel: '{}'...",
-                core::get_kernel_info(self.kernel, KernelInfo::FunctionName)?);
+                core::get_kernel_info(self.kernel.as_core(), KernelInfo::FunctionName)?);
         }
 
-        core::enqueue_kernel(queue, self.kernel, dim_count, self.gwo.to_work_offset(),
-            &gws, self.lws.to_work_size(), self.wait_events, self.new_event)
-            .map_err(OclError::from)
+        let mut profiled = ProfiledEnew::new(self.profiler, self.new_event)
+            .trace(self.trace_recorder, queue, self.label, self.wait_events.as_ref());
+
+        core::enqueue_kernel(queue, self.kernel.as_core(), dim_count, self.gwo.to_work_offset(),
+            &gws, self.lws.to_work_size(), self.wait_events, profiled.enew())?;
+
+        if profiled.is_recording() {
+            profiled.finish(ProfileKey::Kernel(kernel_name(self.kernel.as_core())?));
+        }
+        Ok(())
+    }
+}
+
+
+/// A copy of the raw value of a kernel argument, recorded so that it can be
+/// set again on a re-created kernel (see `Kernel::duplicate`).
+#[derive(Clone, Debug)]
+struct RawArg {
+    size: usize,
+    /// The bytes pointed to by the argument value or `None` if null.
+    value: Option<Vec<u8>>,
+}
+
+impl RawArg {
+    /// Returns a copy of the value that `core::set_kernel_arg` would pass to
+    /// `clSetKernelArg` for `arg`.
+    fn from_kernel_arg<T: OclPrm>(arg: &KernelArg<T>) -> RawArg {
+        let (size, value): (usize, *const c_void) = match *arg {
+            KernelArg::Mem(mem_core) => (mem::size_of::<MemCore>(),
+                mem_core as *const MemCore as *const c_void),
+            KernelArg::Sampler(sampler_core) => (mem::size_of::<SamplerCore>(),
+                sampler_core as *const SamplerCore as *const c_void),
+            KernelArg::Scalar(ref scalar) | KernelArg::Vector(ref scalar) => {
+                (mem::size_of::<T>(), scalar as *const T as *const c_void)
+            },
+            KernelArg::Local(&length) => (mem::size_of::<T>() * length, std::ptr::null()),
+            KernelArg::UnsafePointer { size, value } => (size, value),
+            KernelArg::MemNull | KernelArg::SamplerNull => {
+                (mem::size_of::<*const c_void>(), std::ptr::null())
+            },
+        };
+
+        let value = if value.is_null() {
+            None
+        } else {
+            Some(unsafe { slice::from_raw_parts(value as *const u8, size).to_vec() })
+        };
+
+        RawArg { size: size, value: value }
     }
 }
 
@@ -185,6 +373,10 @@ impl<'k> KernelCmd<'k> {
 ///     - Global Work Offset
 ///     - Global Work Size
 ///     - Local Work Size
+///
+/// Clones of a `Kernel` share the same underlying kernel object and
+/// therefore the same argument values. Use `::duplicate` to create an
+/// independent kernel (for use from another thread, for example).
 //
 // ### `Clone`, `Send`, and segfaults
 //
@@ -246,6 +438,11 @@ pub struct Kernel {
     obj_core: KernelCore,
     named_args: Option<HashMap<&'static str, u32>>,
     mem_args: Arc<Mutex<Vec<Option<MemCore>>>>,
+    /// Retained samplers referred to by `raw_args`:
+    sampler_args: Arc<Mutex<Vec<Option<SamplerCore>>>>,
+    raw_args: Arc<Mutex<Vec<Option<RawArg>>>>,
+    #[cfg(feature = "opencl_version_2_0")]
+    svm_args: Arc<Mutex<Vec<Option<Arc<SvmAlloc>>>>>,
     new_arg_count: u32,
     queue: Option<Queue>,
     gwo: SpatialDims,
@@ -253,6 +450,8 @@ pub struct Kernel {
     lws: SpatialDims,
     num_args: u32,
     arg_types: Vec<ArgType>,
+    /// Empty if argument names are unavailable:
+    arg_names: Vec<String>,
     /// Bypasses argument type check if true:
     bypass_arg_check: bool,
 }
@@ -263,7 +462,15 @@ impl Kernel {
     pub fn new<S: AsRef<str>>(name: S, program: &Program) -> OclResult<Kernel> {
         // let name = name.into();
         let obj_core = core::create_kernel(program, name)?;
+        Kernel::from_core(obj_core)
+    }
 
+    /// Returns a new kernel wrapping a pre-created core kernel, caching its
+    /// argument types.
+    ///
+    /// Prefer `::new` or `Program::create_all_kernels` to create a new
+    /// `Kernel`.
+    pub fn from_core(obj_core: KernelCore) -> OclResult<Kernel> {
         let num_args = match core::get_kernel_info(&obj_core, KernelInfo::NumArgs) {
             Ok(KernelInfoResult::NumArgs(num)) => num,
             Err(err) => return Err(OclError::from(err)),
@@ -289,6 +496,19 @@ impl Kernel {
             arg_types.push(arg_type);
         }
 
+        // Cache argument names, unless unavailable (if the program was not
+        // built from source, for example):
+        let arg_names = if bypass_arg_check {
+            Vec::new()
+        } else {
+            (0..num_args).map(|arg_idx| {
+                match arg_info(&obj_core, arg_idx, KernelArgInfo::Name) {
+                    Ok(KernelArgInfoResult::Name(name)) => Some(name),
+                    _ => None,
+                }
+            }).collect::<Option<Vec<_>>>().unwrap_or_default()
+        };
+
         let mem_args = vec![None; num_args as usize];
 
         Ok(Kernel {
@@ -296,12 +516,17 @@ impl Kernel {
             named_args: None,
             new_arg_count: 0,
             mem_args: Arc::new(Mutex::new(mem_args)),
+            sampler_args: Arc::new(Mutex::new(vec![None; num_args as usize])),
+            raw_args: Arc::new(Mutex::new(vec![None; num_args as usize])),
+            #[cfg(feature = "opencl_version_2_0")]
+            svm_args: Arc::new(Mutex::new(vec![None; num_args as usize])),
             queue: None,
             gwo: SpatialDims::Unspecified,
             gws: SpatialDims::Unspecified,
             lws: SpatialDims::Unspecified,
             num_args: num_args,
             arg_types: arg_types,
+            arg_names: arg_names,
             bypass_arg_check,
         })
     }
@@ -398,6 +623,30 @@ impl Kernel {
         self
     }
 
+    /// Adds a new argument to the kernel specifying the pipe object
+    /// represented by `pipe` (builder-style). Argument is added to the
+    /// bottom of the argument order.
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    #[cfg(feature = "opencl_version_2_0")]
+    pub fn arg_pipe<T>(mut self, pipe: &Pipe<T>) -> Kernel
+            where T: OclPrm + 'static {
+        self.new_arg_pipe(Some(pipe));
+        self
+    }
+
+    /// Adds a new argument to the kernel specifying the shared virtual memory
+    /// pointed to by `svm` (builder-style). Argument is added to the bottom
+    /// of the argument order.
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    #[cfg(feature = "opencl_version_2_0")]
+    pub fn arg_svm<T>(mut self, svm: &SvmVec<T>) -> Kernel
+            where T: OclPrm + 'static {
+        self.new_arg_svm(Some(svm));
+        self
+    }
+
     /// Adds a new named argument (in order) specifying the value: `scalar`
     /// (builder-style).
     ///
@@ -452,6 +701,36 @@ impl Kernel {
         self
     }
 
+    /// Adds a new named pipe argument specifying the pipe object
+    /// represented by `pipe` (builder-style).
+    ///
+    /// Named arguments can be easily modified later using
+    /// `::set_arg_pipe_named()`.
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    #[cfg(feature = "opencl_version_2_0")]
+    pub fn arg_pipe_named<T>(mut self, name: &'static str, pipe_opt: Option<&Pipe<T>>) -> Kernel
+            where T: OclPrm + 'static {
+        let arg_idx = self.new_arg_pipe(pipe_opt);
+        self.insert_named_arg(name, arg_idx);
+        self
+    }
+
+    /// Adds a new named shared virtual memory argument specifying `svm`
+    /// (builder-style).
+    ///
+    /// Named arguments can be easily modified later using
+    /// `::set_arg_svm_named()`.
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    #[cfg(feature = "opencl_version_2_0")]
+    pub fn arg_svm_named<T>(mut self, name: &'static str, svm_opt: Option<&SvmVec<T>>) -> Kernel
+            where T: OclPrm + 'static {
+        let arg_idx = self.new_arg_svm(svm_opt);
+        self.insert_named_arg(name, arg_idx);
+        self
+    }
+
     /// Modifies the kernel argument named: `name`.
     ///
     /// ## Panics [FIXME]
@@ -516,6 +795,82 @@ impl Kernel {
         }.and(Ok(self))
     }
 
+    /// Modifies the pipe kernel argument named: `name`.
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    #[cfg(feature = "opencl_version_2_0")]
+    pub fn set_arg_pipe_named<'a, T>(&'a mut self, name: &'static str,
+            pipe_opt: Option<&Pipe<T>>) -> OclResult<&'a mut Kernel>
+            where T: OclPrm + 'static {
+        let arg_idx = self.resolve_named_arg_idx(name)?;
+        self._set_arg_pipe(arg_idx, pipe_opt).and(Ok(self))
+    }
+
+    /// Modifies the shared virtual memory kernel argument named: `name`.
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    #[cfg(feature = "opencl_version_2_0")]
+    pub fn set_arg_svm_named<'a, T>(&'a mut self, name: &'static str,
+            svm_opt: Option<&SvmVec<T>>) -> OclResult<&'a mut Kernel>
+            where T: OclPrm + 'static {
+        let arg_idx = self.resolve_named_arg_idx(name)?;
+        self._set_arg_svm(arg_idx, svm_opt).and(Ok(self))
+    }
+
+    /// Modifies the kernel argument named: `name`, as declared in the kernel
+    /// source.
+    ///
+    /// The argument index is resolved from the kernel's argument info or, if
+    /// unavailable, from the names given to `::arg_..._named`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,ignore
+    /// kernel.set_arg("buffer", &buffer)?;
+    /// kernel.set_arg("addend", 10.0f32)?;
+    /// kernel.set_arg("scratch", ArgVal::Local::<f32>(64))?;
+    /// kernel.set_arg("sampler", &sampler)?;
+    /// ```
+    pub fn set_arg<'a, 'b, N, T, A>(&'a mut self, name: N, arg: A) -> OclResult<&'a mut Kernel>
+            where N: AsRef<str>, T: OclPrm + 'static, A: Into<ArgVal<'b, T>> {
+        let arg_idx = self.arg_idx(name.as_ref())?;
+        self.set_arg_idx(arg_idx, arg)
+    }
+
+    /// Modifies the kernel argument at index `arg_idx`.
+    ///
+    /// See `::set_arg`.
+    pub fn set_arg_idx<'a, 'b, T, A>(&'a mut self, arg_idx: u32, arg: A)
+            -> OclResult<&'a mut Kernel>
+            where T: OclPrm + 'static, A: Into<ArgVal<'b, T>> {
+        if arg_idx >= self.num_args {
+            return Err(KernelError::ArgIndexOutOfRange { kernel: self.name()?,
+                arg_index: arg_idx }.into());
+        }
+
+        match arg.into() {
+            ArgVal::Scalar(scalar) => self._set_arg::<T>(arg_idx, KernelArg::Scalar(scalar)),
+            ArgVal::Vector(vector) => self._set_arg::<T>(arg_idx, KernelArg::Vector(vector)),
+            ArgVal::Buffer(buffer) => self._set_arg::<T>(arg_idx, KernelArg::Mem(buffer)),
+            // Type is ignored:
+            ArgVal::Image(image) => self._set_arg::<u64>(arg_idx, KernelArg::Mem(image)),
+            ArgVal::MemNull => self._set_arg::<T>(arg_idx, KernelArg::MemNull),
+            ArgVal::Sampler(sampler) => self._set_arg::<u64>(arg_idx, KernelArg::Sampler(sampler)),
+            ArgVal::SamplerNull => self._set_arg::<u64>(arg_idx, KernelArg::SamplerNull),
+            ArgVal::Local(length) => self._set_arg::<T>(arg_idx, KernelArg::Local(&length)),
+        }.and(Ok(self))
+    }
+
+    /// Passes additional execution information to the implementation, such
+    /// as shared virtual memory pointers which this kernel accesses
+    /// indirectly (stored within another SVM allocation, for example).
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    #[cfg(feature = "opencl_version_2_0")]
+    pub fn set_exec_info(&self, exec_info: KernelExecInfo) -> OclResult<()> {
+        core::set_kernel_exec_info(&self.obj_core, exec_info, None).map_err(OclError::from)
+    }
+
     /// Sets the value of a named sampler argument.
     ///
     /// ## Panics [FIXME]
@@ -529,9 +884,12 @@ impl Kernel {
     /// Returns a command builder which is used to chain parameters of an
     /// 'enqueue' command together.
     pub fn cmd(&self) -> KernelCmd {
-        KernelCmd { queue: self.queue.as_ref().map(|q| q.as_ref()), kernel: &self.obj_core,
+        KernelCmd { queue: self.queue.as_ref().map(|q| q.as_ref()), kernel: self,
             gwo: self.gwo, gws: self.gws, lws: self.lws,
-            wait_events: None, new_event: None }
+            wait_events: None, new_event: None,
+            profiler: self.queue.as_ref().and_then(|q| q.profiler()),
+            trace_recorder: self.queue.as_ref().and_then(|q| q.trace_recorder()),
+            label: None }
     }
 
     /// Enqueues this kernel on the default queue and returns the result.
@@ -607,6 +965,12 @@ impl Kernel {
         core::get_kernel_info(&self.obj_core, info_kind)
     }
 
+    /// Returns the memory object (buffer or image) most recently set for
+    /// each argument, if any, by argument index.
+    pub fn mem_args(&self) -> Vec<Option<MemCore>> {
+        self.mem_args.lock().unwrap().clone()
+    }
+
     /// Returns argument information for this kernel.
     pub fn arg_info(&self, arg_index: u32, info_kind: KernelArgInfo)
             -> OclCoreResult<KernelArgInfoResult> {
@@ -619,6 +983,88 @@ impl Kernel {
         core::get_kernel_work_group_info(&self.obj_core, device, info_kind)
     }
 
+    /// Returns sub-group information for this kernel.
+    ///
+    /// See `core::get_kernel_sub_group_info` for the `input_value` expected
+    /// by each kind of request.
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    ///
+    #[cfg(feature = "opencl_version_2_1")]
+    pub fn sub_group_info(&self, device: Device, info_kind: KernelSubGroupInfo,
+            input_value: &[usize]) -> OclCoreResult<KernelSubGroupInfoResult> {
+        core::get_kernel_sub_group_info(&self.obj_core, device, info_kind, input_value, None)
+    }
+
+    /// Returns the maximum sub-group size for this kernel when enqueued on
+    /// `device` with a local work size of `lws`.
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    ///
+    #[cfg(feature = "opencl_version_2_1")]
+    pub fn max_sub_group_size_for_lws<D>(&self, device: Device, lws: D) -> OclResult<usize>
+            where D: Into<SpatialDims> {
+        let lws = sub_group_lws_input(lws.into())?;
+        match self.sub_group_info(device, KernelSubGroupInfo::MaxSubGroupSizeForNdrange, &lws)? {
+            KernelSubGroupInfoResult::MaxSubGroupSizeForNdrange(r) => Ok(r),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the number of sub-groups in each work-group of this kernel
+    /// when enqueued on `device` with a local work size of `lws`.
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    ///
+    #[cfg(feature = "opencl_version_2_1")]
+    pub fn sub_group_count_for_lws<D>(&self, device: Device, lws: D) -> OclResult<usize>
+            where D: Into<SpatialDims> {
+        let lws = sub_group_lws_input(lws.into())?;
+        match self.sub_group_info(device, KernelSubGroupInfo::SubGroupCountForNdrange, &lws)? {
+            KernelSubGroupInfoResult::SubGroupCountForNdrange(r) => Ok(r),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns a local work size which, when this kernel is enqueued on
+    /// `device`, yields exactly `sub_group_count` sub-groups per work-group.
+    ///
+    /// The local work size has the same number of dimensions as the global
+    /// work size (one if unspecified). Returns an error if no such local
+    /// work size exists.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,ignore
+    /// let lws = kernel.lws_for_sub_group_count(device, 4)?;
+    /// let kernel = kernel.lws(lws);
+    /// ```
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    ///
+    #[cfg(feature = "opencl_version_2_1")]
+    pub fn lws_for_sub_group_count(&self, device: Device, sub_group_count: usize)
+            -> OclResult<SpatialDims> {
+        let lens = match self.sub_group_info(device, KernelSubGroupInfo::LocalSizeForSubGroupCount,
+                &[sub_group_count])? {
+            KernelSubGroupInfoResult::LocalSizeForSubGroupCount(r) => r,
+            _ => unreachable!(),
+        };
+
+        let dim_count = if self.gws.is_unspecified() { 1 } else { self.gws.dim_count() as usize };
+
+        if lens[0] == 0 || lens[dim_count..].iter().any(|&len| len != 1) {
+            return Err(KernelError::SubGroupCountUnachievable { kernel: self.name()?,
+                sub_group_count, dim_count, lens: lens.to_vec() }.into());
+        }
+
+        Ok(match dim_count {
+            1 => SpatialDims::One(lens[0]),
+            2 => SpatialDims::Two(lens[0], lens[1]),
+            _ => SpatialDims::Three(lens[0], lens[1], lens[2]),
+        })
+    }
+
     /// Returns the name of this kernel.
     pub fn name(&self) -> OclCoreResult<String> {
         core::get_kernel_info(&self.obj_core, KernelInfo::FunctionName).map(|r| r.into())
@@ -638,6 +1084,26 @@ impl Kernel {
         self.resolve_named_arg_idx(name).ok()
     }
 
+    /// Returns the names of each argument, as declared in the kernel source,
+    /// or an empty slice if argument info is unavailable.
+    pub fn arg_names(&self) -> &[String] {
+        &self.arg_names
+    }
+
+    /// Returns the index of the argument named `name`, as declared in the
+    /// kernel source or given to `::arg_..._named`.
+    pub fn arg_idx(&self, name: &str) -> OclResult<u32> {
+        if let Some(arg_idx) = self.arg_names.iter().position(|n| n == name) {
+            return Ok(arg_idx as u32);
+        }
+
+        match self.named_args.as_ref().and_then(|map| map.get(name)) {
+            Some(&arg_idx) => Ok(arg_idx),
+            None => Err(KernelError::ArgNameNotFound { kernel: self.name()?,
+                name: name.to_owned() }.into()),
+        }
+    }
+
     /// Verifies that a type matches the kernel arg info:
     ///
     /// This function does nothing and always returns `Ok` if the OpenCL
@@ -646,16 +1112,42 @@ impl Kernel {
     pub fn verify_arg_type<T: OclPrm + Any>(&self, arg_index: u32) -> OclResult<()> {
         if self.bypass_arg_check { return Ok(()); }
 
-        let arg_type = self.arg_types.get(arg_index as usize)
-            .ok_or(format!("Kernel arg index out of range. (kernel: {}, index: {})",
-                self.name()?, arg_index))?;
+        let arg_type = match self.arg_types.get(arg_index as usize) {
+            Some(at) => at,
+            None => return Err(KernelError::ArgIndexOutOfRange { kernel: self.name()?,
+                arg_index }.into()),
+        };
 
         if arg_type.is_match::<T>() {
             Ok(())
         } else {
-            let type_name = arg_type_name(&self.obj_core, arg_index)?;
-            Err(format!("Kernel argument type mismatch. The argument at index [{}] \
-                is a '{}' ({:?}).", arg_index, type_name, arg_type).into())
+            Err(KernelError::ArgTypeMismatch { kernel: self.name()?, arg_index,
+                type_name: arg_type_name(&self.obj_core, arg_index)?,
+                arg_type: arg_type.clone() }.into())
+        }
+    }
+
+    /// Verifies that the argument at `arg_index` is a pipe with packets of
+    /// type `T`.
+    ///
+    /// This function does nothing and always returns `Ok` if the OpenCL
+    /// version of any of the devices associated with this kernel is below
+    /// 1.2.
+    pub fn verify_arg_pipe_type<T: OclPrm + Any>(&self, arg_index: u32) -> OclResult<()> {
+        if self.bypass_arg_check { return Ok(()); }
+
+        let arg_type = match self.arg_types.get(arg_index as usize) {
+            Some(at) => at,
+            None => return Err(KernelError::ArgIndexOutOfRange { kernel: self.name()?,
+                arg_index }.into()),
+        };
+
+        if arg_type.is_pipe_match::<T>() {
+            Ok(())
+        } else {
+            Err(KernelError::ArgPipeTypeMismatch { kernel: self.name()?, arg_index,
+                type_name: arg_type_name(&self.obj_core, arg_index)?,
+                arg_type: arg_type.clone() }.into())
         }
     }
 
@@ -670,13 +1162,143 @@ impl Kernel {
     ///
     pub unsafe fn set_arg_unchecked<T: OclPrm>(&mut self, arg_idx: u32,
             arg: KernelArg<T>) -> OclResult<()> {
+        self.record_kernel_arg(arg_idx, &arg);
         core::set_kernel_arg::<T>(&self.obj_core, arg_idx, arg).map_err(OclError::from)
     }
 
+    /// Returns a new, independent, kernel with the same arguments, defaults
+    /// (queue and work sizes), and argument names as this one.
+    ///
+    /// Unlike a clone, which shares its underlying kernel object with the
+    /// original, the duplicate has its own kernel object. Setting an
+    /// argument on one does not affect the other and both may be used from
+    /// different threads concurrently.
+    ///
+    /// Uses `clCloneKernel` if every device associated with the kernel
+    /// supports OpenCL 2.1 (and the `opencl_version_2_1` feature is enabled).
+    /// Otherwise the kernel is re-created from its program and each
+    /// argument is set again. Execution info (see `::set_exec_info`) is not
+    /// carried over in the latter case.
+    pub fn duplicate(&self) -> OclResult<Kernel> {
+        let obj_core = match self.clone_core()? {
+            Some(obj_core) => obj_core,
+            None => {
+                let obj_core = core::create_kernel(&self.obj_core.program()?, self.name()?)?;
+                self.replay_args(&obj_core)?;
+                obj_core
+            },
+        };
+
+        Ok(Kernel {
+            obj_core: obj_core,
+            named_args: self.named_args.clone(),
+            new_arg_count: self.new_arg_count,
+            mem_args: Arc::new(Mutex::new(self.mem_args.lock().unwrap().clone())),
+            sampler_args: Arc::new(Mutex::new(self.sampler_args.lock().unwrap().clone())),
+            raw_args: Arc::new(Mutex::new(self.raw_args.lock().unwrap().clone())),
+            #[cfg(feature = "opencl_version_2_0")]
+            svm_args: Arc::new(Mutex::new(self.svm_args.lock().unwrap().clone())),
+            queue: self.queue.clone(),
+            gwo: self.gwo,
+            gws: self.gws,
+            lws: self.lws,
+            num_args: self.num_args,
+            arg_types: self.arg_types.clone(),
+            arg_names: self.arg_names.clone(),
+            bypass_arg_check: self.bypass_arg_check,
+        })
+    }
+
+    /// Returns a copy of the kernel object made with `clCloneKernel` if all
+    /// associated devices support it.
+    #[cfg(feature = "opencl_version_2_1")]
+    fn clone_core(&self) -> OclResult<Option<KernelCore>> {
+        let device_versions = self.obj_core.device_versions()?;
+
+        if device_versions.iter().all(|&dv| dv >= [2, 1].into()) {
+            core::clone_kernel(&self.obj_core, Some(&device_versions)).map(Some)
+                .map_err(OclError::from)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns a copy of the kernel object made with `clCloneKernel` if all
+    /// associated devices support it.
+    #[cfg(not(feature = "opencl_version_2_1"))]
+    fn clone_core(&self) -> OclResult<Option<KernelCore>> {
+        Ok(None)
+    }
+
+    /// Sets every recorded argument value of this kernel on `obj_core`.
+    fn replay_args(&self, obj_core: &KernelCore) -> OclResult<()> {
+        for (arg_idx, raw_arg) in self.raw_args.lock().unwrap().iter().enumerate() {
+            if let Some(ref raw_arg) = *raw_arg {
+                let value = match raw_arg.value {
+                    Some(ref bytes) => bytes.as_ptr() as *const c_void,
+                    None => std::ptr::null(),
+                };
+
+                core::set_kernel_arg::<u8>(obj_core, arg_idx as u32,
+                    KernelArg::UnsafePointer { size: raw_arg.size, value: value })?;
+            }
+        }
+
+        #[cfg(feature = "opencl_version_2_0")]
+        {
+            for (arg_idx, alloc) in self.svm_args.lock().unwrap().iter().enumerate() {
+                if let Some(ref alloc) = *alloc {
+                    core::set_kernel_arg_svm_pointer(obj_core, arg_idx as u32,
+                        alloc.as_ptr() as *const u8, None)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records the raw value of an argument so that it can be replayed by
+    /// `::duplicate`.
+    fn record_arg(&self, arg_idx: u32, raw_arg: Option<RawArg>) {
+        self.sampler_args.lock().unwrap()[arg_idx as usize] = None;
+        #[cfg(feature = "opencl_version_2_0")]
+        {
+            self.svm_args.lock().unwrap()[arg_idx as usize] = None;
+        }
+        self.raw_args.lock().unwrap()[arg_idx as usize] = raw_arg;
+    }
+
+    /// Records `arg` (see `::record_arg`), retaining the sampler it refers
+    /// to, if any, for as long as its handle is recorded.
+    fn record_kernel_arg<T: OclPrm>(&self, arg_idx: u32, arg: &KernelArg<T>) {
+        self.record_arg(arg_idx, Some(RawArg::from_kernel_arg(arg)));
+
+        if let KernelArg::Sampler(sampler) = *arg {
+            self.sampler_args.lock().unwrap()[arg_idx as usize] = Some(sampler.clone());
+        }
+    }
+
     /// Sets an argument by index.
     fn _set_arg<T: OclPrm + 'static>(&mut self, arg_idx: u32, arg: KernelArg<T>) -> OclResult<()> {
         self.verify_arg_type::<T>(arg_idx)?;
+        self._set_arg_verified(arg_idx, arg)
+    }
+
+    /// Sets a pipe argument by index.
+    #[cfg(feature = "opencl_version_2_0")]
+    fn _set_arg_pipe<T: OclPrm + 'static>(&mut self, arg_idx: u32, pipe_opt: Option<&Pipe<T>>)
+            -> OclResult<()> {
+        self.verify_arg_pipe_type::<T>(arg_idx)?;
 
+        match pipe_opt {
+            Some(pipe) => self._set_arg_verified::<T>(arg_idx, KernelArg::Mem(pipe.as_core())),
+            None => self._set_arg_verified::<T>(arg_idx, KernelArg::MemNull),
+        }
+    }
+
+    /// Sets an argument by index after its type has been verified.
+    fn _set_arg_verified<T: OclPrm + 'static>(&mut self, arg_idx: u32, arg: KernelArg<T>)
+            -> OclResult<()> {
         // If the `KernelArg` is a `Mem` variant, clone the `MemCore` it
         // refers to, store it in `self.mem_args`, and create a new
         // `KernelArg::Mem` referring to the locally stored copy. This prevents
@@ -692,9 +1314,38 @@ impl Kernel {
             arg => arg,
         };
 
+        self.record_kernel_arg(arg_idx, &arg);
         core::set_kernel_arg::<T>(&self.obj_core, arg_idx, arg).map_err(OclError::from)
     }
 
+    /// Sets a shared virtual memory argument by index.
+    #[cfg(feature = "opencl_version_2_0")]
+    fn _set_arg_svm<T: OclPrm + 'static>(&mut self, arg_idx: u32, svm_opt: Option<&SvmVec<T>>)
+            -> OclResult<()> {
+        self.verify_arg_type::<T>(arg_idx)?;
+
+        let svm_ptr = match svm_opt {
+            Some(svm) => svm.as_ptr(),
+            None => std::ptr::null(),
+        };
+
+        // A null SVM pointer is replayed as a null argument value:
+        self.record_arg(arg_idx, if svm_ptr.is_null() {
+            Some(RawArg { size: mem::size_of::<*const c_void>(), value: None })
+        } else {
+            None
+        });
+
+        // Keep the allocation alive for as long as this kernel refers to it
+        // (see `_set_arg_verified`):
+        if let Some(svm) = svm_opt {
+            self.svm_args.lock().unwrap()[arg_idx as usize] = Some(svm.alloc().clone());
+        }
+
+        core::set_kernel_arg_svm_pointer(&self.obj_core, arg_idx, svm_ptr, None)
+            .map_err(OclError::from)
+    }
+
     fn fmt_info(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         f.debug_struct("Kernel")
             .field("FunctionName", &self.info(KernelInfo::FunctionName))
@@ -756,11 +1407,11 @@ impl Kernel {
             Some(ref map) => {
                 match map.get(name) {
                     Some(&ai) => Ok(ai),
-                    None => Err(format!("Kernel::set_arg_scl_named(): Invalid argument \
-                        name: '{}'.", name).into()),
+                    None => Err(KernelError::ArgNameNotFound { kernel: self.name()?,
+                        name: name.to_owned() }.into()),
                 }
             },
-            None => Err("Kernel::resolve_named_arg_idx: No named arguments declared.".into()),
+            None => Err(KernelError::NoNamedArgs { kernel: self.name()? }.into()),
         }
     }
 
@@ -827,6 +1478,46 @@ impl Kernel {
         self.new_arg::<T>(KernelArg::Vector(vector))
     }
 
+    /// Non-builder-style version of `::arg_pipe()`.
+    #[cfg(feature = "opencl_version_2_0")]
+    fn new_arg_pipe<T>(&mut self, pipe_opt: Option<&Pipe<T>>) -> u32
+            where T: OclPrm + 'static {
+        self.assert_unlocked();
+        let arg_idx = self.new_arg_count;
+
+        match self._set_arg_pipe(arg_idx, pipe_opt) {
+            Ok(_) => (),
+            Err(err) => {
+                panic!("Kernel::new_arg_pipe(kernel name: '{}' arg index: '{}'): {}",
+                    self.name().unwrap(), arg_idx, err);
+            }
+        }
+
+        self.new_arg_count += 1;
+        assert!(self.new_arg_count <= self.num_args);
+        arg_idx
+    }
+
+    /// Non-builder-style version of `::arg_svm()`.
+    #[cfg(feature = "opencl_version_2_0")]
+    fn new_arg_svm<T>(&mut self, svm_opt: Option<&SvmVec<T>>) -> u32
+            where T: OclPrm + 'static {
+        self.assert_unlocked();
+        let arg_idx = self.new_arg_count;
+
+        match self._set_arg_svm(arg_idx, svm_opt) {
+            Ok(_) => (),
+            Err(err) => {
+                panic!("Kernel::new_arg_svm(kernel name: '{}' arg index: '{}'): {}",
+                    self.name().unwrap(), arg_idx, err);
+            }
+        }
+
+        self.new_arg_count += 1;
+        assert!(self.new_arg_count <= self.num_args);
+        arg_idx
+    }
+
     /// Non-builder-style version of `::arg_loc()`.
     fn new_arg_loc<T>(&mut self, length: usize) -> u32
             where T: OclPrm + 'static {
@@ -853,6 +1544,15 @@ impl Kernel {
     }
 }
 
+/// Returns the lengths of each specified dimension of `lws`.
+#[cfg(feature = "opencl_version_2_1")]
+fn sub_group_lws_input(lws: SpatialDims) -> OclResult<Vec<usize>> {
+    match lws.to_lens() {
+        Ok(lens) => Ok(lens[..lws.dim_count() as usize].to_vec()),
+        Err(_) => Err(KernelError::SubGroupLwsUnspecified.into()),
+    }
+}
+
 impl Clone for Kernel {
     // TODO: Create a new, identical, kernel core instead of cloning it.
     fn clone(&self) -> Kernel {
@@ -865,12 +1565,17 @@ impl Clone for Kernel {
             named_args: self.named_args.clone(),
             new_arg_count: self.new_arg_count.clone(),
             mem_args: self.mem_args.clone(),
+            sampler_args: self.sampler_args.clone(),
+            raw_args: self.raw_args.clone(),
+            #[cfg(feature = "opencl_version_2_0")]
+            svm_args: self.svm_args.clone(),
             queue: self.queue.clone(),
             gwo: self.gwo.clone(),
             gws: self.gws.clone(),
             lws: self.lws.clone(),
             num_args: self.num_args.clone(),
             arg_types: self.arg_types.clone(),
+            arg_names: self.arg_names.clone(),
             bypass_arg_check: self.bypass_arg_check.clone(),
         }
     }
@@ -926,7 +1631,8 @@ pub mod arg_type {
     use std::any::{Any, TypeId};
     use ffi::{cl_char, cl_uchar, cl_short, cl_ushort, cl_int, cl_uint, cl_long, cl_ulong,
         cl_half, cl_float, cl_double, cl_bool, cl_bitfield};
-    use core::{Error as OclCoreError, Result as OclCoreResult, Status, OclPrm, Kernel as KernelCore};
+    use core::{Error as OclCoreError, Result as OclCoreResult, Status, OclPrm, Kernel as KernelCore,
+        KernelArgInfo, KernelArgInfoResult, KernelArgTypeQualifier};
     use error::{Error as OclError, Result as OclResult};
     use standard::Sampler;
     use super::{arg_info, arg_type_name};
@@ -981,6 +1687,7 @@ pub mod arg_type {
         base_type: BaseType,
         cardinality: Cardinality,
         is_ptr: bool,
+        is_pipe: bool,
     }
 
     impl ArgType {
@@ -991,6 +1698,7 @@ pub mod arg_type {
                 base_type: BaseType::Unknown,
                 cardinality: Cardinality::One,
                 is_ptr: false,
+                is_pipe: false,
             })
         }
 
@@ -1002,6 +1710,7 @@ pub mod arg_type {
         /// `::starts_with` be used for base type names instead?
         pub fn from_str(type_name: &str) -> OclCoreResult<ArgType> {
             let is_ptr = type_name.contains("*");
+            let is_pipe = type_name.starts_with("pipe ");
 
             let card = if type_name.contains("16") {
                 Cardinality::Sixteen
@@ -1049,6 +1758,7 @@ pub mod arg_type {
                 base_type: base,
                 cardinality: card,
                 is_ptr: is_ptr,
+                is_pipe: is_pipe,
             })
         }
 
@@ -1065,14 +1775,28 @@ pub mod arg_type {
             use core::ErrorKind as OclCoreErrorKind;
 
             match arg_type_name(core, arg_index) {
-                Ok(type_name) => ArgType::from_str(type_name.as_str()),
+                Ok(type_name) => {
+                    let mut arg_type = ArgType::from_str(type_name.as_str())?;
+
+                    // Pipe arguments are reported by their packet type name
+                    // with a `pipe` type qualifier:
+                    if let Ok(KernelArgInfoResult::TypeQualifier(qualifier)) =
+                            arg_info(core, arg_index, KernelArgInfo::TypeQualifier) {
+                        if qualifier.contains(KernelArgTypeQualifier::PIPE) {
+                            arg_type.is_pipe = true;
+                        }
+                    }
+
+                    Ok(arg_type)
+                },
                 Err(err) => {
                     // Escape hatches for known, platform-specific errors.
                     match *err.kind() {
                         OclCoreErrorKind::Api(ref api_err) => {
                             if api_err.status() == Status::CL_KERNEL_ARG_INFO_NOT_AVAILABLE {
                                 return Ok(ArgType { base_type: BaseType::Unknown,
-                                    cardinality: Cardinality::One, is_ptr: false })
+                                    cardinality: Cardinality::One, is_ptr: false,
+                                    is_pipe: false })
                             }
                         }
                         OclCoreErrorKind::EmptyInfoResult(EmptyInfoResultError::KernelArg) => {
@@ -1087,7 +1811,11 @@ pub mod arg_type {
         }
 
         /// Returns true if the type of `T` matches the base type of this `ArgType`.
+        ///
+        /// Always returns false for pipe arguments (see `::is_pipe_match`).
         pub fn is_match<T: OclPrm + Any + 'static>(&self) -> bool {
+            if self.is_pipe { return false; }
+
             match self.base_type {
                 BaseType::Char => {
                     let card_match = match self.cardinality {
@@ -1260,6 +1988,20 @@ pub mod arg_type {
         pub fn is_ptr(&self) -> bool {
             self.is_ptr
         }
+
+        /// Returns true if this is a pipe argument with packets of type `T`.
+        ///
+        /// Also returns true if the type of the argument could not be
+        /// determined.
+        pub fn is_pipe_match<T: OclPrm + Any + 'static>(&self) -> bool {
+            if !self.is_pipe && self.base_type != BaseType::Unknown { return false; }
+            ArgType { is_pipe: false, .. self.clone() }.is_match::<T>()
+        }
+
+        /// Returns true if this is a pipe argument.
+        pub fn is_pipe(&self) -> bool {
+            self.is_pipe
+        }
     }
 
     impl<'a> From<&'a str> for ArgType {
@@ -6,6 +6,7 @@ mod platform;
 mod device;
 mod context;
 mod program;
+mod program_cache;
 mod kernel;
 mod queue;
 mod buffer;
@@ -13,23 +14,49 @@ mod image;
 mod sampler;
 mod pro_que;
 mod event;
+#[cfg(not(feature = "async_block"))]
+mod event_reactor;
 mod spatial_dims;
+mod clock;
+mod profiler;
+mod trace;
+#[cfg(feature = "opencl_version_2_0")]
+mod svm;
+#[cfg(feature = "opencl_version_2_0")]
+mod pipe;
 
 pub use self::platform::{PlatformError, Extensions, Platform};
-pub use self::device::{DeviceError, Device, DeviceSpecifier};
-pub use self::context::{Context, ContextBuilder};
-pub use self::program::{Program, ProgramBuilder, BuildOpt};
-pub use self::queue::Queue;
-pub use self::kernel::{Kernel, KernelCmd};
+pub use self::device::{DeviceError, Device, DeviceSpecifier, SubDevice};
+pub use self::context::{Context, ContextBuilder, ContextError};
+pub use self::program::{Program, ProgramBuilder, BuildOpt, ProgramError};
+pub use self::program_cache::ProgramCache;
+pub use self::queue::{Queue, QueueBuilder, NativeKernelCmd, QueueError};
+pub use self::clock::DeviceClock;
+pub use self::profiler::{Profiler, ProfileKey, ProfileReport, CommandStats, DurationStats};
+pub(crate) use self::profiler::ProfiledEnew;
+pub use self::trace::TraceRecorder;
+pub(crate) use self::trace::TraceCmd;
+pub use self::kernel::{Kernel, KernelCmd, KernelError, ArgType, BaseType, Cardinality, ArgVal};
 pub use self::buffer::{BufferCmdKind, BufferCmdDataShape, BufferCmd, Buffer, QueCtx,
     BufferBuilder, BufferReadCmd, BufferWriteCmd, BufferMapCmd, BufferCmdError};
-pub use self::image::{Image, ImageCmd, ImageCmdKind, ImageBuilder};
+pub use self::image::{Image, ImageCmd, ImageCmdKind, ImageBuilder, ImageCmdError};
 pub use self::sampler::Sampler;
-pub use self::pro_que::{ProQue, ProQueBuilder};
-pub use self::event::{Event, EventArray, EventList, IntoMarker, RawEventArray, IntoRawEventArray};
-pub use self::spatial_dims::SpatialDims;
+pub use self::pro_que::{ProQue, ProQueBuilder, ProQueError};
+pub use self::event::{Event, EventArray, EventList, IntoMarker, RawEventArray, IntoRawEventArray,
+    EventError};
+#[cfg(not(feature = "async_block"))]
+pub use self::event_reactor::{EventReactor, ReactorMode};
+pub use self::spatial_dims::{SpatialDims, SpatialDimsError};
+#[cfg(feature = "opencl_version_2_0")]
+pub use self::svm::{SvmVec, SvmError};
+#[cfg(feature = "opencl_version_2_0")]
+pub use self::pipe::Pipe;
+#[cfg(feature = "opencl_version_2_0")]
+pub(crate) use self::svm::SvmAlloc;
 #[cfg(not(feature = "async_block"))]
 pub use self::cb::{_unpark_task, box_raw_void};
+#[cfg(all(feature = "std_future", not(feature = "async_block")))]
+pub use self::cb::_wake_waker;
 pub use self::traits::{MemLen, WorkDims};
 pub use self::types::{ClNullEventPtrEnum, ClWaitListPtrEnum};
 
@@ -79,6 +106,19 @@ mod cb {
                 drivers instead (will work with Intel CPUs).\n\n", status, user_data);
         }
     }
+
+    /// Wakes the `std::task::Waker` pointed to by `user_data`.
+    ///
+    /// The waker is woken regardless of the event status. Futures check the
+    /// status when next polled and report negative (error) statuses.
+    #[cfg(feature = "std_future")]
+    pub extern "C" fn _wake_waker(event_ptr: cl_event, event_status: i32, user_data: *mut c_void) {
+        let _ = (event_ptr, event_status);
+        if !user_data.is_null() {
+            let waker = unsafe { Box::from_raw(user_data as *mut ::std::task::Waker) };
+            waker.wake();
+        }
+    }
 }
 
 
@@ -0,0 +1,114 @@
+//! An `OpenCL` pipe.
+
+use std;
+use std::mem;
+use std::ops::Deref;
+use std::marker::PhantomData;
+use core::error::Result as OclCoreResult;
+use core::{self, OclPrm, Mem as MemCore, MemFlags, MemInfo, MemInfoResult, PipeInfo,
+    PipeInfoResult};
+use error::Result as OclResult;
+use standard::Context;
+
+
+/// A pipe object which passes packets of type `T` between kernels.
+///
+/// Pipes are accessed only by kernels, never by the host. A producer kernel
+/// writes packets with `write_pipe` and a consumer kernel reads them with
+/// `read_pipe`, allowing kernels to be chained together without round
+/// trips through host memory. The packet size is `size_of::<T>()`.
+///
+/// Pass a pipe to a kernel using `Kernel::arg_pipe`.
+///
+/// [Version Controlled: OpenCL 2.0+]
+///
+#[derive(Clone, Debug)]
+pub struct Pipe<T: OclPrm> {
+    obj_core: MemCore,
+    max_packets: u32,
+    _data: PhantomData<T>,
+}
+
+impl<T: OclPrm> Pipe<T> {
+    /// Returns a new pipe able to hold up to `max_packets` packets.
+    pub fn new(context: &Context, max_packets: u32) -> OclResult<Pipe<T>> {
+        Pipe::with_flags(context, MemFlags::new().read_write().host_no_access(), max_packets)
+    }
+
+    /// Returns a new pipe able to hold up to `max_packets` packets created
+    /// using `flags`.
+    ///
+    /// `flags` may only contain `MemFlags::READ_WRITE` and/or
+    /// `MemFlags::HOST_NO_ACCESS` (or be empty).
+    pub fn with_flags(context: &Context, flags: MemFlags, max_packets: u32)
+            -> OclResult<Pipe<T>> {
+        let obj_core = core::create_pipe(context, flags, Pipe::<T>::packet_size(), max_packets,
+            None)?;
+
+        Ok(Pipe {
+            obj_core: obj_core,
+            max_packets: max_packets,
+            _data: PhantomData,
+        })
+    }
+
+    /// Returns the size of each packet in bytes.
+    #[inline]
+    pub fn packet_size() -> u32 {
+        mem::size_of::<T>() as u32
+    }
+
+    /// Returns the maximum number of packets this pipe can hold.
+    #[inline]
+    pub fn max_packets(&self) -> u32 {
+        self.max_packets
+    }
+
+    /// Returns info about the underlying pipe object.
+    pub fn info(&self, info_kind: PipeInfo) -> OclCoreResult<PipeInfoResult> {
+        core::get_pipe_info(&self.obj_core, info_kind)
+    }
+
+    /// Returns info about the underlying memory object.
+    pub fn mem_info(&self, info_kind: MemInfo) -> OclCoreResult<MemInfoResult> {
+        core::get_mem_object_info(&self.obj_core, info_kind)
+    }
+
+    /// Returns a reference to the core pointer wrapper, usable by functions in
+    /// the `core` module.
+    #[inline]
+    pub fn as_core(&self) -> &MemCore {
+        &self.obj_core
+    }
+
+    /// Formats pipe info.
+    fn fmt_info(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Pipe")
+            .field("PacketSize", &self.info(PipeInfo::PacketSize))
+            .field("MaxPackets", &self.info(PipeInfo::MaxPackets))
+            .field("Type", &self.mem_info(MemInfo::Type))
+            .field("Flags", &self.mem_info(MemInfo::Flags))
+            .field("Size", &self.mem_info(MemInfo::Size))
+            .finish()
+    }
+}
+
+impl<T: OclPrm> Deref for Pipe<T> {
+    type Target = MemCore;
+
+    fn deref(&self) -> &MemCore {
+        &self.obj_core
+    }
+}
+
+impl<T: OclPrm> AsRef<MemCore> for Pipe<T> {
+    fn as_ref(&self) -> &MemCore {
+        &self.obj_core
+    }
+}
+
+impl<T: OclPrm> std::fmt::Display for Pipe<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        self.fmt_info(f)
+    }
+}
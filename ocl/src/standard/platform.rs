@@ -13,12 +13,22 @@ use core::error::{Result as OclCoreResult};
 use error::{Result as OclResult};
 
 
-#[derive(Debug, Fail)]
+/// A platform related error.
+#[derive(Debug)]
 pub enum PlatformError {
-    #[fail(display = "No platforms found.")]
     NoPlatforms,
 }
 
+impl std::fmt::Display for PlatformError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            PlatformError::NoPlatforms => write!(f, "No platforms found."),
+        }
+    }
+}
+
+impl std::error::Error for PlatformError {}
+
 
 /// Extensions of a platform.
 #[derive(Debug, Clone)]
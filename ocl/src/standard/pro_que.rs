@@ -1,6 +1,8 @@
 //! A convenient wrapper for `Program` and `Queue`.
 
+use std;
 use std::ops::Deref;
+use std::collections::HashMap;
 use error::{Error as OclError, Result as OclResult};
 use core::{OclPrm, CommandQueueProperties};
 use standard::{Platform, Device, Context, ProgramBuilder, Program, Queue, Kernel, Buffer,
@@ -12,6 +14,34 @@ static DIMS_ERR_MSG: &'static str = "This 'ProQue' has not had any dimensions sp
 const DEBUG_PRINT: bool = false;
 
 
+/// A `ProQue` related error.
+#[derive(Debug)]
+pub enum ProQueError {
+    DimsUnset,
+    NoProgramSource,
+    InvalidDeviceCount(usize),
+}
+
+impl std::fmt::Display for ProQueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ProQueError::DimsUnset => write!(f, "{}", DIMS_ERR_MSG),
+            ProQueError::NoProgramSource => write!(f, "No program builder or kernel source \
+                defined. OpenCL programs must have some source code to be compiled. Use '::src' \
+                to directly add source code or '::program_builder' for more complex builds. \
+                Please see the 'ProQueBuilder' and 'ProgramBuilder' documentation for more \
+                information."),
+            ProQueError::InvalidDeviceCount(count) => write!(f, "Invalid number of devices \
+                specified ({}). Each 'ProQue' can only be associated with a single device. Use \
+                'Context', 'Program', and 'Queue' separately for multi-device configurations.",
+                count),
+        }
+    }
+}
+
+impl std::error::Error for ProQueError {}
+
+
 /// An all-in-one chimera of the `Program`, `Queue`, `Context` and
 /// (optionally) `SpatialDims` types.
 ///
@@ -81,6 +111,21 @@ impl ProQue {
         }
     }
 
+    /// Creates a kernel, with pre-assigned dimensions, for every kernel
+    /// function defined in this `ProQue`'s program, keyed by kernel name.
+    pub fn create_all_kernels(&self) -> OclResult<HashMap<String, Kernel>> {
+        let kernels = self.program.create_all_kernels()?;
+
+        Ok(kernels.into_iter().map(|(name, kernel)| {
+            let kernel = kernel.queue(self.queue.clone());
+
+            match self.dims {
+                Some(d) => (name, kernel.gws(d)),
+                None => (name, kernel),
+            }
+        }).collect())
+    }
+
     /// Returns a new buffer.
     ///
     /// The default dimensions and queue from this `ProQue` will be used.
@@ -149,7 +194,7 @@ impl ProQue {
     pub fn dims_result(&self) -> OclResult<&SpatialDims> {
         match self.dims {
             Some(ref dims) => Ok(dims),
-            None => Err(DIMS_ERR_MSG.into()),
+            None => Err(ProQueError::DimsUnset.into()),
         }
     }
 }
@@ -336,10 +381,7 @@ impl ProQueBuilder {
         let program_builder = match self.program_builder {
             // Some(program_builder) => ProQueBuilder::_build(self.context, self.device_idx, program_builder),
             Some(ref program_builder) => program_builder,
-            None => return Err("ProQueBuilder::build(): No program builder or kernel source defined. \
-                OpenCL programs must have some source code to be compiled. Use '::src' to directly \
-                add source code or '::program_builder' for more complex builds. Please see the \
-                'ProQueBuilder' and 'ProgramBuilder' documentation for more information.".into()),
+            None => return Err(ProQueError::NoProgramSource.into()),
         };
 
         // If no platform is set or no context platform is set, use the first available:
@@ -374,10 +416,7 @@ impl ProQueBuilder {
                 if device_list.len() == 1 {
                     device_list[0]
                 } else {
-                    return Err(format!("Invalid number of devices specified ({}). Each 'ProQue' \
-                        can only be associated with a single device. Use 'Context', 'Program', and \
-                        'Queue' separately for multi-device configurations.",
-                        device_list.len()).into());
+                    return Err(ProQueError::InvalidDeviceCount(device_list.len()).into());
                 }
             },
             None => Device::first(platform)?,
@@ -405,9 +444,9 @@ impl ProQueBuilder {
         let queue = Queue::new(&context, device, self.queue_properties)?;
 
         // println!("PROQUEBUILDER: About to load SRC_STRINGS.");
-        let src_strings = program_builder.get_src_strings().map_err(|e| e.to_string())?;
+        let src_strings = program_builder.get_src_strings()?;
         // println!("PROQUEBUILDER: About to load CMPLR_OPTS.");
-        let cmplr_opts = program_builder.get_compiler_options().map_err(|e| e.to_string())?;
+        let cmplr_opts = program_builder.get_compiler_options()?;
         // println!("PROQUEBUILDER: All done.");
 
         let program = Program::new(
@@ -0,0 +1,422 @@
+//! Command profiling statistics.
+
+use std::fmt;
+use std::io::Write;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard, Condvar};
+use std::time::Duration;
+use core::{ProfilingInfo, ClNullEventPtr, CommandQueue as CommandQueueCore};
+use error::Result as OclResult;
+use standard::{Event, ClNullEventPtrEnum, ClWaitListPtrEnum, TraceRecorder, TraceCmd};
+
+
+/// Identifies the commands whose durations are aggregated together.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ProfileKey {
+    /// A kernel, by name.
+    Kernel(String),
+    /// A buffer command, by kind (`"read"`, `"write_rect"`, `"copy"`,
+    /// `"fill"`, `"map"`, etc.).
+    Buffer(&'static str),
+    /// An image command, by kind (`"read"`, `"write"`, `"copy"`, etc.).
+    Image(&'static str),
+}
+
+impl fmt::Display for ProfileKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProfileKey::Kernel(ref name) => write!(f, "kernel:{}", name),
+            ProfileKey::Buffer(kind) => write!(f, "buffer:{}", kind),
+            ProfileKey::Image(kind) => write!(f, "image:{}", kind),
+        }
+    }
+}
+
+
+/// Statistics for a set of durations.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DurationStats {
+    pub count: usize,
+    pub total: Duration,
+    pub mean: Duration,
+    pub p50: Duration,
+    pub p99: Duration,
+    pub max: Duration,
+}
+
+impl DurationStats {
+    /// Returns the statistics for `nanos`, sorting it in the process.
+    fn from_nanos(nanos: &mut [u64]) -> DurationStats {
+        if nanos.is_empty() { return DurationStats::default(); }
+
+        nanos.sort();
+        let total: u64 = nanos.iter().sum();
+
+        DurationStats {
+            count: nanos.len(),
+            total: Duration::from_nanos(total),
+            mean: Duration::from_nanos(total / nanos.len() as u64),
+            p50: Duration::from_nanos(percentile(nanos, 50)),
+            p99: Duration::from_nanos(percentile(nanos, 99)),
+            max: Duration::from_nanos(nanos[nanos.len() - 1]),
+        }
+    }
+}
+
+
+/// Returns the nearest-rank percentile `pct` of `sorted`, which must not be
+/// empty.
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    let rank = (pct * sorted.len() + 99) / 100;
+    sorted[rank.max(1) - 1]
+}
+
+
+/// Returns `duration` in microseconds.
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1.0e6 + duration.subsec_nanos() as f64 / 1.0e3
+}
+
+
+/// Profiling statistics for one kind of command.
+#[derive(Clone, Debug)]
+pub struct CommandStats {
+    pub key: ProfileKey,
+    /// Time from the command being enqueued until it started executing.
+    pub queued_to_start: DurationStats,
+    /// Time spent executing.
+    pub start_to_end: DurationStats,
+}
+
+
+/// A snapshot of the statistics collected by a `Profiler`.
+///
+/// Commands are sorted by their total execution time (`start_to_end.total`),
+/// longest first.
+///
+/// Printing a report (`Display`) produces a table with times in
+/// microseconds. Use `::write_csv` to export.
+#[derive(Clone, Debug)]
+pub struct ProfileReport {
+    commands: Vec<CommandStats>,
+    unresolved: usize,
+}
+
+impl ProfileReport {
+    /// Returns the statistics for each kind of command.
+    pub fn commands(&self) -> &[CommandStats] {
+        &self.commands
+    }
+
+    /// Returns the statistics for commands identified by `key`.
+    pub fn get(&self, key: &ProfileKey) -> Option<&CommandStats> {
+        self.commands.iter().find(|stats| stats.key == *key)
+    }
+
+    /// Returns the number of recorded commands which failed or for which
+    /// profiling info was unavailable (such as those enqueued on a queue
+    /// created without `CommandQueueProperties::PROFILING_ENABLE`).
+    pub fn unresolved(&self) -> usize {
+        self.unresolved
+    }
+
+    /// Writes this report as comma separated values with a header row. Times
+    /// are in nanoseconds.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> OclResult<()> {
+        writeln!(writer, "command,count,queued_to_start_total,queued_to_start_mean,\
+            queued_to_start_p50,queued_to_start_p99,queued_to_start_max,start_to_end_total,\
+            start_to_end_mean,start_to_end_p50,start_to_end_p99,start_to_end_max")?;
+
+        for stats in self.commands.iter() {
+            write!(writer, "{},{}", stats.key, stats.start_to_end.count)?;
+            for ds in [&stats.queued_to_start, &stats.start_to_end].iter() {
+                for d in [ds.total, ds.mean, ds.p50, ds.p99, ds.max].iter() {
+                    write!(writer, ",{}", d.as_secs() * 1_000_000_000 + d.subsec_nanos() as u64)?;
+                }
+            }
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ProfileReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:<32} {:>8} | {:>12} {:>10} {:>10} {:>10} | {:>12} {:>10} {:>10} {:>10} {:>10}",
+            "command", "count", "queued(us)", "mean", "p50", "p99", "exec(us)", "mean", "p50",
+            "p99", "max")?;
+
+        for stats in self.commands.iter() {
+            let q = &stats.queued_to_start;
+            let e = &stats.start_to_end;
+            writeln!(f, "{:<32} {:>8} | {:>12.3} {:>10.3} {:>10.3} {:>10.3} | {:>12.3} {:>10.3} \
+                {:>10.3} {:>10.3} {:>10.3}", stats.key.to_string(), e.count, micros(q.total),
+                micros(q.mean), micros(q.p50), micros(q.p99), micros(e.total), micros(e.mean),
+                micros(e.p50), micros(e.p99), micros(e.max))?;
+        }
+
+        if self.unresolved > 0 {
+            writeln!(f, "({} unresolved)", self.unresolved)?;
+        }
+        Ok(())
+    }
+}
+
+
+/// Durations recorded for one kind of command, in nanoseconds.
+#[derive(Default)]
+struct Samples {
+    queued_to_start: Vec<u64>,
+    start_to_end: Vec<u64>,
+}
+
+
+struct State {
+    samples: BTreeMap<ProfileKey, Samples>,
+    pending: usize,
+    unresolved: usize,
+}
+
+
+struct Shared {
+    state: Mutex<State>,
+    resolved: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<State> {
+        match self.state.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Records the durations of a completed command.
+    fn resolve(&self, key: ProfileKey, event: &Event) {
+        let times = [ProfilingInfo::Queued, ProfilingInfo::Start, ProfilingInfo::End].iter()
+            .map(|&info| event.profiling_info(info).and_then(|res| res.time()))
+            .collect::<Result<Vec<u64>, _>>();
+
+        let mut state = self.lock();
+        match times {
+            Ok(times) => {
+                let samples = state.samples.entry(key).or_insert_with(Samples::default);
+                samples.queued_to_start.push(times[1].saturating_sub(times[0]));
+                samples.start_to_end.push(times[2].saturating_sub(times[1]));
+            },
+            Err(_) => state.unresolved += 1,
+        }
+        self.finish_pending(state);
+    }
+
+    /// Decrements the pending count, notifying waiters when it reaches zero.
+    fn finish_pending(&self, mut state: MutexGuard<State>) {
+        state.pending -= 1;
+        if state.pending == 0 {
+            self.resolved.notify_all();
+        }
+    }
+}
+
+
+/// Collects command events and aggregates their durations.
+///
+/// Attach to a queue using `QueueBuilder::profiler` or `Queue::set_profiler`
+/// to profile every kernel, buffer, and image command enqueued using that
+/// queue (the kernel's default queue for kernel commands). Alternatively,
+/// pass to the `::profiler` method of individual commands (`KernelCmd`,
+/// `BufferCmd`, `ImageCmd`, etc.). Queues used must be created with profiling enabled
+/// (`CommandQueueProperties::PROFILING_ENABLE`, implied by
+/// `QueueBuilder::profiler`).
+///
+/// Events are resolved in the background as each command completes. Use
+/// `::wait_for_pending` before calling `::report` to make sure all commands
+/// have been accounted for.
+///
+/// Clones share the same statistics.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// let profiler = Profiler::new();
+/// let queue = Queue::builder().profiler(profiler.clone()).build(&context)?;
+/// // ... enqueue commands ...
+/// queue.finish()?;
+/// profiler.wait_for_pending();
+/// println!("{}", profiler.report());
+/// ```
+///
+#[derive(Clone)]
+pub struct Profiler {
+    shared: Arc<Shared>,
+}
+
+impl Profiler {
+    /// Returns a new, empty, `Profiler`.
+    pub fn new() -> Profiler {
+        Profiler {
+            shared: Arc::new(Shared {
+                state: Mutex::new(State {
+                    samples: BTreeMap::new(),
+                    pending: 0,
+                    unresolved: 0,
+                }),
+                resolved: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Records the command associated with `event` under `key`.
+    ///
+    /// The command's durations are added once it completes. Commands which
+    /// fail, or for which profiling info is unavailable, are counted as
+    /// unresolved.
+    pub fn record(&self, key: ProfileKey, event: &Event) {
+        self.shared.lock().pending += 1;
+
+        let shared = self.shared.clone();
+        let resolve_event = event.clone();
+
+        let res = event.on_complete(move |res| {
+            match res {
+                Ok(()) => shared.resolve(key, &resolve_event),
+                Err(_) => {
+                    let mut state = shared.lock();
+                    state.unresolved += 1;
+                    shared.finish_pending(state);
+                },
+            }
+        });
+
+        if res.is_err() {
+            let mut state = self.shared.lock();
+            state.unresolved += 1;
+            self.shared.finish_pending(state);
+        }
+    }
+
+    /// Returns the number of recorded commands which have not yet completed.
+    pub fn pending_count(&self) -> usize {
+        self.shared.lock().pending
+    }
+
+    /// Blocks until every recorded command has completed and been resolved.
+    pub fn wait_for_pending(&self) {
+        let mut state = self.shared.lock();
+        while state.pending > 0 {
+            state = match self.shared.resolved.wait(state) {
+                Ok(guard) => guard,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+        }
+    }
+
+    /// Returns a report of the statistics collected so far.
+    pub fn report(&self) -> ProfileReport {
+        let state = self.shared.lock();
+
+        let mut commands: Vec<CommandStats> = state.samples.iter().map(|(key, samples)| {
+            CommandStats {
+                key: key.clone(),
+                queued_to_start: DurationStats::from_nanos(&mut samples.queued_to_start.clone()),
+                start_to_end: DurationStats::from_nanos(&mut samples.start_to_end.clone()),
+            }
+        }).collect();
+
+        commands.sort_by(|a, b| b.start_to_end.total.cmp(&a.start_to_end.total));
+
+        ProfileReport {
+            commands: commands,
+            unresolved: state.unresolved,
+        }
+    }
+
+    /// Discards all statistics collected so far.
+    ///
+    /// Commands still pending are recorded once they complete.
+    pub fn reset(&self) {
+        let mut state = self.shared.lock();
+        state.samples.clear();
+        state.unresolved = 0;
+    }
+}
+
+impl fmt::Debug for Profiler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let state = self.shared.lock();
+        f.debug_struct("Profiler")
+            .field("pending", &state.pending)
+            .field("unresolved", &state.unresolved)
+            .finish()
+    }
+}
+
+
+/// The new event destination of a command which may be profiled or traced.
+///
+/// When profiling or tracing, an event is always created for the command,
+/// recorded, and copied to any destination specified by the caller.
+pub(crate) struct ProfiledEnew<'p, 'e> {
+    profiler: Option<&'p Profiler>,
+    trace: Option<TraceCmd<'p>>,
+    event: Event,
+    enew: Option<ClNullEventPtrEnum<'e>>,
+}
+
+impl<'p, 'e> ProfiledEnew<'p, 'e> {
+    pub(crate) fn new(profiler: Option<&'p Profiler>, enew: Option<ClNullEventPtrEnum<'e>>)
+            -> ProfiledEnew<'p, 'e> {
+        ProfiledEnew {
+            profiler: profiler,
+            trace: None,
+            event: Event::empty(),
+            enew: enew,
+        }
+    }
+
+    /// Also records the command with `recorder`, if any. Must be called
+    /// before `ewait` is consumed.
+    pub(crate) fn trace(mut self, recorder: Option<&'p TraceRecorder>,
+            queue: &'p CommandQueueCore, label: Option<String>,
+            ewait: Option<&ClWaitListPtrEnum>) -> ProfiledEnew<'p, 'e> {
+        self.trace = recorder.map(|recorder| TraceCmd::new(recorder, queue, label, ewait));
+        self
+    }
+
+    /// Returns the new event destination to pass to the enqueue function.
+    pub(crate) fn enew(&mut self) -> Option<ClNullEventPtrEnum> {
+        if self.is_recording() {
+            Some(ClNullEventPtrEnum::Event(&mut self.event))
+        } else {
+            self.enew.take()
+        }
+    }
+
+    /// Returns true if profiling or tracing.
+    pub(crate) fn is_recording(&self) -> bool {
+        self.profiler.is_some() || self.trace.is_some()
+    }
+
+    /// Records `event`, created by the caller, under `key`.
+    pub(crate) fn record(self, key: ProfileKey, event: &Event) {
+        if let Some(trace) = self.trace {
+            trace.record(&key, event);
+        }
+        if let Some(profiler) = self.profiler {
+            profiler.record(key, event);
+        }
+    }
+
+    /// Records the command under `key` and copies its event to the caller's
+    /// destination. Call only once the command has been enqueued.
+    pub(crate) fn finish(mut self, key: ProfileKey) {
+        if !self.is_recording() { return; }
+
+        match self.enew.take() {
+            Some(ClNullEventPtrEnum::Null) | None => (),
+            Some(mut enew) => unsafe { enew.clone_from(&self.event) },
+        }
+        let event = self.event.clone();
+        self.record(key, &event);
+    }
+}
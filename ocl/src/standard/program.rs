@@ -5,16 +5,58 @@ use std::ffi::CString;
 use std::io::Read;
 use std::fs::File;
 use std::path::PathBuf;
-use std::collections::HashSet;
+use std::collections::{HashSet, HashMap};
 use std::convert::Into;
 
 
 use core::{self, Result as OclCoreResult, Program as ProgramCore, Context as ContextCore,
-    ProgramInfo, ProgramInfoResult, ProgramBuildInfo, ProgramBuildInfoResult};
-#[cfg(feature = "opencl_version_2_1")]
+    ProgramInfo, ProgramInfoResult, ProgramBuildInfo, ProgramBuildInfoResult, KernelInfo,
+    KernelInfoResult};
 use core::ClVersions;
 use error::{Result as OclResult, Error as OclError};
-use standard::{Context, Device, DeviceSpecifier};
+use standard::{Context, Device, DeviceSpecifier, Kernel, ProgramCache};
+
+
+/// A program related error.
+#[derive(Debug)]
+pub enum ProgramError {
+    HeadersRequireCompile,
+    IlWithSourceFiles,
+    BuiltInKernelsWithSource,
+    BinariesWithSource,
+    BinariesDeviceCountMismatch { binaries: usize, devices: usize },
+    CompileIl,
+    CompileBinaries,
+    BuiltInKernelUnsupported { kernel: String, device: String, supported: Vec<String> },
+}
+
+impl std::fmt::Display for ProgramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ProgramError::HeadersRequireCompile => write!(f, "Embedded headers may only be used \
+                when compiling (use '::compile')."),
+            ProgramError::IlWithSourceFiles => write!(f, "No source files may be set when \
+                building with IR."),
+            ProgramError::BuiltInKernelsWithSource => write!(f, "No source files, source text, \
+                intermediate language, binaries, or build options may be set when building with \
+                built-in kernels."),
+            ProgramError::BinariesWithSource => write!(f, "No source files, source text, or \
+                intermediate language may be set when building with binaries."),
+            ProgramError::BinariesDeviceCountMismatch { binaries, devices } => write!(f, "The \
+                number of binaries ({}) must equal the number of devices ({}).", binaries,
+                devices),
+            ProgramError::CompileIl => write!(f, "Programs with intermediate language source can \
+                not be compiled separately."),
+            ProgramError::CompileBinaries => write!(f, "Programs with binary source can not be \
+                compiled separately."),
+            ProgramError::BuiltInKernelUnsupported { ref kernel, ref device, ref supported } =>
+                write!(f, "The built-in kernel, '{}', is not supported by the device: '{}' \
+                (supported: {:?}).", kernel, device, supported),
+        }
+    }
+}
+
+impl std::error::Error for ProgramError {}
 
 
 /// A program from which kernels can be created from.
@@ -48,6 +90,126 @@ impl Program {
         Ok(Program(obj_core))
     }
 
+    /// Returns a new program compiled (but not linked) from pre-created build
+    /// components, device list, and embedded headers.
+    ///
+    /// Each header program must have been created from source (using
+    /// `::with_source`) and is made available to `#include` directives under
+    /// its associated name.
+    ///
+    /// Prefer `ProgramBuilder::compile` to create a new compiled `Program`.
+    ///
+    pub fn compile(context_obj_core: &ContextCore, src_strings: Vec<CString>,
+            device_ids: Option<&[Device]>, cmplr_opts: CString, headers: &[(CString, Program)])
+            -> OclResult<Program> {
+        let obj_core = core::create_program_with_source(context_obj_core, &src_strings)?;
+
+        let header_names: Vec<CString> = headers.iter().map(|&(ref n, _)| n.clone()).collect();
+        let header_cores: Vec<&ProgramCore> = headers.iter().map(|&(_, ref p)| p.as_core())
+            .collect();
+
+        core::compile_program(&obj_core, device_ids, &cmplr_opts, &header_cores,
+            &header_names, None)?;
+
+        Ok(Program(obj_core))
+    }
+
+    /// Returns a new program linked from a list of compiled programs and/or
+    /// libraries.
+    ///
+    /// An executable program, from which kernels can be created, is returned
+    /// unless `link_opts` contains `-create-library`, in which case the
+    /// result is a library which may itself be linked into other programs
+    /// (see `::link_library`).
+    ///
+    /// If linking fails, the returned error will contain the build log for
+    /// each device.
+    ///
+    pub fn link(context_obj_core: &ContextCore, programs: &[&Program],
+            device_ids: Option<&[Device]>, link_opts: CString) -> OclResult<Program> {
+        let program_cores: Vec<&ProgramCore> = programs.iter().map(|p| p.as_core()).collect();
+
+        let obj_core = core::link_program(context_obj_core, device_ids, &link_opts,
+            &program_cores, None)?;
+
+        Ok(Program(obj_core))
+    }
+
+    /// Returns a new library linked from a list of compiled programs and/or
+    /// other libraries.
+    ///
+    /// Equivalent to calling `::link` with `-create-library` prepended to
+    /// `link_opts`.
+    ///
+    pub fn link_library(context_obj_core: &ContextCore, programs: &[&Program],
+            device_ids: Option<&[Device]>, link_opts: CString) -> OclResult<Program> {
+        let mut opts = b"-create-library ".to_vec();
+        opts.extend_from_slice(link_opts.as_bytes());
+        Program::link(context_obj_core, programs, device_ids, CString::new(opts)?)
+    }
+
+    /// Returns a new program containing the built-in kernels named in
+    /// `kernel_names`.
+    ///
+    /// Each name must be advertised by every device in `device_ids` (see
+    /// `Device::built_in_kernels`). Kernels can then be created from the
+    /// program as usual (using `Kernel::new`, etc.).
+    ///
+    /// Prefer `ProgramBuilder::built_in_kernel` to create a new `Program`
+    /// with built-in kernels.
+    ///
+    /// [Version Controlled: OpenCL 1.2+]
+    pub fn with_built_in_kernels<S>(context_obj_core: &ContextCore, kernel_names: &[S],
+            device_ids: &[Device]) -> OclResult<Program>
+            where S: AsRef<str> {
+        for device in device_ids {
+            let available = device.built_in_kernels()?;
+
+            for name in kernel_names {
+                if !available.iter().any(|a| a == name.as_ref()) {
+                    return Err(ProgramError::BuiltInKernelUnsupported {
+                        kernel: name.as_ref().to_owned(), device: device.name()?,
+                        supported: available }.into());
+                }
+            }
+        }
+
+        let names = kernel_names.iter().map(|n| n.as_ref()).collect::<Vec<_>>().join(";");
+        let device_versions = context_obj_core.device_versions()?;
+
+        let obj_core = core::create_program_with_built_in_kernels(context_obj_core, device_ids,
+            &CString::new(names)?, Some(&device_versions))?;
+
+        Ok(Program(obj_core))
+    }
+
+    /// Returns a new program built from pre-compiled binaries, one for each
+    /// device in `device_ids`.
+    ///
+    /// Binaries may be obtained from a previously built program with
+    /// `::binaries`. Prefer `ProgramBuilder::binaries` to create a new
+    /// `Program` from binaries.
+    ///
+    pub fn with_binaries(context_obj_core: &ContextCore, binaries: &[&[u8]],
+            device_ids: &[Device], cmplr_opts: CString) -> OclResult<Program> {
+        let obj_core = core::create_program_with_binary(context_obj_core, device_ids, binaries)?;
+
+        core::build_program(&obj_core, Some(device_ids), &cmplr_opts, None, None)?;
+
+        Ok(Program(obj_core))
+    }
+
+    /// Returns a new program created from source but neither compiled nor
+    /// built.
+    ///
+    /// Used to create headers for use with `::compile`.
+    pub fn with_source(context_obj_core: &ContextCore, src_strings: Vec<CString>)
+            -> OclResult<Program> {
+        core::create_program_with_source(context_obj_core, &src_strings)
+            .map(Program)
+            .map_err(OclError::from)
+    }
+
     /// Returns a new program built from pre-created build components and device
     /// list for programs with intermediate language byte source.
     #[cfg(feature = "opencl_version_2_1")]
@@ -82,6 +244,64 @@ impl Program {
         core::get_program_build_info(&self.0, &device, info_kind)
     }
 
+    /// Returns the log from the most recent build, compile, or link of this
+    /// program for each of its associated devices.
+    pub fn build_logs(&self) -> OclResult<Vec<(Device, String)>> {
+        let devices = self.0.devices()?;
+
+        let logs = core::program_build_logs(&self.0, &devices)?;
+        Ok(logs.into_iter().map(|(d, log)| (Device::from(d), log)).collect())
+    }
+
+    /// Returns the binary for each of this program's associated devices.
+    ///
+    /// The binaries may be passed to `ProgramBuilder::binaries` (or
+    /// `::with_binaries`) to re-create this program without recompiling it.
+    /// A device for which the program has not been built will have an empty
+    /// binary.
+    pub fn binaries(&self) -> OclResult<Vec<(Device, Vec<u8>)>> {
+        let devices = self.0.devices()?;
+
+        match self.info(ProgramInfo::Binaries)? {
+            ProgramInfoResult::Binaries(binaries) => {
+                Ok(devices.into_iter().map(Device::from).zip(binaries).collect())
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the names of all kernels defined in this program.
+    ///
+    /// The program must have been successfully built (or linked).
+    pub fn kernel_names(&self) -> OclResult<Vec<String>> {
+        match self.info(ProgramInfo::KernelNames)? {
+            ProgramInfoResult::KernelNames(names) => {
+                Ok(names.split(';').map(str::trim).filter(|n| !n.is_empty())
+                    .map(String::from).collect())
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Creates a kernel for every kernel function defined in this program,
+    /// returning them in a map keyed by kernel name.
+    ///
+    /// The program must have been successfully built (or linked).
+    pub fn create_all_kernels(&self) -> OclResult<HashMap<String, Kernel>> {
+        let kernel_cores = core::create_kernels_in_program(&self.0)?;
+        let mut kernels = HashMap::with_capacity(kernel_cores.len());
+
+        for kernel_core in kernel_cores {
+            let name = match core::get_kernel_info(&kernel_core, KernelInfo::FunctionName)? {
+                KernelInfoResult::FunctionName(name) => name,
+                _ => unreachable!(),
+            };
+            kernels.insert(name, Kernel::from_core(kernel_core)?);
+        }
+
+        Ok(kernels)
+    }
+
     fn fmt_info(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         f.debug_struct("Program")
             .field("ReferenceCount", &self.info(ProgramInfo::ReferenceCount))
@@ -169,6 +389,10 @@ pub struct ProgramBuilder {
     options: Vec<BuildOpt>,
     src_files: Vec<PathBuf>,
     il: Option<Vec<u8>>,
+    binaries: Option<Vec<Vec<u8>>>,
+    cache: Option<ProgramCache>,
+    headers: Vec<(String, String)>,
+    built_in_kernels: Vec<String>,
     device_spec: Option<DeviceSpecifier>,
 }
 
@@ -179,6 +403,10 @@ impl ProgramBuilder {
             options: Vec::with_capacity(64),
             src_files: Vec::with_capacity(16),
             il: None,
+            binaries: None,
+            cache: None,
+            headers: Vec::new(),
+            built_in_kernels: Vec::new(),
             device_spec: None,
         }
     }
@@ -195,23 +423,25 @@ impl ProgramBuilder {
     /// * TODO: Check for duplicate devices in the final device list.
     #[cfg(not(feature = "opencl_version_2_1"))]
     pub fn build(self, context: &Context) -> OclResult<Program> {
+        if !self.headers.is_empty() { return Err(ProgramError::HeadersRequireCompile.into()); }
+
         let device_list = match self.device_spec {
             Some(ref ds) => ds.to_device_list(context.platform()?)?,
             None => context.devices(),
         };
 
+        if !self.built_in_kernels.is_empty() {
+            return self.build_built_in(context, &device_list);
+        }
+
+        if self.binaries.is_some() {
+            return self.build_binaries(context, &device_list);
+        }
+
         match self.il {
-            Some(_) => {
-                return Err("ocl::ProgramBuilder::build: Unreachable section (IL).".into());
-            },
-            None => {
-                Program::new(
-                    context,
-                    self.get_src_strings().map_err(|e| e.to_string())?,
-                    Some(&device_list[..]),
-                    self.get_compiler_options().map_err(|e| e.to_string())?,
-                ).map_err(OclError::from)
-            },
+            // IL can only be set with the `opencl_version_2_1` feature:
+            Some(_) => unreachable!(),
+            None => self.build_src(context, &device_list),
         }
     }
 
@@ -224,36 +454,164 @@ impl ProgramBuilder {
     /// * TODO: Check for duplicate devices in the final device list.
     #[cfg(feature = "opencl_version_2_1")]
     pub fn build(mut self, context: &Context) -> OclResult<Program> {
+        if !self.headers.is_empty() { return Err(ProgramError::HeadersRequireCompile.into()); }
+
         let device_list = match self.device_spec {
             Some(ref ds) => ds.to_device_list(context.platform()?)?,
             None => context.devices().to_owned(),
         };
 
+        if !self.built_in_kernels.is_empty() {
+            return self.build_built_in(context, &device_list);
+        }
+
+        if self.binaries.is_some() {
+            return self.build_binaries(context, &device_list);
+        }
+
         match self.il.take() {
             Some(il) => {
-                if cfg!(feature = "opencl_version_2_1") {
-                    if self.src_files.len() > 0 { return Err("ProgramBuilder::build: \
-                        No source files may be set when building with IR.".into()); }
-
-                    Program::with_il(
-                        il,
-                        Some(&device_list[..]),
-                        self.get_compiler_options().map_err(|e| e.to_string())?,
-                        context
-                    )
-                } else {
-                    return Err("ocl::ProgramBuilder::build: Unreachable section.".into());
-                }
-            },
-            None => {
-                Program::new(
-                    context,
-                    self.get_src_strings().map_err(|e| e.to_string())?,
+                if self.src_files.len() > 0 { return Err(ProgramError::IlWithSourceFiles.into()); }
+
+                Program::with_il(
+                    il,
                     Some(&device_list[..]),
-                    self.get_compiler_options().map_err(|e| e.to_string())?,
+                    self.get_compiler_options()?,
+                    context
                 )
             },
+            None => self.build_src(context, &device_list),
+        }
+    }
+
+    /// Builds a program from the built-in kernels specified with
+    /// `::built_in_kernel`.
+    fn build_built_in(&self, context: &Context, device_list: &[Device]) -> OclResult<Program> {
+        if self.il.is_some() || self.binaries.is_some() || !self.src_files.is_empty() ||
+                !self.options.is_empty() {
+            return Err(ProgramError::BuiltInKernelsWithSource.into());
+        }
+
+        Program::with_built_in_kernels(context, &self.built_in_kernels, device_list)
+    }
+
+    /// Builds a program from the binaries specified with `::binaries`.
+    fn build_binaries(&self, context: &Context, device_list: &[Device]) -> OclResult<Program> {
+        let has_src_text = self.options.iter().any(|opt| match *opt {
+            BuildOpt::IncludeDefine { .. } | BuildOpt::IncludeRaw(_) |
+                BuildOpt::IncludeRawEof(_) => true,
+            _ => false,
+        });
+
+        if self.il.is_some() || !self.src_files.is_empty() || has_src_text {
+            return Err(ProgramError::BinariesWithSource.into());
+        }
+
+        let binaries = self.binaries.as_ref().unwrap();
+        if binaries.len() != device_list.len() {
+            return Err(ProgramError::BinariesDeviceCountMismatch { binaries: binaries.len(),
+                devices: device_list.len() }.into());
+        }
+
+        let binaries: Vec<&[u8]> = binaries.iter().map(|b| &b[..]).collect();
+        Program::with_binaries(context, &binaries, device_list,
+            self.get_compiler_options()?)
+    }
+
+    /// Builds a program from source, loading it from (and storing it in) the
+    /// cache specified with `::cache`, if any.
+    ///
+    /// Cached binaries which fail to load or build (because of a driver
+    /// update, for example) are ignored and overwritten.
+    fn build_src(&self, context: &Context, device_list: &[Device]) -> OclResult<Program> {
+        let src_strings = self.get_src_strings()?;
+        let cmplr_opts = self.get_compiler_options()?;
+
+        let cache = match self.cache {
+            Some(ref cache) => cache,
+            None => return Program::new(context, src_strings, Some(device_list), cmplr_opts),
+        };
+
+        let mut keys = Vec::with_capacity(device_list.len());
+        for device in device_list {
+            keys.push(cache.key(&src_strings, &cmplr_opts, device)?);
+        }
+
+        let mut binaries = Vec::with_capacity(keys.len());
+        for key in &keys {
+            match cache.load(key)? {
+                Some(binary) => binaries.push(binary),
+                None => break,
+            }
+        }
+
+        if !binaries.is_empty() && binaries.len() == keys.len() {
+            let binaries: Vec<&[u8]> = binaries.iter().map(|b| &b[..]).collect();
+            if let Ok(program) = Program::with_binaries(context, &binaries, device_list,
+                    cmplr_opts.clone()) {
+                return Ok(program);
+            }
+        }
+
+        let program = Program::new(context, src_strings, Some(device_list), cmplr_opts)?;
+
+        for (device, binary) in program.binaries()? {
+            if binary.is_empty() { continue; }
+            if let Some(idx) = device_list.iter().position(|d| *d == device) {
+                cache.store(&keys[idx], &binary)?;
+            }
+        }
+
+        Ok(program)
+    }
+
+    /// Returns a newly compiled, but not linked, Program.
+    ///
+    /// The resulting program object may be linked with others (and with
+    /// libraries) using `Program::link` or `Program::link_library`. Embedded
+    /// headers added with `::header` are made available to the compiler.
+    ///
+    /// If compilation fails, the returned error will contain the build log
+    /// for each device.
+    ///
+    pub fn compile(&self, context: &Context) -> OclResult<Program> {
+        if self.il.is_some() { return Err(ProgramError::CompileIl.into()); }
+        if self.binaries.is_some() { return Err(ProgramError::CompileBinaries.into()); }
+
+        let device_list = match self.device_spec {
+            Some(ref ds) => ds.to_device_list(context.platform()?)?,
+            None => context.devices(),
+        };
+
+        let mut headers = Vec::with_capacity(self.headers.len());
+        for &(ref name, ref src) in self.headers.iter() {
+            let header = Program::with_source(context, vec![CString::new(src.clone())?])?;
+            headers.push((CString::new(name.clone())?, header));
         }
+
+        Program::compile(
+            context,
+            self.get_src_strings()?,
+            Some(&device_list[..]),
+            self.get_compiler_options()?,
+            &headers,
+        )
+    }
+
+    /// Returns a newly compiled Program, linked into a library.
+    ///
+    /// The resulting library may be linked into other programs using
+    /// `Program::link`.
+    ///
+    pub fn compile_library(&self, context: &Context) -> OclResult<Program> {
+        let object = self.compile(context)?;
+
+        let device_list = match self.device_spec {
+            Some(ref ds) => ds.to_device_list(context.platform()?)?,
+            None => context.devices(),
+        };
+
+        Program::link_library(context, &[&object], Some(&device_list[..]), CString::new("")?)
     }
 
     /// Adds a build option containing a compiler command line definition.
@@ -301,6 +659,35 @@ impl ProgramBuilder {
         self
     }
 
+    /// Adds a built-in kernel, by name, to this program.
+    ///
+    /// Built-in kernels are device-specific kernels provided by the
+    /// implementation (see `Device::built_in_kernels`). When any are added,
+    /// the program will contain only built-in kernels and any source files,
+    /// source text, or build options will cause an error upon building. Each
+    /// name is validated against the kernels advertised by every device the
+    /// program is built for.
+    ///
+    /// [Version Controlled: OpenCL 1.2+]
+    pub fn built_in_kernel<S: Into<String>>(mut self, name: S) -> ProgramBuilder {
+        self.built_in_kernels.push(name.into());
+        self
+    }
+
+    /// Adds an embedded header, available to `#include` directives as
+    /// `include_name`, for use when compiling this program with
+    /// `::compile` or `::compile_library`.
+    ///
+    /// ## Example
+    ///
+    /// `...header("helpers.h", "float square(float x) { return x * x; }")...`
+    ///
+    pub fn header<S, T>(mut self, include_name: S, src: T) -> ProgramBuilder
+            where S: Into<String>, T: Into<String> {
+        self.headers.push((include_name.into(), src.into()));
+        self
+    }
+
     /// Adds SPIR-V or an implementation-defined intermediate language to this program.
     ///
     /// Any source files or source text added to this build will cause an
@@ -317,6 +704,32 @@ impl ProgramBuilder {
         self
     }
 
+    /// Adds pre-compiled binaries to this program, one for each device it is
+    /// built for (in the same order as those devices).
+    ///
+    /// Binaries can be obtained from a previously built program using
+    /// `Program::binaries`. Any source files, source text, or intermediate
+    /// language added to this build will cause an error upon building. Build
+    /// options are passed to the compiler as usual.
+    ///
+    pub fn binaries(mut self, binaries: Vec<Vec<u8>>) -> ProgramBuilder {
+        self.binaries = Some(binaries);
+        self
+    }
+
+    /// Specifies a cache in which to store and look up binaries for this
+    /// program.
+    ///
+    /// Only programs built from source (using `::build`) are cached. See
+    /// [`ProgramCache`][program_cache] for more information.
+    ///
+    /// [program_cache]: struct.ProgramCache.html
+    ///
+    pub fn cache(mut self, cache: ProgramCache) -> ProgramBuilder {
+        self.cache = Some(cache);
+        self
+    }
+
     /// Specifies a list of devices to build this program on. The devices must
     /// be associated with the context passed to `::build` later on.
     ///
@@ -0,0 +1,170 @@
+//! A persistent, on-disk cache of program binaries.
+
+use std::ffi::CString;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use core::{DeviceInfo, DeviceInfoResult};
+use error::Result as OclResult;
+use standard::{Device, Platform};
+
+
+/// The 64-bit FNV-1a offset basis.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// The 64-bit FNV-1a prime.
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// The file extension used for cached binaries.
+const BINARY_EXT: &str = "clbin";
+
+
+/// A minimal FNV-1a hasher.
+///
+/// Used instead of `DefaultHasher` because cache keys must remain stable
+/// across processes and compiler versions.
+struct KeyHasher(u64);
+
+impl KeyHasher {
+    fn new() -> KeyHasher {
+        KeyHasher(FNV_OFFSET_BASIS)
+    }
+
+    /// Hashes `bytes`, prefixed by their length so that adjacent fields can
+    /// not run together.
+    fn write_field(&mut self, bytes: &[u8]) {
+        self.write_u64(bytes.len() as u64);
+        self.write(bytes);
+    }
+
+    fn write_u64(&mut self, val: u64) {
+        for i in 0..8 {
+            self.write(&[(val >> (i * 8)) as u8]);
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+
+/// A directory of program binaries, keyed by program source, compiler
+/// options, and device.
+///
+/// Pass to `ProgramBuilder::cache` to have programs built from source loaded
+/// from previously stored binaries when possible. Binaries rejected by the
+/// driver (after a driver update, for example) are rebuilt from source and
+/// overwritten.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// let cache = ProgramCache::new("target/kernel_cache")?;
+/// let program = Program::builder()
+///     .src(src)
+///     .devices(device)
+///     .cache(cache)
+///     .build(&context)?;
+/// ```
+///
+#[derive(Clone, Debug)]
+pub struct ProgramCache {
+    dir: PathBuf,
+}
+
+impl ProgramCache {
+    /// Returns a new `ProgramCache` which stores binaries in `dir`, creating
+    /// the directory if it does not exist.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> OclResult<ProgramCache> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(ProgramCache { dir: dir })
+    }
+
+    /// Returns the directory in which binaries are stored.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the cache key for a program built from `src_strings` with
+    /// `cmplr_opts` on `device`.
+    ///
+    /// The key is a hash of the source strings, the compiler options, and
+    /// the device name, driver version, and platform name and version.
+    pub fn key(&self, src_strings: &[CString], cmplr_opts: &CString, device: &Device)
+            -> OclResult<String> {
+        let driver_version = match device.info(DeviceInfo::DriverVersion)? {
+            DeviceInfoResult::DriverVersion(v) => v,
+            _ => unreachable!(),
+        };
+
+        let platform = match device.info(DeviceInfo::Platform)? {
+            DeviceInfoResult::Platform(p) => Platform::new(p),
+            _ => unreachable!(),
+        };
+
+        let mut hasher = KeyHasher::new();
+        hasher.write_u64(src_strings.len() as u64);
+        for src in src_strings {
+            hasher.write_field(src.as_bytes());
+        }
+        hasher.write_field(cmplr_opts.as_bytes());
+        hasher.write_field(device.name()?.as_bytes());
+        hasher.write_field(driver_version.as_bytes());
+        hasher.write_field(platform.name()?.as_bytes());
+        hasher.write_field(platform.version()?.as_bytes());
+
+        Ok(format!("{:016x}", hasher.finish()))
+    }
+
+    /// Returns the binary stored under `key`, if any.
+    pub fn load(&self, key: &str) -> OclResult<Option<Vec<u8>>> {
+        let path = self.path(key);
+        if !path.is_file() { return Ok(None); }
+
+        let mut binary = Vec::new();
+        File::open(&path)?.read_to_end(&mut binary)?;
+
+        if binary.is_empty() { Ok(None) } else { Ok(Some(binary)) }
+    }
+
+    /// Stores `binary` under `key`, replacing any existing binary.
+    ///
+    /// The binary is written to a temporary file which is then renamed so
+    /// that other processes never observe a partially written binary.
+    pub fn store(&self, key: &str, binary: &[u8]) -> OclResult<()> {
+        let path = self.path(key);
+        let tmp_path = self.dir.join(format!("{}.{}.tmp", key, ::std::process::id()));
+
+        {
+            let mut file = File::create(&tmp_path)?;
+            file.write_all(binary)?;
+            file.sync_all()?;
+        }
+
+        fs::rename(&tmp_path, &path).map_err(|err| {
+            let _ = fs::remove_file(&tmp_path);
+            err.into()
+        })
+    }
+
+    /// Removes the binary stored under `key`, if any.
+    pub fn remove(&self, key: &str) -> OclResult<()> {
+        let path = self.path(key);
+        if path.is_file() { fs::remove_file(&path)?; }
+        Ok(())
+    }
+
+    /// Returns the path of the file for `key`.
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", key, BINARY_EXT))
+    }
+}
@@ -2,10 +2,33 @@
 
 use std;
 use std::ops::{Deref, DerefMut};
+use ffi::c_void;
 use core::{self, Result as OclCoreResult, CommandQueue as CommandQueueCore, CommandQueueInfo,
-    CommandQueueInfoResult, OpenclVersion, CommandQueueProperties, ClWaitListPtr, ClContextPtr};
+    CommandQueueInfoResult, OpenclVersion, CommandQueueProperties, ClWaitListPtr, ClContextPtr,
+    Mem as MemCore, NativeKernelFn, OclPrm, QueueProperties, QueuePriority, QueueThrottle};
 use error::{Error as OclError, Result as OclResult};
-use standard::{Context, Device, Event};
+use standard::{Context, Device, Event, Buffer, ClNullEventPtrEnum, ClWaitListPtrEnum, Profiler,
+    TraceRecorder};
+
+/// A queue related error.
+#[derive(Debug)]
+pub enum QueueError {
+    ContextHasNoDevices,
+    ProfilingDisabled,
+}
+
+impl std::fmt::Display for QueueError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            QueueError::ContextHasNoDevices => write!(f, "The context has no devices."),
+            QueueError::ProfilingDisabled => write!(f, "The queue must be created with \
+                'CommandQueueProperties::PROFILING_ENABLE'."),
+        }
+    }
+}
+
+impl std::error::Error for QueueError {}
+
 
 /// A command queue which manages all actions taken on kernels, buffers, and
 /// images.
@@ -20,6 +43,8 @@ use standard::{Context, Device, Event};
 pub struct Queue {
     obj_core: CommandQueueCore,
     device_version: OpenclVersion,
+    profiler: Option<Profiler>,
+    trace_recorder: Option<TraceRecorder>,
 }
 
 impl Queue {
@@ -32,9 +57,79 @@ impl Queue {
         Ok(Queue {
             obj_core: obj_core,
             device_version: device_version,
+            profiler: None,
+            trace_recorder: None,
         })
     }
 
+    /// Returns a new Queue on the device specified by `device` created using
+    /// a full list of queue properties.
+    ///
+    /// Queues are created with `clCreateCommandQueueWithProperties` on
+    /// OpenCL 2.0+ devices and with `clCreateCommandQueue` otherwise. See
+    /// `core::create_command_queue_with_properties`.
+    pub fn with_properties(context: &Context, device: Device, properties: &QueueProperties)
+            -> OclResult<Queue> {
+        let device_version = device.version()?;
+        let obj_core = core::create_command_queue_with_properties(context, &device, properties,
+            Some(&device_version))?;
+
+        Ok(Queue {
+            obj_core: obj_core,
+            device_version: device_version,
+            profiler: None,
+            trace_recorder: None,
+        })
+    }
+
+    /// Returns a new `QueueBuilder`.
+    pub fn builder() -> QueueBuilder {
+        QueueBuilder::new()
+    }
+
+    /// Sets the profiler which records kernel, buffer, and image commands
+    /// enqueued using this queue (or, for kernel commands, using a kernel with this
+    /// queue as its default).
+    ///
+    /// Only affects this queue and clones made afterwards. The queue must
+    /// have been created with `CommandQueueProperties::PROFILING_ENABLE`.
+    pub fn set_profiler(&mut self, profiler: Option<Profiler>) {
+        self.profiler = profiler;
+    }
+
+    /// Returns the profiler set for this queue, if any.
+    pub fn profiler(&self) -> Option<&Profiler> {
+        self.profiler.as_ref()
+    }
+
+    /// Sets the trace recorder which records kernel, buffer, and image
+    /// commands enqueued using this queue (or, for kernel commands, using a
+    /// kernel with this queue as its default).
+    ///
+    /// Only affects this queue and clones made afterwards. The queue must
+    /// have been created with `CommandQueueProperties::PROFILING_ENABLE`.
+    pub fn set_trace_recorder(&mut self, recorder: Option<TraceRecorder>) {
+        self.trace_recorder = recorder;
+    }
+
+    /// Returns the trace recorder set for this queue, if any.
+    pub fn trace_recorder(&self) -> Option<&TraceRecorder> {
+        self.trace_recorder.as_ref()
+    }
+
+    /// Makes this queue the default on-device queue for its device.
+    ///
+    /// This queue must have been created as an on-device queue (see
+    /// `QueueBuilder::on_device`).
+    ///
+    /// [Version Controlled: OpenCL 2.1+]
+    ///
+    #[cfg(feature = "opencl_version_2_1")]
+    pub fn set_default_device_queue(&self) -> OclResult<()> {
+        core::set_default_device_command_queue(&self.context(), &self.device(), &self.obj_core,
+            Some(&self.device_version)).map_err(OclError::from)
+    }
+
     /// Issues all previously queued OpenCL commands to the device.
     pub fn flush(&self) -> OclResult<()> {
         core::flush(&self.obj_core).map_err(OclError::from)
@@ -56,6 +151,38 @@ impl Queue {
             .map_err(OclError::from)
     }
 
+    /// Returns a native kernel command builder which, when enqueued, will
+    /// run `func` on a host thread managed by the OpenCL runtime.
+    ///
+    /// Native kernels are ordered with respect to other commands in the
+    /// same way as any other command (by queue and by events). `func` is
+    /// passed a pointer to the contents of each buffer added with
+    /// `NativeKernelCmd::buf`, in order.
+    ///
+    /// The device associated with this queue must support native kernels
+    /// (generally only CPU devices do). See
+    /// `DeviceExecCapabilities::NATIVE_KERNEL`.
+    ///
+    /// ## Example
+    ///
+    /// ```rust,ignore
+    /// queue.enqueue_native(|ptrs| println!("Buffer contents at: {:?}", ptrs[0]))
+    ///     .buf(&buffer)
+    ///     .ewait(&kernel_a_event)
+    ///     .enew(&mut native_event)
+    ///     .enq()?;
+    /// ```
+    pub fn enqueue_native<'c, F>(&'c self, func: F) -> NativeKernelCmd<'c>
+            where F: FnOnce(&[*mut c_void]) + Send + 'static {
+        NativeKernelCmd {
+            queue: &self.obj_core,
+            func: Box::new(func),
+            mem_objs: Vec::new(),
+            wait_events: None,
+            new_event: None,
+        }
+    }
+
     /// Returns a reference to the core pointer wrapper, usable by functions in
     /// the `core` module.
     #[inline]
@@ -84,12 +211,20 @@ impl Queue {
     }
 
     fn fmt_info(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        f.debug_struct("Queue")
-            .field("Context", &self.info(CommandQueueInfo::Context))
+        let properties = self.info(CommandQueueInfo::Properties);
+        let mut dbg = f.debug_struct("Queue");
+        dbg.field("Context", &self.info(CommandQueueInfo::Context))
             .field("Device", &self.info(CommandQueueInfo::Device))
             .field("ReferenceCount", &self.info(CommandQueueInfo::ReferenceCount))
-            .field("Properties", &self.info(CommandQueueInfo::Properties))
-            .finish()
+            .field("Properties", &properties);
+
+        // `CL_QUEUE_SIZE` is only valid for on-device queues:
+        if let Ok(CommandQueueInfoResult::Properties(props)) = properties {
+            if props.contains(CommandQueueProperties::ON_DEVICE) {
+                dbg.field("Size", &self.info(CommandQueueInfo::Size));
+            }
+        }
+        dbg.finish()
     }
 }
 
@@ -130,4 +265,196 @@ unsafe impl<'a> ClContextPtr for &'a Queue {
         self.context_ptr().expect("<&Queue as ClContextPtr>::as_ptr: \
             Unable to obtain a context pointer.")
     }
-}
\ No newline at end of file
+}
+
+/// A builder for `Queue`.
+///
+/// Create using `Queue::builder`.
+///
+/// ## Defaults
+///
+/// * The first device associated with the context passed to `::build`
+/// * No properties (an in-order, host-side queue)
+///
+#[must_use = "builders do nothing unless '::build' is called"]
+#[derive(Clone, Debug)]
+pub struct QueueBuilder {
+    device: Option<Device>,
+    properties: QueueProperties,
+    profiler: Option<Profiler>,
+    trace_recorder: Option<TraceRecorder>,
+}
+
+impl QueueBuilder {
+    /// Returns a new `QueueBuilder`.
+    pub fn new() -> QueueBuilder {
+        QueueBuilder {
+            device: None,
+            properties: QueueProperties::new(),
+            profiler: None,
+            trace_recorder: None,
+        }
+    }
+
+    /// Specifies the device on which to create the queue.
+    pub fn device(mut self, device: Device) -> QueueBuilder {
+        self.device = Some(device);
+        self
+    }
+
+    /// Specifies the command queue properties bitfield directly.
+    ///
+    /// Overwrites any previously specified command queue properties.
+    pub fn properties(mut self, properties: CommandQueueProperties) -> QueueBuilder {
+        self.properties = self.properties.properties(properties);
+        self
+    }
+
+    /// Enables out-of-order execution.
+    pub fn out_of_order(self) -> QueueBuilder {
+        self.add_properties(CommandQueueProperties::OUT_OF_ORDER_EXEC_MODE_ENABLE)
+    }
+
+    /// Enables profiling.
+    pub fn profiling(self) -> QueueBuilder {
+        self.add_properties(CommandQueueProperties::PROFILING_ENABLE)
+    }
+
+    /// Specifies a profiler which will record commands enqueued using the
+    /// queue (see `Queue::set_profiler`).
+    ///
+    /// Implies `::profiling`.
+    pub fn profiler(mut self, profiler: Profiler) -> QueueBuilder {
+        self.profiler = Some(profiler);
+        self.profiling()
+    }
+
+    /// Specifies a trace recorder which will record commands enqueued using
+    /// the queue (see `Queue::set_trace_recorder`).
+    ///
+    /// Implies `::profiling`.
+    pub fn trace_recorder(mut self, recorder: TraceRecorder) -> QueueBuilder {
+        self.trace_recorder = Some(recorder);
+        self.profiling()
+    }
+
+    /// Creates an on-device queue, used by kernels to enqueue child kernels.
+    ///
+    /// Implies `::out_of_order`.
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    ///
+    pub fn on_device(self) -> QueueBuilder {
+        self.add_properties(CommandQueueProperties::ON_DEVICE |
+            CommandQueueProperties::OUT_OF_ORDER_EXEC_MODE_ENABLE)
+    }
+
+    /// Creates the default on-device queue for the device.
+    ///
+    /// Implies `::on_device`. Use `Queue::set_default_device_queue` to
+    /// replace an existing default queue (OpenCL 2.1+).
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    ///
+    pub fn on_device_default(self) -> QueueBuilder {
+        self.on_device().add_properties(CommandQueueProperties::ON_DEVICE_DEFAULT)
+    }
+
+    /// Specifies the size of an on-device queue in bytes.
+    ///
+    /// Must not exceed the `CL_DEVICE_QUEUE_ON_DEVICE_MAX_SIZE` of the device.
+    ///
+    /// [Version Controlled: OpenCL 2.0+]
+    ///
+    pub fn size(mut self, size: u32) -> QueueBuilder {
+        self.properties = self.properties.size(size);
+        self
+    }
+
+    /// Specifies a priority hint.
+    ///
+    /// Requires the `cl_khr_priority_hints` extension and OpenCL 2.0+.
+    pub fn priority(mut self, priority: QueuePriority) -> QueueBuilder {
+        self.properties = self.properties.priority(priority);
+        self
+    }
+
+    /// Specifies a throttle hint.
+    ///
+    /// Requires the `cl_khr_throttle_hints` extension and OpenCL 2.0+.
+    pub fn throttle(mut self, throttle: QueueThrottle) -> QueueBuilder {
+        self.properties = self.properties.throttle(throttle);
+        self
+    }
+
+    /// Returns a new `Queue` associated with `context`.
+    pub fn build(self, context: &Context) -> OclResult<Queue> {
+        let device = match self.device {
+            Some(d) => d,
+            None => match context.devices().first() {
+                Some(&d) => d,
+                None => return Err(QueueError::ContextHasNoDevices.into()),
+            },
+        };
+
+        let mut queue = Queue::with_properties(context, device, &self.properties)?;
+        queue.set_profiler(self.profiler);
+        queue.set_trace_recorder(self.trace_recorder);
+        Ok(queue)
+    }
+
+    /// Adds `properties` to any already specified.
+    fn add_properties(mut self, properties: CommandQueueProperties) -> QueueBuilder {
+        let props = self.properties.get_properties().unwrap_or(CommandQueueProperties::empty());
+        self.properties = self.properties.properties(props | properties);
+        self
+    }
+}
+
+
+/// A native kernel command builder used to enqueue a host function.
+///
+/// Create using `Queue::enqueue_native`.
+#[must_use = "commands do nothing unless enqueued"]
+pub struct NativeKernelCmd<'c> {
+    queue: &'c CommandQueueCore,
+    func: NativeKernelFn,
+    mem_objs: Vec<&'c MemCore>,
+    wait_events: Option<ClWaitListPtrEnum<'c>>,
+    new_event: Option<ClNullEventPtrEnum<'c>>,
+}
+
+impl<'c> NativeKernelCmd<'c> {
+    /// Adds a buffer, a pointer to the contents of which will be passed to
+    /// the native function (in the order added).
+    pub fn buf<T: OclPrm>(mut self, buffer: &'c Buffer<T>) -> NativeKernelCmd<'c> {
+        self.mem_objs.push(buffer.as_core());
+        self
+    }
+
+    /// Specifies an event or list of events to wait on before the command
+    /// will run.
+    ///
+    /// See `KernelCmd::ewait` for more information.
+    pub fn ewait<'e, Ewl>(mut self, ewait: Ewl) -> NativeKernelCmd<'c>
+            where 'e: 'c, Ewl: Into<ClWaitListPtrEnum<'e>> {
+        self.wait_events = Some(ewait.into());
+        self
+    }
+
+    /// Specifies the destination to store a new, optionally created event
+    /// associated with this command.
+    ///
+    /// See `KernelCmd::enew` for more information.
+    pub fn enew<'e, En>(mut self, new_event_dest: En) -> NativeKernelCmd<'c>
+            where 'e: 'c, En: Into<ClNullEventPtrEnum<'e>> {
+        self.new_event = Some(new_event_dest.into());
+        self
+    }
+
+    /// Enqueues this native kernel command.
+    pub fn enq(self) -> OclResult<()> {
+        core::enqueue_native_kernel(self.queue, self.func, &self.mem_objs, self.wait_events,
+            self.new_event).map_err(OclError::from)
+    }
+}
@@ -1,6 +1,7 @@
 //! A simple way to specify the sizes or offsets of up to three dimensions.
 use std::convert::From;
-use std::fmt::Debug;
+use std::fmt::{self, Debug};
+use std::error::Error as StdError;
 use std::ops::Index;
 use num_traits::{Num, ToPrimitive};
 use error::{Result as OclResult};
@@ -8,10 +9,25 @@ use standard::{MemLen, WorkDims};
 use core::util;
 
 
-#[derive(Debug, Fail)]
-#[fail(display = "Cannot convert to a valid set of dimensions. \
-    Please specify some dimensions.")]
-pub struct UnspecifiedDimensionsError;
+/// A `SpatialDims` related error.
+#[derive(Debug)]
+pub enum SpatialDimsError {
+    Unspecified,
+    OutOfOrder,
+}
+
+impl fmt::Display for SpatialDimsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SpatialDimsError::Unspecified => write!(f, "Cannot convert to a valid set of \
+                dimensions. Please specify some dimensions."),
+            SpatialDimsError::OutOfOrder => write!(f, "Dimensions must be defined from left to \
+                right. If you define the 2nd dimension, you must also define the 1st, etc."),
+        }
+    }
+}
+
+impl StdError for SpatialDimsError {}
 
 
 /// Specifies a size or offset in up to three dimensions.
@@ -47,20 +63,17 @@ impl SpatialDims {
     /// instead.
     ///
     pub fn new(d0: Option<usize>, d1: Option<usize>, d2: Option<usize>) -> OclResult<SpatialDims> {
-        let std_err_msg = "Dimensions must be defined from left to right. If you define the 2nd \
-            dimension, you must also define the 1st, etc.";
-
         if d2.is_some() {
             if d1.is_some() && d0.is_some() {
                 Ok(SpatialDims::Three(d0.unwrap(), d1.unwrap(), d2.unwrap()))
             } else {
-                Err(std_err_msg.into())
+                Err(SpatialDimsError::OutOfOrder.into())
             }
         } else if d1.is_some() {
             if d0.is_some() {
                 Ok(SpatialDims::Two(d1.unwrap(), d0.unwrap()))
             } else {
-                Err(std_err_msg.into())
+                Err(SpatialDimsError::OutOfOrder.into())
             }
         } else if d0.is_some() {
             Ok(SpatialDims::One(d0.unwrap()))
@@ -80,9 +93,9 @@ impl SpatialDims {
     }
 
     /// Returns a 3D size or an error if unspecified.
-    pub fn to_lens(&self) -> Result<[usize; 3], UnspecifiedDimensionsError> {
+    pub fn to_lens(&self) -> Result<[usize; 3], SpatialDimsError> {
         match *self {
-            SpatialDims::Unspecified => Err(UnspecifiedDimensionsError),
+            SpatialDims::Unspecified => Err(SpatialDimsError::Unspecified),
             SpatialDims::One(x) => Ok([x, 1, 1]),
             SpatialDims::Two(x, y) => Ok([x, y, 1]),
             SpatialDims::Three(x, y, z) => Ok([x, y, z]),
@@ -90,9 +103,9 @@ impl SpatialDims {
     }
 
     /// Returns a 3D offset or an error if unspecified.
-    pub fn to_offset(&self) -> Result<[usize; 3], UnspecifiedDimensionsError> {
+    pub fn to_offset(&self) -> Result<[usize; 3], SpatialDimsError> {
         match *self {
-            SpatialDims::Unspecified => Err(UnspecifiedDimensionsError),
+            SpatialDims::Unspecified => Err(SpatialDimsError::Unspecified),
             SpatialDims::One(x) => Ok([x, 0, 0]),
             SpatialDims::Two(x, y) => Ok([x, y, 0]),
             SpatialDims::Three(x, y, z) => Ok([x, y, z]),
@@ -0,0 +1,303 @@
+//! Shared virtual memory.
+
+use std;
+use std::slice;
+use std::sync::Arc;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use ffi::c_void;
+use core::{self, OclPrm, SvmMemFlags, MapFlags};
+use error::{Error as OclError, Result as OclResult};
+use standard::{Queue, Event, EventList};
+
+
+/// A shared virtual memory related error.
+#[derive(Debug)]
+pub enum SvmError {
+    LenZero,
+    LenTooLarge { len: usize },
+    LenMismatch { expected: usize, found: usize },
+}
+
+impl std::fmt::Display for SvmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            SvmError::LenZero => write!(f, "`len` must be greater than zero."),
+            SvmError::LenTooLarge { len } => write!(f, "`len` ({}) is too large: the size \
+                of the allocation in bytes overflows a `usize`.", len),
+            SvmError::LenMismatch { expected, found } => write!(f, "Slice length ({}) must \
+                equal the length of this `SvmVec` ({}).", found, expected),
+        }
+    }
+}
+
+impl std::error::Error for SvmError {}
+
+
+/// A shared virtual memory allocation, freed when dropped.
+///
+/// Shared between an `SvmVec` and any kernels it has been set as an argument
+/// for so that the allocation outlives every enqueued use of it.
+#[derive(Debug)]
+pub(crate) struct SvmAlloc {
+    ptr: *mut c_void,
+    queue: Queue,
+}
+
+impl Drop for SvmAlloc {
+    /// Enqueues a command to free the allocation once all previously
+    /// enqueued commands on the queue have completed.
+    fn drop(&mut self) {
+        unsafe {
+            core::enqueue_svm_free(self.queue.as_core(), &[self.ptr], None::<&EventList>,
+                None::<&mut Event>, Some(&self.queue.device_version()))
+                .expect("error dropping `SvmVec`");
+        }
+    }
+}
+
+impl SvmAlloc {
+    /// Returns a pointer to the start of the allocation.
+    pub(crate) fn as_ptr(&self) -> *mut c_void {
+        self.ptr
+    }
+}
+
+unsafe impl Send for SvmAlloc {}
+unsafe impl Sync for SvmAlloc {}
+
+
+/// A fixed-length, contiguous region of shared virtual memory (SVM) which
+/// can be accessed by both the host and devices using the same pointers.
+///
+/// Pointers into an `SvmVec` can be stored within other SVM allocations and
+/// followed from within kernels, allowing pointer-based data structures
+/// (trees, linked lists, etc.) to be shared with devices. Kernels which
+/// follow such pointers must be told about the allocations they may access
+/// (see `Kernel::set_exec_info`).
+///
+/// ## Host access
+///
+/// An `SvmVec` dereferences to a slice (`[T]`) but only while its memory is
+/// accessible to the host:
+///
+/// * Coarse-grained allocations (the default) must first be mapped with
+///   `::map` and later unmapped with `::unmap` before being used by a
+///   kernel.
+/// * Fine-grained allocations (created with
+///   `SvmMemFlags::FINE_GRAIN_BUFFER`) are always accessible. Take care to
+///   only access memory which is not in use by a running kernel.
+///
+/// Dereferencing while the memory is inaccessible will panic.
+///
+/// [Version Controlled: OpenCL 2.0+]
+///
+#[derive(Debug)]
+pub struct SvmVec<T: OclPrm> {
+    alloc: Arc<SvmAlloc>,
+    len: usize,
+    flags: SvmMemFlags,
+    mapped: bool,
+    _data: PhantomData<T>,
+}
+
+impl<T: OclPrm> SvmVec<T> {
+    /// Returns a new, coarse-grained, read/write `SvmVec` of `len` elements
+    /// with each element set to its default value.
+    pub fn new(queue: Queue, len: usize) -> OclResult<SvmVec<T>> {
+        SvmVec::with_flags(queue, SvmMemFlags::default(), len)
+    }
+
+    /// Returns a new `SvmVec` of `len` elements allocated using `flags` with
+    /// each element set to its default value.
+    ///
+    /// All commands (fill, map, free, etc.) are enqueued on `queue`. Returns
+    /// an error if `len` is zero or its size in bytes overflows a `usize`.
+    pub fn with_flags(queue: Queue, flags: SvmMemFlags, len: usize) -> OclResult<SvmVec<T>> {
+        if len == 0 {
+            return Err(SvmError::LenZero.into());
+        }
+
+        let size = len.checked_mul(std::mem::size_of::<T>())
+            .ok_or(SvmError::LenTooLarge { len: len })?;
+        let ptr = core::svm_alloc(&queue.context(), flags, size, 0, None)?;
+
+        let mut svm = SvmVec {
+            alloc: Arc::new(SvmAlloc { ptr: ptr, queue: queue }),
+            len: len,
+            flags: flags,
+            mapped: false,
+            _data: PhantomData,
+        };
+
+        svm.fill(Default::default())?;
+        Ok(svm)
+    }
+
+    /// Fills every element with `val`, blocking until complete.
+    ///
+    /// Coarse-grained memory must not be mapped while filling.
+    pub fn fill(&mut self, val: T) -> OclResult<()> {
+        let mut new_event = Event::empty();
+
+        unsafe {
+            core::enqueue_svm_mem_fill(self.queue().as_core(), self.alloc.ptr as *mut T, val,
+                self.len, None::<&EventList>, Some(&mut new_event),
+                Some(&self.queue().device_version()))?;
+        }
+
+        new_event.wait_for().map_err(OclError::from)
+    }
+
+    /// Maps the memory for host access, blocking until complete.
+    ///
+    /// Does nothing if already mapped.
+    pub fn map(&mut self, flags: MapFlags) -> OclResult<()> {
+        if self.mapped { return Ok(()); }
+
+        unsafe {
+            core::enqueue_svm_map(self.queue().as_core(), true, flags, self.alloc.ptr as *mut T,
+                self.len, None::<&EventList>, None::<&mut Event>,
+                Some(&self.queue().device_version()))?;
+        }
+
+        self.mapped = true;
+        Ok(())
+    }
+
+    /// Unmaps the memory, making it available to kernels.
+    ///
+    /// The unmap command is enqueued but not waited upon. Commands
+    /// subsequently enqueued on the same (in-order) queue will not begin
+    /// until it has completed. Does nothing if not mapped.
+    pub fn unmap(&mut self) -> OclResult<()> {
+        if !self.mapped { return Ok(()); }
+
+        unsafe {
+            core::enqueue_svm_unmap(self.queue().as_core(), self.alloc.ptr as *mut T,
+                None::<&EventList>, None::<&mut Event>, Some(&self.queue().device_version()))?;
+        }
+
+        self.mapped = false;
+        Ok(())
+    }
+
+    /// Copies every element from `src`, blocking until complete.
+    ///
+    /// Coarse-grained memory must not be mapped while copying.
+    pub fn copy_from_slice(&mut self, src: &[T]) -> OclResult<()> {
+        if src.len() != self.len {
+            return Err(SvmError::LenMismatch { expected: self.len, found: src.len() }.into());
+        }
+
+        unsafe {
+            core::enqueue_svm_memcpy(self.queue().as_core(), true, self.alloc.ptr as *mut T,
+                src.as_ptr(), self.len, None::<&EventList>, None::<&mut Event>,
+                Some(&self.queue().device_version()))?;
+        }
+        Ok(())
+    }
+
+    /// Copies every element into `dst`, blocking until complete.
+    ///
+    /// Coarse-grained memory must not be mapped while copying.
+    pub fn copy_to_slice(&self, dst: &mut [T]) -> OclResult<()> {
+        if dst.len() != self.len {
+            return Err(SvmError::LenMismatch { expected: self.len, found: dst.len() }.into());
+        }
+
+        unsafe {
+            core::enqueue_svm_memcpy(self.queue().as_core(), true, dst.as_mut_ptr(),
+                self.as_ptr(), self.len, None::<&EventList>, None::<&mut Event>,
+                Some(&self.queue().device_version()))?;
+        }
+        Ok(())
+    }
+
+    /// Returns true if this memory can currently be accessed by the host
+    /// (i.e. it is either fine-grained or mapped).
+    #[inline]
+    pub fn is_host_accessible(&self) -> bool {
+        self.mapped || self.is_fine_grained()
+    }
+
+    /// Returns true if this memory was allocated as a fine-grained buffer.
+    #[inline]
+    pub fn is_fine_grained(&self) -> bool {
+        self.flags.contains(SvmMemFlags::FINE_GRAIN_BUFFER)
+    }
+
+    /// Returns true if this memory is currently mapped.
+    #[inline]
+    pub fn is_mapped(&self) -> bool {
+        self.mapped
+    }
+
+    /// Returns the length (number of elements).
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the flags used to allocate this memory.
+    #[inline]
+    pub fn flags(&self) -> SvmMemFlags {
+        self.flags
+    }
+
+    /// Returns the queue used by this `SvmVec`.
+    #[inline]
+    pub fn queue(&self) -> &Queue {
+        &self.alloc.queue
+    }
+
+    /// Returns a pointer to the first element.
+    ///
+    /// The pointer may be stored within other SVM allocations and used
+    /// from within kernels.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self.alloc.ptr as *const T
+    }
+
+    /// Returns a mutable pointer to the first element.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.alloc.ptr as *mut T
+    }
+
+    /// Returns a reference to the underlying allocation.
+    pub(crate) fn alloc(&self) -> &Arc<SvmAlloc> {
+        &self.alloc
+    }
+
+    /// Panics if this memory is not currently accessible to the host.
+    fn assert_host_accessible(&self) {
+        assert!(self.is_host_accessible(), "SvmVec: Coarse-grained memory must be mapped \
+            (see `SvmVec::map`) before it can be accessed by the host.");
+    }
+}
+
+impl<T: OclPrm> Deref for SvmVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.assert_host_accessible();
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+impl<T: OclPrm> DerefMut for SvmVec<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.assert_host_accessible();
+        unsafe { slice::from_raw_parts_mut(self.alloc.ptr as *mut T, self.len) }
+    }
+}
+
+impl<T: OclPrm> Drop for SvmVec<T> {
+    /// Unmaps the memory if mapped. The allocation is freed once it is no
+    /// longer referenced by any kernel.
+    fn drop(&mut self) {
+        self.unmap().expect("error dropping `SvmVec`");
+    }
+}
@@ -0,0 +1,299 @@
+//! Command timeline traces.
+
+use std::fmt;
+use std::slice;
+use std::io::Write;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use core::{ProfilingInfo, CommandQueue as CommandQueueCore, ClWaitListPtr};
+use error::Result as OclResult;
+use standard::{Event, Device, ClWaitListPtrEnum, ProfileKey};
+
+
+/// A recorded command.
+#[derive(Clone)]
+struct Record {
+    label: String,
+    queue: CommandQueueCore,
+    device: Option<Device>,
+    event: Event,
+    /// The events (by pointer) which the command waited on.
+    deps: Vec<usize>,
+}
+
+
+/// The profiling times of a completed command, in nanoseconds.
+struct Times {
+    queued: u64,
+    submit: u64,
+    start: u64,
+    end: u64,
+}
+
+impl Times {
+    /// Blocks until `event` completes and returns its profiling times.
+    fn resolve(event: &Event) -> OclResult<Times> {
+        event.wait_for()?;
+        let time = |info| event.profiling_info(info).and_then(|res| res.time());
+
+        Ok(Times {
+            queued: time(ProfilingInfo::Queued)?,
+            submit: time(ProfilingInfo::Submit)?,
+            start: time(ProfilingInfo::Start)?,
+            end: time(ProfilingInfo::End)?,
+        })
+    }
+}
+
+
+/// Returns the key of each event (its pointer) in `ewait`.
+fn wait_list_keys(ewait: &ClWaitListPtrEnum) -> Vec<usize> {
+    let count = ewait.count() as usize;
+    if count == 0 { return Vec::new(); }
+
+    unsafe { slice::from_raw_parts(ewait.as_ptr_ptr(), count) }.iter()
+        .filter(|ptr| !ptr.is_null())
+        .map(|&ptr| ptr as usize)
+        .collect()
+}
+
+
+/// Returns `s` as a quoted JSON string.
+fn json_str(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+
+/// Returns `nanos` as a JSON number of microseconds.
+fn json_micros(nanos: u64) -> String {
+    format!("{}.{:03}", nanos / 1000, nanos % 1000)
+}
+
+
+/// Records enqueued commands for export as a timeline.
+///
+/// Attach to a queue using `QueueBuilder::trace_recorder` or
+/// `Queue::set_trace_recorder` to record every kernel, buffer, and image
+/// command enqueued using that queue (the kernel's default queue for kernel
+/// commands). Each command's event is recorded together with the queue it
+/// was enqueued on, that queue's device, its label (see `KernelCmd::label`,
+/// etc., defaulting to its `ProfileKey`, such as `"kernel:add"` or
+/// `"buffer:read"`), and the events it waited on (`::ewait`). Queues used must be created with profiling enabled
+/// (`CommandQueueProperties::PROFILING_ENABLE`, implied by
+/// `QueueBuilder::trace_recorder`).
+///
+/// Recorded events are retained until `::clear` is called.
+///
+/// Clones share the same records.
+///
+/// ## Example
+///
+/// ```rust,ignore
+/// let recorder = TraceRecorder::new();
+/// let queue = Queue::builder().trace_recorder(recorder.clone()).build(&context)?;
+/// // ... enqueue commands ...
+/// recorder.write_chrome_trace(File::create("trace.json")?)?;
+/// ```
+///
+#[derive(Clone)]
+pub struct TraceRecorder {
+    records: Arc<Mutex<Vec<Record>>>,
+}
+
+impl TraceRecorder {
+    /// Returns a new, empty, `TraceRecorder`.
+    pub fn new() -> TraceRecorder {
+        TraceRecorder {
+            records: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<Vec<Record>> {
+        match self.records.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// Records the command associated with `event`, enqueued on `queue`,
+    /// under `label`.
+    pub fn record<Q>(&self, label: &str, queue: &Q, event: &Event)
+            where Q: AsRef<CommandQueueCore> {
+        self.record_command(label.to_owned(), queue.as_ref(), event, Vec::new())
+    }
+
+    /// Records a command along with the keys of the events it waited on.
+    fn record_command(&self, label: String, queue: &CommandQueueCore, event: &Event,
+            deps: Vec<usize>) {
+        let record = Record {
+            label: label,
+            queue: queue.clone(),
+            device: queue.device().ok().map(Device::from),
+            event: event.clone(),
+            deps: deps,
+        };
+        self.lock().push(record);
+    }
+
+    /// Returns the number of commands recorded.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns true if no commands have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Discards all recorded commands.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Writes the recorded commands as a Chrome trace (JSON), viewable using
+    /// `chrome://tracing` or Perfetto.
+    ///
+    /// Each device is shown as a process and each queue as a thread (track)
+    /// within it, containing a span for the time each command was running.
+    /// The time each command spent queued (on the host) and submitted (to
+    /// the device) are shown as async spans. Flow arrows connect each
+    /// command to the recorded commands it waited on.
+    ///
+    /// Times are relative to the earliest recorded command. Blocks until all
+    /// recorded commands have completed. Commands which fail, or for which
+    /// profiling info is unavailable, are omitted.
+    pub fn write_chrome_trace<W: Write>(&self, mut writer: W) -> OclResult<()> {
+        let records = self.lock().clone();
+
+        let commands: Vec<(Record, Times)> = records.into_iter()
+            .filter_map(|record| Times::resolve(&record.event).ok().map(|times| (record, times)))
+            .collect();
+
+        let t0 = commands.iter().map(|&(_, ref times)| times.queued).min().unwrap_or(0);
+        let ts = |nanos: u64| json_micros(nanos.saturating_sub(t0));
+
+        let mut entries = Vec::new();
+        let mut devices: Vec<Option<Device>> = Vec::new();
+        let mut queues: Vec<usize> = Vec::new();
+        let mut tracks = Vec::with_capacity(commands.len());
+        let mut command_idxs = HashMap::with_capacity(commands.len());
+
+        for (idx, &(ref record, _)) in commands.iter().enumerate() {
+            let pid = match devices.iter().position(|&d| d == record.device) {
+                Some(pid) => pid,
+                None => {
+                    let name = record.device.and_then(|d| d.name().ok())
+                        .unwrap_or_else(|| "Unknown device".to_owned());
+                    entries.push(format!("{{\"name\":\"process_name\",\"ph\":\"M\",\"pid\":{},\
+                        \"args\":{{\"name\":{}}}}}", devices.len(), json_str(&name)));
+                    devices.push(record.device);
+                    devices.len() - 1
+                },
+            };
+
+            let queue_key = record.queue.as_ptr() as usize;
+            let tid = match queues.iter().position(|&q| q == queue_key) {
+                Some(tid) => tid,
+                None => {
+                    entries.push(format!("{{\"name\":\"thread_name\",\"ph\":\"M\",\"pid\":{},\
+                        \"tid\":{},\"args\":{{\"name\":\"Queue {}\"}}}}", pid, queues.len(),
+                        queues.len()));
+                    queues.push(queue_key);
+                    queues.len() - 1
+                },
+            };
+
+            tracks.push((pid, tid));
+            command_idxs.insert(unsafe { *record.event.as_core().as_ptr_ref() as usize }, idx);
+        }
+
+        let mut flow_id = 0;
+
+        for (idx, &(ref record, ref times)) in commands.iter().enumerate() {
+            let (pid, tid) = tracks[idx];
+            let name = json_str(&record.label);
+
+            for &(cat, begin, end) in [("queued", times.queued, times.submit),
+                    ("submitted", times.submit, times.start)].iter() {
+                entries.push(format!("{{\"name\":{},\"cat\":\"{}\",\"ph\":\"b\",\"id\":{},\
+                    \"ts\":{},\"pid\":{},\"tid\":{}}}", name, cat, idx, ts(begin), pid, tid));
+                entries.push(format!("{{\"name\":{},\"cat\":\"{}\",\"ph\":\"e\",\"id\":{},\
+                    \"ts\":{},\"pid\":{},\"tid\":{}}}", name, cat, idx, ts(end), pid, tid));
+            }
+
+            entries.push(format!("{{\"name\":{},\"cat\":\"running\",\"ph\":\"X\",\"ts\":{},\
+                \"dur\":{},\"pid\":{},\"tid\":{}}}", name, ts(times.start),
+                json_micros(times.end.saturating_sub(times.start)), pid, tid));
+
+            // Only commands recorded earlier can be dependencies (a later
+            // command may reuse the pointer of an unrecorded, released event):
+            for dep in record.deps.iter() {
+                let dep_idx = match command_idxs.get(dep) {
+                    Some(&dep_idx) if dep_idx < idx => dep_idx,
+                    _ => continue,
+                };
+                let (dep_pid, dep_tid) = tracks[dep_idx];
+
+                entries.push(format!("{{\"name\":\"ewait\",\"cat\":\"dependency\",\"ph\":\"s\",\
+                    \"id\":{},\"ts\":{},\"pid\":{},\"tid\":{}}}", flow_id,
+                    ts(commands[dep_idx].1.end), dep_pid, dep_tid));
+                entries.push(format!("{{\"name\":\"ewait\",\"cat\":\"dependency\",\"ph\":\"f\",\
+                    \"bp\":\"e\",\"id\":{},\"ts\":{},\"pid\":{},\"tid\":{}}}", flow_id,
+                    ts(times.start), pid, tid));
+                flow_id += 1;
+            }
+        }
+
+        writeln!(writer, "{{\"traceEvents\":[")?;
+        writeln!(writer, "{}", entries.join(",\n"))?;
+        writeln!(writer, "],\"displayTimeUnit\":\"ns\"}}")?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for TraceRecorder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("TraceRecorder")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+
+/// A command to be recorded by a `TraceRecorder` once enqueued.
+pub(crate) struct TraceCmd<'r> {
+    recorder: &'r TraceRecorder,
+    queue: &'r CommandQueueCore,
+    label: Option<String>,
+    deps: Vec<usize>,
+}
+
+impl<'r> TraceCmd<'r> {
+    /// Returns a new `TraceCmd`. Must be created before `ewait` is consumed.
+    pub(crate) fn new(recorder: &'r TraceRecorder, queue: &'r CommandQueueCore,
+            label: Option<String>, ewait: Option<&ClWaitListPtrEnum>) -> TraceCmd<'r> {
+        TraceCmd {
+            recorder: recorder,
+            queue: queue,
+            label: label,
+            deps: ewait.map(wait_list_keys).unwrap_or_default(),
+        }
+    }
+
+    /// Records the command, labeled with `key` unless a label was specified.
+    pub(crate) fn record(self, key: &ProfileKey, event: &Event) {
+        let label = self.label.unwrap_or_else(|| key.to_string());
+        self.recorder.record_command(label, self.queue, event, self.deps);
+    }
+}
@@ -0,0 +1,46 @@
+//! Tests programs created from built-in kernels.
+
+use core::OpenclVersion;
+use standard::{Platform, Device, Context, Program, Kernel};
+
+#[test]
+fn built_in_kernels() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if device.version().unwrap() < OpenclVersion::new(1, 2) { continue; }
+
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+
+            // Names not advertised by the device are rejected:
+            assert!(Program::builder()
+                .devices(device)
+                .built_in_kernel("__not_a_built_in_kernel__")
+                .build(&context).is_err());
+
+            // Built-in kernels can not be mixed with source:
+            assert!(Program::builder()
+                .devices(device)
+                .built_in_kernel("__not_a_built_in_kernel__")
+                .src("__kernel void k() {}")
+                .build(&context).is_err());
+
+            let names = device.built_in_kernels().unwrap();
+
+            if names.is_empty() { continue; }
+
+            let mut builder = Program::builder().devices(device);
+            for name in names.iter() {
+                builder = builder.built_in_kernel(name.clone());
+            }
+            let program = builder.build(&context).unwrap();
+
+            for name in names.iter() {
+                let kernel = Kernel::new(name.clone(), &program).unwrap();
+                assert_eq!(&kernel.name().unwrap(), name);
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Tests that the Rust types `ocl-derive` chooses for kernel parameters are
+//! those accepted by `ArgType::is_match`.
+
+use std::collections::HashMap;
+use standard::ArgType;
+use prm::{Char2, Char3, Char4, Char8, Char16, Uchar2, Uchar3, Uchar4, Uchar8, Uchar16,
+    Short2, Short3, Short4, Short8, Short16, Ushort2, Ushort3, Ushort4, Ushort8, Ushort16,
+    Int2, Int3, Int4, Int8, Int16, Uint2, Uint3, Uint4, Uint8, Uint16,
+    Long2, Long3, Long4, Long8, Long16, Ulong2, Ulong3, Ulong4, Ulong8, Ulong16,
+    Float2, Float3, Float4, Float8, Float16, Double2, Double3, Double4, Double8, Double16};
+
+mod prm_types {
+    #![allow(dead_code)]
+    include!("../../ocl-derive/src/prm_types.rs");
+}
+
+/// Maps the path of each type (as generated by `prm_type`) to
+/// `ArgType::is_match` for that type.
+macro_rules! matchers {
+    ( $($scl:ident),* ; $($vec:ident),* ) => {{
+        let mut matchers: HashMap<String, fn(&ArgType) -> bool> = HashMap::new();
+        $( matchers.insert(stringify!($scl).to_owned(), ArgType::is_match::<$scl>); )*
+        $( matchers.insert(format!("::ocl::prm::{}", stringify!($vec)),
+            ArgType::is_match::<$vec>); )*
+        matchers
+    }};
+}
+
+#[test]
+fn derive_prm_types() {
+    let matchers = matchers!(i8, u8, i16, u16, i32, u32, i64, u64, f32, f64;
+        Char2, Char3, Char4, Char8, Char16, Uchar2, Uchar3, Uchar4, Uchar8, Uchar16,
+        Short2, Short3, Short4, Short8, Short16, Ushort2, Ushort3, Ushort4, Ushort8, Ushort16,
+        Int2, Int3, Int4, Int8, Int16, Uint2, Uint3, Uint4, Uint8, Uint16,
+        Long2, Long3, Long4, Long8, Long16, Ulong2, Ulong3, Ulong4, Ulong8, Ulong16,
+        Float2, Float3, Float4, Float8, Float16, Double2, Double3, Double4, Double8, Double16);
+
+    let mut type_names = Vec::new();
+    for &(base, _, _) in prm_types::PRM_BASE_TYPES.iter() {
+        type_names.push(base.to_owned());
+        for card in prm_types::PRM_VECTOR_CARDINALITIES.iter() {
+            type_names.push(format!("{}{}", base, card));
+        }
+    }
+    assert_eq!(type_names.len(), matchers.len());
+
+    for type_name in type_names.iter() {
+        let ty = prm_types::prm_type(type_name).unwrap();
+        let is_match = matchers.get(&ty).unwrap_or_else(|| panic!("No Rust type '{}' \
+            (for '{}').", ty, type_name));
+
+        for arg_type_name in &[type_name.clone(), format!("{}*", type_name)] {
+            let arg_type = ArgType::from_str(arg_type_name).unwrap();
+            assert!(is_match(&arg_type), "'{}' does not match '{}'.", ty, arg_type_name);
+        }
+    }
+}
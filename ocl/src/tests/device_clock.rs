@@ -0,0 +1,77 @@
+//! Tests host/device clock correlation with `DeviceClock`.
+
+use std::thread;
+use std::time::{Duration, Instant};
+use core::ProfilingInfo;
+use standard::{Platform, Device, Context, Queue, Program, Kernel, Buffer, Event, DeviceClock};
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+"#;
+
+const WORK_SIZE: usize = 1 << 16;
+
+/// Generous allowance for the inaccuracy of marker-based calibration.
+const TOLERANCE_MS: u64 = 50;
+
+#[test]
+fn device_clock() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+
+            let queue = Queue::builder()
+                .device(device)
+                .profiling()
+                .build(&context).unwrap();
+
+            let program = Program::builder()
+                .devices(device)
+                .src(SRC)
+                .build(&context).unwrap();
+
+            let buffer = Buffer::<f32>::builder()
+                .queue(queue.clone())
+                .len(WORK_SIZE)
+                .fill_val(0.0f32)
+                .build().unwrap();
+
+            let kernel = Kernel::new("add", &program).unwrap()
+                .queue(queue.clone())
+                .gws(WORK_SIZE)
+                .arg_buf(&buffer)
+                .arg_scl(1.0f32);
+
+            let mut clock = DeviceClock::new(&queue).unwrap();
+
+            let tolerance = Duration::from_millis(TOLERANCE_MS);
+            let before = Instant::now();
+            let mut event = Event::empty();
+            unsafe { kernel.cmd().enew(&mut event).enq().unwrap(); }
+            event.wait_for().unwrap();
+            let after = Instant::now();
+
+            let queued = event.profiling_instant(ProfilingInfo::Queued, &clock).unwrap();
+            let start = event.profiling_instant(ProfilingInfo::Start, &clock).unwrap();
+            let end = event.profiling_instant(ProfilingInfo::End, &clock).unwrap();
+
+            assert!(queued <= start && start <= end);
+            assert!(queued + tolerance >= before);
+            assert!(end <= after + tolerance);
+
+            let start_sys = event.profiling_system_time(ProfilingInfo::Start, &clock).unwrap();
+            let end_sys = event.profiling_system_time(ProfilingInfo::End, &clock).unwrap();
+            assert!(start_sys <= end_sys);
+
+            // Allow enough time to pass for drift to be measured meaningfully:
+            thread::sleep(Duration::from_millis(100));
+            clock.recalibrate().unwrap();
+            assert!(clock.drift() > 0.9 && clock.drift() < 1.1);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+//! Tests that errors can be matched on by kind.
+
+use std::error::Error as StdError;
+use core::OpenclVersion;
+use core::error::{ErrorKind as OclCoreErrorKind, ProgramBuildError};
+use error::{ErrorKind, KernelError, ProQueError, SpatialDimsError};
+use standard::{Platform, Device, ProQue, SpatialDims, Kernel};
+use ::BufferCmdError;
+
+const WORK_SIZE: usize = 1 << 10;
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+"#;
+
+#[test]
+fn kernel_errors() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            // Argument types are unavailable below OpenCL 1.2:
+            if device.version().unwrap() < OpenclVersion::new(1, 2) { continue; }
+
+            let pro_que = ProQue::builder()
+                .platform(platform)
+                .device(device)
+                .src(SRC)
+                .dims(WORK_SIZE)
+                .build().unwrap();
+
+            let buffer = pro_que.create_buffer::<f32>().unwrap();
+            let kernel = pro_que.create_kernel("add").unwrap()
+                .arg_buf(&buffer)
+                .arg_scl(5.0f32);
+
+            match *kernel.verify_arg_type::<i32>(1).unwrap_err().kind() {
+                ErrorKind::Kernel(KernelError::ArgTypeMismatch { ref kernel, arg_index,
+                        ref type_name, .. }) => {
+                    assert_eq!(kernel, "add");
+                    assert_eq!(arg_index, 1);
+                    assert_eq!(type_name, "float");
+                },
+                ref kind => panic!("Unexpected error kind: {:?}", kind),
+            }
+
+            match *kernel.verify_arg_type::<f32>(2).unwrap_err().kind() {
+                ErrorKind::Kernel(KernelError::ArgIndexOutOfRange { ref kernel, arg_index }) => {
+                    assert_eq!(kernel, "add");
+                    assert_eq!(arg_index, 2);
+                },
+                ref kind => panic!("Unexpected error kind: {:?}", kind),
+            }
+
+            // A kernel with no queue:
+            let kernel = Kernel::new("add", pro_que.program()).unwrap()
+                .gws(WORK_SIZE)
+                .arg_buf(&buffer)
+                .arg_scl(5.0f32);
+
+            match *unsafe { kernel.enq() }.unwrap_err().kind() {
+                ErrorKind::Kernel(KernelError::NoQueue { ref kernel }) => {
+                    assert_eq!(kernel, "add");
+                },
+                ref kind => panic!("Unexpected error kind: {:?}", kind),
+            }
+        }
+    }
+}
+
+#[test]
+fn buffer_cmd_errors() {
+    let pro_que = ProQue::builder()
+        .src(SRC)
+        .dims(WORK_SIZE)
+        .build().unwrap();
+
+    let buffer = pro_que.create_buffer::<f32>().unwrap();
+    let mut vec = vec![0.0f32; WORK_SIZE];
+
+    match *buffer.read(&mut vec).offset(WORK_SIZE).enq().unwrap_err().kind() {
+        ErrorKind::BufferCmd(BufferCmdError::OffsetOutOfRange { mem_len, offset, .. }) => {
+            assert_eq!(mem_len, WORK_SIZE);
+            assert_eq!(offset, WORK_SIZE);
+        },
+        ref kind => panic!("Unexpected error kind: {:?}", kind),
+    }
+
+    match *buffer.read(&mut vec).len(WORK_SIZE * 2).enq().unwrap_err().kind() {
+        ErrorKind::BufferCmd(BufferCmdError::DataRangeOutOfBounds { range_end, data_len }) => {
+            assert_eq!(range_end, WORK_SIZE * 2);
+            assert_eq!(data_len, WORK_SIZE);
+        },
+        ref kind => panic!("Unexpected error kind: {:?}", kind),
+    }
+}
+
+#[test]
+fn program_build_error() {
+    let err = ProQue::builder()
+        .src("__kernel void broken(__global float* buffer) { not_a_variable; }")
+        .dims(WORK_SIZE)
+        .build().unwrap_err();
+
+    match *err.kind() {
+        ErrorKind::OclCore(ref core_err) => match *core_err.kind() {
+            OclCoreErrorKind::ProgramBuild(ProgramBuildError::DeviceBuildLogs(ref logs)) => {
+                assert!(!logs.0.is_empty());
+                assert!(logs.0.iter().all(|&(_, ref log)| !log.is_empty()));
+            },
+            ref kind => panic!("Unexpected error kind: {:?}", kind),
+        },
+        ref kind => panic!("Unexpected error kind: {:?}", kind),
+    }
+
+    assert!(err.source().is_none());
+}
+
+#[test]
+fn builder_errors() {
+    match *ProQue::builder().dims(WORK_SIZE).build().unwrap_err().kind() {
+        ErrorKind::ProQue(ProQueError::NoProgramSource) => (),
+        ref kind => panic!("Unexpected error kind: {:?}", kind),
+    }
+
+    match *SpatialDims::new(None, Some(1), None).unwrap_err().kind() {
+        ErrorKind::SpatialDims(SpatialDimsError::OutOfOrder) => (),
+        ref kind => panic!("Unexpected error kind: {:?}", kind),
+    }
+}
@@ -0,0 +1,61 @@
+//! Tests closure-based event callbacks.
+
+use std::sync::mpsc;
+use std::time::Duration;
+use core::{CommandExecutionStatus, ClEventPtrRef};
+use core::error::{ErrorKind as OclCoreErrorKind, ApiWrapperError};
+use error::ErrorKind;
+use ffi;
+use standard::{Context, Event, EventList};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+#[test]
+fn event_on_complete() {
+    let context = Context::builder().build().unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    let event = Event::user(&context).unwrap();
+    event.on_complete(move |res| tx.send(res).unwrap()).unwrap();
+    assert!(rx.try_recv().is_err());
+
+    event.set_complete().unwrap();
+    assert!(rx.recv_timeout(TIMEOUT).unwrap().is_ok());
+
+    // Negative statuses are reported as errors:
+    let (tx, rx) = mpsc::channel();
+    let event = Event::user(&context).unwrap();
+    event.on_status(CommandExecutionStatus::Complete, move |res| tx.send(res).unwrap()).unwrap();
+    unsafe { ffi::clSetUserEventStatus(*event.as_ptr_ref(), -1); }
+
+    match *rx.recv_timeout(TIMEOUT).unwrap().unwrap_err().kind() {
+        ErrorKind::OclCore(ref err) => match *err.kind() {
+            OclCoreErrorKind::ApiWrapper(ApiWrapperError::EventTerminated(-1)) => (),
+            ref kind => panic!("Unexpected error kind: {:?}", kind),
+        },
+        ref kind => panic!("Unexpected error kind: {:?}", kind),
+    }
+}
+
+#[test]
+fn event_list_on_all_complete() {
+    let context = Context::builder().build().unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    let events: Vec<Event> = (0..3).map(|_| Event::user(&context).unwrap()).collect();
+    let event_list = EventList::from(events.clone());
+    event_list.on_all_complete(move |res| tx.send(res).unwrap()).unwrap();
+
+    for event in &events[..2] {
+        event.set_complete().unwrap();
+    }
+    assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+
+    events[2].set_complete().unwrap();
+    assert!(rx.recv_timeout(TIMEOUT).unwrap().is_ok());
+
+    // Empty lists complete immediately:
+    let (tx, rx) = mpsc::channel();
+    EventList::new().on_all_complete(move |res| tx.send(res).unwrap()).unwrap();
+    assert!(rx.try_recv().unwrap().is_ok());
+}
@@ -0,0 +1,94 @@
+//! Tests that `EventReactor` registers each event once and wakes its tasks.
+
+use std::thread;
+use std::time::Duration;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use futures::{future, Async, Poll};
+use futures::executor::{self, Notify};
+use core::ClEventPtrRef;
+use error::Error as OclError;
+use ffi;
+use standard::{Context, Event, EventReactor, ReactorMode};
+
+const POLL_COUNT: usize = 100;
+
+/// Counts notifications.
+struct CountNotify(AtomicUsize);
+
+impl Notify for CountNotify {
+    fn notify(&self, _id: usize) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// Registers a task many times on a single user event then completes it.
+fn register_and_complete(mode: ReactorMode) {
+    let context = Context::builder().build().unwrap();
+    let event = Event::user(&context).unwrap();
+    let reactor = EventReactor::new(mode);
+    let notify = Arc::new(CountNotify(AtomicUsize::new(0)));
+
+    let mut task = executor::spawn(future::poll_fn(|| -> Poll<(), OclError> {
+        reactor.register(&event)?;
+        Ok(Async::NotReady)
+    }));
+
+    for _ in 0..POLL_COUNT {
+        assert_eq!(task.poll_future_notify(&notify, 0).unwrap(), Async::NotReady);
+    }
+    assert_eq!(reactor.pending_count(), 1);
+    assert_eq!(notify.0.load(Ordering::SeqCst), 0);
+
+    event.set_complete().unwrap();
+
+    for _ in 0..1000 {
+        if reactor.pending_count() == 0 && notify.0.load(Ordering::SeqCst) != 0 { break; }
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    assert_eq!(reactor.pending_count(), 0);
+    assert_eq!(notify.0.load(Ordering::SeqCst), 1);
+}
+
+/// Polls a task waiting on a user event then terminates the event
+/// abnormally. The woken task must resolve to an error rather than register
+/// the event again.
+fn register_and_terminate(mode: ReactorMode) {
+    let context = Context::builder().build().unwrap();
+    let event = Event::user(&context).unwrap();
+    let reactor = EventReactor::new(mode);
+    let notify = Arc::new(CountNotify(AtomicUsize::new(0)));
+
+    let mut task = executor::spawn(future::poll_fn(|| -> Poll<(), OclError> {
+        if event.poll_complete()? { return Ok(Async::Ready(())); }
+        reactor.register(&event)?;
+        Ok(Async::NotReady)
+    }));
+
+    assert_eq!(task.poll_future_notify(&notify, 0).unwrap(), Async::NotReady);
+    assert_eq!(reactor.pending_count(), 1);
+
+    unsafe { ffi::clSetUserEventStatus(*event.as_ptr_ref(), -1); }
+
+    for _ in 0..1000 {
+        if reactor.pending_count() == 0 && notify.0.load(Ordering::SeqCst) != 0 { break; }
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    assert_eq!(notify.0.load(Ordering::SeqCst), 1);
+    assert!(task.poll_future_notify(&notify, 0).is_err());
+    assert_eq!(reactor.pending_count(), 0);
+}
+
+#[test]
+fn event_reactor_callback() {
+    register_and_complete(ReactorMode::Callback);
+    register_and_terminate(ReactorMode::Callback);
+}
+
+#[test]
+fn event_reactor_poll() {
+    register_and_complete(ReactorMode::Poll(Duration::from_millis(1)));
+    register_and_terminate(ReactorMode::Poll(Duration::from_millis(1)));
+}
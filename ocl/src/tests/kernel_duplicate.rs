@@ -0,0 +1,94 @@
+//! Tests kernels duplicated with `Kernel::duplicate`.
+
+use std::thread;
+use core::{SamplerInfo, SamplerInfoResult};
+use standard::{ProQue, Sampler};
+
+const WORK_SIZE: usize = 1 << 12;
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+
+    __kernel void add_sampled(sampler_t sampler, __global float* buffer) {
+        buffer[get_global_id(0)] += 1.0f;
+    }
+"#;
+
+#[test]
+fn kernel_duplicate() {
+    let pro_que = ProQue::builder()
+        .src(SRC)
+        .dims(WORK_SIZE)
+        .build().unwrap();
+
+    let buffer_a = pro_que.create_buffer::<f32>().unwrap();
+    let buffer_b = pro_que.create_buffer::<f32>().unwrap();
+
+    let kernel = pro_que.create_kernel("add").unwrap()
+        .arg_buf_named("buffer", Some(&buffer_a))
+        .arg_scl_named("addend", Some(1.0f32));
+
+    // The duplicate starts with the same arguments:
+    let mut dup = kernel.duplicate().unwrap();
+    assert!(dup.as_core().as_ptr() != kernel.as_core().as_ptr());
+
+    // ...which can be changed without affecting the original:
+    dup.set_arg_buf_named("buffer", Some(&buffer_b)).unwrap();
+    dup.set_arg_scl_named("addend", 2.0f32).unwrap();
+
+    let handles: Vec<_> = vec![kernel.clone(), dup].into_iter().map(|kernel| {
+        thread::spawn(move || {
+            for _ in 0..10 {
+                unsafe { kernel.enq().unwrap(); }
+            }
+            kernel.default_queue().unwrap().finish().unwrap();
+        })
+    }).collect();
+
+    for handle in handles { handle.join().unwrap(); }
+
+    let mut vec = vec![0.0f32; WORK_SIZE];
+    buffer_a.read(&mut vec).enq().unwrap();
+    assert!(vec.iter().all(|&v| v == 10.0f32));
+    buffer_b.read(&mut vec).enq().unwrap();
+    assert!(vec.iter().all(|&v| v == 20.0f32));
+
+    // Duplicating a duplicate carries over the original arguments:
+    let dup = kernel.duplicate().unwrap().duplicate().unwrap();
+    unsafe { dup.enq().unwrap(); }
+
+    buffer_a.read(&mut vec).enq().unwrap();
+    assert!(vec.iter().all(|&v| v == 11.0f32));
+}
+
+#[test]
+fn kernel_duplicate_sampler() {
+    let pro_que = ProQue::builder()
+        .src(SRC)
+        .dims(WORK_SIZE)
+        .build().unwrap();
+
+    let buffer = pro_que.create_buffer::<f32>().unwrap();
+    let sampler = Sampler::with_defaults(pro_que.context()).unwrap();
+
+    let kernel = pro_que.create_kernel("add_sampled").unwrap()
+        .arg_smp(&sampler)
+        .arg_buf(&buffer);
+
+    // The kernel retains the sampler for as long as it is recorded:
+    match sampler.info(SamplerInfo::ReferenceCount).unwrap() {
+        SamplerInfoResult::ReferenceCount(count) => assert_eq!(count, 2),
+        res => panic!("Unexpected sampler info: {:?}", res),
+    }
+
+    // ...so it can still be replayed onto a duplicate once dropped:
+    drop(sampler);
+    let dup = kernel.duplicate().unwrap();
+    unsafe { dup.enq().unwrap(); }
+
+    let mut vec = vec![0.0f32; WORK_SIZE];
+    buffer.read(&mut vec).enq().unwrap();
+    assert!(vec.iter().all(|&v| v == 1.0f32));
+}
@@ -0,0 +1,55 @@
+//! Tests kernel arguments set by name using `Kernel::set_arg`.
+
+use standard::{ProQue, ArgVal};
+use error::{ErrorKind, KernelError};
+use prm::Float2;
+
+const WORK_SIZE: usize = 1 << 10;
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend, float2 scale,
+            __local float* scratch) {
+        uint idx = get_global_id(0);
+        scratch[get_local_id(0)] = addend * scale.x * scale.y;
+        buffer[idx] += scratch[get_local_id(0)];
+    }
+"#;
+
+#[test]
+fn kernel_set_arg() {
+    let pro_que = ProQue::builder()
+        .src(SRC)
+        .dims(WORK_SIZE)
+        .build().unwrap();
+
+    let buffer = pro_que.create_buffer::<f32>().unwrap();
+    let mut kernel = pro_que.create_kernel("add").unwrap().lws(64);
+
+    // Argument names are unavailable on OpenCL 1.1:
+    if kernel.arg_names().is_empty() { return; }
+    assert_eq!(kernel.arg_names(), &["buffer", "addend", "scale", "scratch"]);
+
+    // Names need not be static or registered beforehand:
+    let names: Vec<String> = kernel.arg_names().to_vec();
+    kernel.set_arg(names[0].clone(), &buffer).unwrap();
+    kernel.set_arg(&names[1], 2.0f32).unwrap();
+    kernel.set_arg("scale", ArgVal::Vector(Float2::new(1.0, 3.0))).unwrap();
+    kernel.set_arg("scratch", ArgVal::Local::<f32>(64)).unwrap();
+    assert_eq!(kernel.arg_idx("scale").unwrap(), 2);
+
+    unsafe { kernel.enq().unwrap(); }
+
+    let mut vec = vec![0.0f32; WORK_SIZE];
+    buffer.read(&mut vec).enq().unwrap();
+    assert!(vec.iter().all(|&v| v == 6.0f32));
+
+    // Types are still verified:
+    assert!(kernel.set_arg("addend", 2u32).is_err());
+
+    match *kernel.set_arg("nonexistent", 1.0f32).unwrap_err().kind() {
+        ErrorKind::Kernel(KernelError::ArgNameNotFound { ref name, .. }) => {
+            assert_eq!(name, "nonexistent")
+        },
+        ref kind => panic!("Unexpected error: {:?}", kind),
+    }
+}
@@ -0,0 +1,72 @@
+//! Tests memory object destructor callbacks.
+
+use std::sync::mpsc;
+use std::time::Duration;
+use standard::{Platform, Device, Context, Queue, Buffer, Image};
+use core::{ImageChannelOrder, ImageChannelDataType, MemObjectType};
+
+#[test]
+fn buffer_on_release() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+            let queue = Queue::new(&context, device, None).unwrap();
+
+            let buffer = Buffer::<u32>::builder()
+                .queue(queue.clone())
+                .len(1 << 10)
+                .fill_val(7)
+                .build().unwrap();
+
+            let (tx, rx) = mpsc::channel();
+            let tx_first = tx.clone();
+
+            buffer.on_release(move || tx_first.send("first").unwrap()).unwrap();
+            buffer.on_release(move || tx.send("second").unwrap()).unwrap();
+
+            // Not called while a clone is still alive:
+            let buffer_clone = buffer.clone();
+            drop(buffer);
+            queue.finish().unwrap();
+            assert!(rx.try_recv().is_err());
+
+            drop(buffer_clone);
+            queue.finish().unwrap();
+
+            // Called in reverse order of registration:
+            assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), "second");
+            assert_eq!(rx.recv_timeout(Duration::from_secs(5)).unwrap(), "first");
+        }
+    }
+}
+
+#[test]
+fn image_on_release() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+            let queue = Queue::new(&context, device, None).unwrap();
+
+            let image = Image::<u8>::builder()
+                .channel_order(ImageChannelOrder::Rgba)
+                .channel_data_type(ImageChannelDataType::UnormInt8)
+                .image_type(MemObjectType::Image2d)
+                .dims((64, 64))
+                .queue(queue.clone())
+                .build().unwrap();
+
+            let (tx, rx) = mpsc::channel();
+            image.on_release(move || tx.send(()).unwrap()).unwrap();
+
+            drop(image);
+            queue.finish().unwrap();
+            assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
+        }
+    }
+}
@@ -21,6 +21,32 @@ pub mod vector_types;
 pub mod context_props;
 pub mod async;
 pub mod buffer_sink_stream_cycles;
+pub mod sub_devices;
+pub mod program_link;
+pub mod program_kernels;
+pub mod native_kernel;
+pub mod mem_release;
+pub mod built_in_kernels;
+#[cfg(feature = "opencl_version_2_0")]
+pub mod svm;
+#[cfg(feature = "opencl_version_2_0")]
+pub mod pipes;
+pub mod queue_properties;
+pub mod kernel_duplicate;
+pub mod kernel_set_arg;
+#[cfg(feature = "opencl_version_2_1")]
+pub mod sub_groups;
+pub mod device_clock;
+pub mod program_cache;
+pub mod error_kinds;
+pub mod derive_prm_types;
+#[cfg(not(feature = "async_block"))]
+pub mod event_reactor;
+pub mod event_callbacks;
+pub mod profiler;
+pub mod trace;
+#[cfg(feature = "std_future")]
+pub mod std_future;
 
 use self::rand::Rng;
 use core::error::{Result as OclCoreResult};
@@ -0,0 +1,73 @@
+//! Tests native (host function) kernels.
+
+use std::slice;
+use std::sync::mpsc;
+use core::{DeviceInfo, DeviceInfoResult, DeviceExecCapabilities};
+use standard::{Platform, Device, ProQue, Event};
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+"#;
+
+const WORK_SIZE: usize = 1 << 10;
+
+/// Returns true if `device` can execute native kernels.
+fn supports_native_kernels(device: &Device) -> bool {
+    match device.info(DeviceInfo::ExecutionCapabilities) {
+        Ok(DeviceInfoResult::ExecutionCapabilities(caps)) => {
+            caps.contains(DeviceExecCapabilities::NATIVE_KERNEL)
+        },
+        _ => false,
+    }
+}
+
+#[test]
+fn native_kernel_between_kernels() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            let pro_que = ProQue::builder()
+                .platform(platform)
+                .device(device)
+                .src(SRC)
+                .dims(WORK_SIZE)
+                .build().unwrap();
+
+            if !supports_native_kernels(&device) {
+                assert!(pro_que.queue().enqueue_native(|_| ()).enq().is_err());
+                continue;
+            }
+
+            let buffer = pro_que.create_buffer::<f32>().unwrap();
+            let kernel = pro_que.create_kernel("add").unwrap()
+                .arg_buf(&buffer)
+                .arg_scl(1.0f32);
+
+            let mut kernel_a_event = Event::empty();
+            let mut native_event = Event::empty();
+            let (tx, rx) = mpsc::channel();
+
+            unsafe { kernel.cmd().enew(&mut kernel_a_event).enq().unwrap(); }
+
+            // Doubles each element, reporting the values it found:
+            pro_que.queue().enqueue_native(move |ptrs| {
+                    let vals = unsafe { slice::from_raw_parts_mut(ptrs[0] as *mut f32, WORK_SIZE) };
+                    tx.send(vals.to_vec()).unwrap();
+                    for val in vals.iter_mut() { *val *= 2.0; }
+                })
+                .buf(&buffer)
+                .ewait(&kernel_a_event)
+                .enew(&mut native_event)
+                .enq().unwrap();
+
+            unsafe { kernel.cmd().ewait(&native_event).enq().unwrap(); }
+
+            let mut vec = vec![0.0f32; WORK_SIZE];
+            buffer.read(&mut vec).enq().unwrap();
+
+            assert!(rx.recv().unwrap().iter().all(|&v| v == 1.0f32));
+            assert!(vec.iter().all(|&v| v == 3.0f32));
+        }
+    }
+}
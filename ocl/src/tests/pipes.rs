@@ -0,0 +1,85 @@
+//! Tests pipes used to pass data between kernels.
+
+use core::{OpenclVersion, PipeInfo, PipeInfoResult, MemInfo, MemInfoResult, MemObjectType};
+use standard::{Platform, Device, ProQue, Program, Buffer, Pipe};
+use ::MemFlags;
+
+static SRC: &'static str = r#"
+    __kernel void produce(__global const int* src, __write_only pipe int out) {
+        int val = src[get_global_id(0)];
+        write_pipe(out, &val);
+    }
+
+    __kernel void consume(__read_only pipe int in, __global int* sum) {
+        int val;
+        if (read_pipe(in, &val) == 0) {
+            atomic_add(sum, val);
+        }
+    }
+"#;
+
+const WORK_SIZE: usize = 1 << 10;
+
+#[test]
+fn pipe_producer_consumer() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if device.version().unwrap() < OpenclVersion::new(2, 0) { continue; }
+
+            let pro_que = ProQue::builder()
+                .platform(platform)
+                .device(device)
+                .prog_bldr(Program::builder()
+                    .src(SRC)
+                    .cmplr_opt("-cl-std=CL2.0 -cl-kernel-arg-info"))
+                .dims(WORK_SIZE)
+                .build().unwrap();
+
+            let pipe = Pipe::<i32>::new(pro_que.context(), WORK_SIZE as u32).unwrap();
+
+            match pipe.info(PipeInfo::PacketSize).unwrap() {
+                PipeInfoResult::PacketSize(size) => assert_eq!(size, 4),
+                _ => unreachable!(),
+            }
+            match pipe.mem_info(MemInfo::Type).unwrap() {
+                MemInfoResult::Type(ty) => assert_eq!(ty, MemObjectType::Pipe),
+                _ => unreachable!(),
+            }
+
+            let src: Vec<i32> = (0..WORK_SIZE as i32).collect();
+            let src_buf = Buffer::<i32>::builder()
+                .queue(pro_que.queue().clone())
+                .flags(MemFlags::new().read_only().copy_host_ptr())
+                .len(WORK_SIZE)
+                .host_data(&src)
+                .build().unwrap();
+            let sum_buf = Buffer::<i32>::builder()
+                .queue(pro_que.queue().clone())
+                .len(1)
+                .fill_val(0)
+                .build().unwrap();
+
+            let produce = pro_que.create_kernel("produce").unwrap()
+                .arg_buf(&src_buf)
+                .arg_pipe(&pipe);
+            let consume = pro_que.create_kernel("consume").unwrap()
+                .arg_pipe(&pipe)
+                .arg_buf(&sum_buf);
+
+            // Pipe arguments are distinguished from buffers and scalars:
+            assert!(produce.verify_arg_pipe_type::<i32>(1).is_ok());
+            assert!(produce.verify_arg_pipe_type::<f32>(1).is_err());
+            assert!(produce.verify_arg_type::<i32>(1).is_err());
+            assert!(produce.verify_arg_pipe_type::<i32>(0).is_err());
+
+            unsafe {
+                produce.enq().unwrap();
+                consume.enq().unwrap();
+            }
+
+            let mut sum = vec![0i32; 1];
+            sum_buf.read(&mut sum).enq().unwrap();
+            assert_eq!(sum[0], src.iter().sum::<i32>());
+        }
+    }
+}
@@ -0,0 +1,89 @@
+//! Tests `Profiler`.
+
+use enums::{ImageChannelOrder, ImageChannelDataType, MemObjectType};
+use standard::{Context, Queue, Program, ProQue, Profiler, ProfileKey, Event, Image};
+
+const WORK_SIZE: usize = 1 << 12;
+const ITERS: usize = 8;
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+"#;
+
+#[test]
+fn profiler() {
+    let context = Context::builder().build().unwrap();
+    let device = context.devices()[0];
+    let profiler = Profiler::new();
+    let queue = Queue::builder()
+        .device(device)
+        .profiler(profiler.clone())
+        .build(&context).unwrap();
+    let program = Program::builder()
+        .devices(device)
+        .src(SRC)
+        .build(&context).unwrap();
+    let pro_que = ProQue::new(context, queue, program, Some(WORK_SIZE));
+
+    let buffer = pro_que.create_buffer::<f32>().unwrap();
+    let kernel = pro_que.create_kernel("add").unwrap()
+        .arg_buf(&buffer)
+        .arg_scl(1.0f32);
+
+    let mut vec = vec![0.0f32; WORK_SIZE];
+
+    // Commands enqueued using the queue are recorded, including the event
+    // requested by the caller (which must still be set):
+    for _ in 0..ITERS {
+        let mut event = Event::empty();
+        unsafe { kernel.cmd().enew(&mut event).enq().unwrap(); }
+        assert!(!event.is_empty());
+    }
+    buffer.read(&mut vec).enq().unwrap();
+    buffer.write(&vec).enq().unwrap();
+
+    let image = Image::<f32>::builder()
+        .channel_order(ImageChannelOrder::R)
+        .channel_data_type(ImageChannelDataType::Float)
+        .image_type(MemObjectType::Image1d)
+        .dims(WORK_SIZE)
+        .queue(pro_que.queue().clone())
+        .build().unwrap();
+    image.write(&vec).enq().unwrap();
+    image.read(&mut vec).enq().unwrap();
+
+    // As are commands given a profiler explicitly:
+    let other_profiler = Profiler::new();
+    unsafe { kernel.cmd().profiler(&other_profiler).enq().unwrap(); }
+
+    pro_que.queue().finish().unwrap();
+    profiler.wait_for_pending();
+    other_profiler.wait_for_pending();
+    assert_eq!(profiler.pending_count(), 0);
+
+    let report = profiler.report();
+    assert_eq!(report.unresolved(), 0);
+    assert_eq!(report.commands().len(), 5);
+
+    let kernel_stats = report.get(&ProfileKey::Kernel("add".to_owned())).unwrap();
+    assert_eq!(kernel_stats.start_to_end.count, ITERS);
+    assert!(kernel_stats.start_to_end.max >= kernel_stats.start_to_end.p50);
+    assert_eq!(report.get(&ProfileKey::Buffer("read")).unwrap().start_to_end.count, 1);
+    assert_eq!(report.get(&ProfileKey::Buffer("write")).unwrap().start_to_end.count, 1);
+    assert_eq!(report.get(&ProfileKey::Image("write")).unwrap().start_to_end.count, 1);
+    assert_eq!(report.get(&ProfileKey::Image("read")).unwrap().start_to_end.count, 1);
+
+    let other_report = other_profiler.report();
+    assert_eq!(other_report.get(&ProfileKey::Kernel("add".to_owned())).unwrap()
+        .start_to_end.count, 1);
+
+    // One header line plus one line per command:
+    let mut csv = Vec::new();
+    report.write_csv(&mut csv).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap().lines().count(), 6);
+
+    profiler.reset();
+    assert!(profiler.report().commands().is_empty());
+}
@@ -0,0 +1,104 @@
+//! Tests program binaries and `ProgramCache`.
+
+use std::env;
+use std::fs::{self, File};
+use std::io::Write;
+use std::process;
+use standard::{ProQue, Program, ProgramCache, Kernel, Buffer};
+
+const WORK_SIZE: usize = 1 << 10;
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+"#;
+
+/// Enqueues `add` from `program` and checks the result.
+fn check_program(pro_que: &ProQue, program: &Program) {
+    let buffer: Buffer<f32> = pro_que.create_buffer().unwrap();
+
+    let kernel = Kernel::new("add", program).unwrap()
+        .queue(pro_que.queue().clone())
+        .gws(WORK_SIZE)
+        .arg_buf(&buffer)
+        .arg_scl(5.0f32);
+
+    unsafe { kernel.enq().unwrap(); }
+
+    let mut vec = vec![0.0f32; WORK_SIZE];
+    buffer.read(&mut vec).enq().unwrap();
+    assert!(vec.iter().all(|&v| v == 5.0f32));
+}
+
+#[test]
+fn program_binaries() {
+    let pro_que = ProQue::builder()
+        .src(SRC)
+        .dims(WORK_SIZE)
+        .build().unwrap();
+
+    let binaries = pro_que.program().binaries().unwrap();
+    assert_eq!(binaries.len(), 1);
+    assert_eq!(binaries[0].0, pro_que.device());
+    assert!(!binaries[0].1.is_empty());
+
+    let program = Program::builder()
+        .devices(pro_que.device())
+        .binaries(binaries.into_iter().map(|(_, b)| b).collect())
+        .build(pro_que.context()).unwrap();
+
+    check_program(&pro_que, &program);
+
+    // Binaries and source can not be mixed:
+    assert!(Program::builder()
+        .src(SRC)
+        .devices(pro_que.device())
+        .binaries(vec![vec![0u8; 16]])
+        .build(pro_que.context()).is_err());
+}
+
+#[test]
+fn program_cache() {
+    let dir = env::temp_dir().join(format!("ocl_program_cache_test_{}", process::id()));
+    let cache = ProgramCache::new(&dir).unwrap();
+
+    let pro_que = ProQue::builder()
+        .src(SRC)
+        .dims(WORK_SIZE)
+        .build().unwrap();
+
+    let build = || {
+        Program::builder()
+            .src(SRC)
+            .devices(pro_que.device())
+            .cache(cache.clone())
+            .build(pro_que.context()).unwrap()
+    };
+
+    let src_strings = Program::builder().src(SRC).get_src_strings().unwrap();
+    let cmplr_opts = Program::builder().get_compiler_options().unwrap();
+    let key = cache.key(&src_strings, &cmplr_opts, &pro_que.device()).unwrap();
+
+    // The first build stores a binary:
+    assert!(cache.load(&key).unwrap().is_none());
+    check_program(&pro_que, &build());
+    let binary = cache.load(&key).unwrap().unwrap();
+
+    // ...which is used by the second:
+    check_program(&pro_que, &build());
+    assert_eq!(cache.load(&key).unwrap().unwrap(), binary);
+
+    // Different options use a different key:
+    let other_opts = Program::builder().cmplr_opt("-cl-fast-relaxed-math")
+        .get_compiler_options().unwrap();
+    assert!(cache.key(&src_strings, &other_opts, &pro_que.device()).unwrap() != key);
+
+    // A rejected binary is rebuilt and overwritten:
+    File::create(dir.join(format!("{}.clbin", key))).unwrap()
+        .write_all(b"not a binary").unwrap();
+    check_program(&pro_que, &build());
+    assert_eq!(cache.load(&key).unwrap().unwrap(), binary);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,63 @@
+//! Tests creating every kernel in a program at once.
+
+use core::OpenclVersion;
+use standard::{Platform, Device, ProQue};
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+
+    __kernel void mul(__global float* buffer, float coeff) {
+        buffer[get_global_id(0)] *= coeff;
+    }
+
+    float helper(float val) {
+        return val;
+    }
+"#;
+
+#[test]
+fn create_all_kernels() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if device.version().unwrap() < OpenclVersion::new(1, 2) { continue; }
+
+            let pro_que = ProQue::builder()
+                .platform(platform)
+                .device(device)
+                .src(SRC)
+                .dims(1 << 10)
+                .build().unwrap();
+
+            let mut names = pro_que.program().kernel_names().unwrap();
+            names.sort();
+            assert_eq!(names, vec!["add".to_string(), "mul".to_string()]);
+
+            let mut kernels = pro_que.create_all_kernels().unwrap();
+            assert_eq!(kernels.len(), 2);
+
+            let buffer = pro_que.create_buffer::<f32>().unwrap();
+
+            let add = kernels.remove("add").unwrap()
+                .arg_buf(&buffer)
+                .arg_scl(5.0f32);
+            let mul = kernels.remove("mul").unwrap()
+                .arg_buf(&buffer)
+                .arg_scl(2.0f32);
+
+            // Argument types are cached for each kernel:
+            assert!(add.verify_arg_type::<f32>(1).is_ok());
+            assert!(mul.verify_arg_type::<i32>(1).is_err());
+
+            unsafe {
+                add.enq().unwrap();
+                mul.enq().unwrap();
+            }
+
+            let mut vec = vec![0.0f32; buffer.len()];
+            buffer.read(&mut vec).enq().unwrap();
+            assert!(vec.iter().all(|&v| v == 10.0f32));
+        }
+    }
+}
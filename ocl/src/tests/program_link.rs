@@ -0,0 +1,113 @@
+//! Tests separate compilation and linking of programs.
+
+use core::{DeviceInfo, DeviceInfoResult, OpenclVersion};
+use standard::{Platform, Device, Context, Queue, Program, Buffer, Kernel};
+
+static HEADER_SRC: &'static str = r#"
+    float scale(float val);
+"#;
+
+static HELPER_SRC: &'static str = r#"
+    #include "scale.h"
+
+    float scale(float val) {
+        return val * SCALE;
+    }
+"#;
+
+static KERN_SRC: &'static str = r#"
+    #include "scale.h"
+
+    __kernel void scale_all(__global float* buffer) {
+        buffer[get_global_id(0)] = scale(buffer[get_global_id(0)]);
+    }
+"#;
+
+const WORK_SIZE: usize = 1 << 10;
+
+/// Returns true if `device` supports separate compilation and linking.
+fn supports_linking(device: &Device) -> bool {
+    if device.version().map(|v| v < OpenclVersion::new(1, 2)).unwrap_or(true) { return false; }
+
+    match device.info(DeviceInfo::LinkerAvailable) {
+        Ok(DeviceInfoResult::LinkerAvailable(available)) => available,
+        _ => false,
+    }
+}
+
+#[test]
+fn compile_and_link() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if !supports_linking(&device) { continue; }
+
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+
+            let helpers = Program::builder()
+                .devices(device)
+                .header("scale.h", HEADER_SRC)
+                .cmplr_def("SCALE", 3)
+                .src(HELPER_SRC)
+                .compile_library(&context).unwrap();
+
+            let main = Program::builder()
+                .devices(device)
+                .header("scale.h", HEADER_SRC)
+                .src(KERN_SRC)
+                .compile(&context).unwrap();
+
+            for (log_device, _) in main.build_logs().unwrap() {
+                assert_eq!(log_device, device);
+            }
+
+            let program = Program::link(&context, &[&main, &helpers], Some(&[device]),
+                Default::default()).unwrap();
+
+            let queue = Queue::new(&context, device, None).unwrap();
+
+            let buffer = Buffer::<f32>::builder()
+                .queue(queue.clone())
+                .len(WORK_SIZE)
+                .fill_val(2.0f32)
+                .build().unwrap();
+
+            let kernel = Kernel::new("scale_all", &program).unwrap()
+                .queue(queue)
+                .gws(WORK_SIZE)
+                .arg_buf(&buffer);
+
+            unsafe { kernel.enq().unwrap(); }
+
+            let mut vec = vec![0.0f32; WORK_SIZE];
+            buffer.read(&mut vec).enq().unwrap();
+            assert!(vec.iter().all(|&v| v == 6.0f32));
+        }
+    }
+}
+
+#[test]
+fn link_unresolved_symbol() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if !supports_linking(&device) { continue; }
+
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+
+            let main = Program::builder()
+                .devices(device)
+                .header("scale.h", HEADER_SRC)
+                .src(KERN_SRC)
+                .compile(&context).unwrap();
+
+            // `scale` is never defined:
+            assert!(Program::link(&context, &[&main], Some(&[device]),
+                Default::default()).is_err());
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Tests command queues created with properties.
+
+use core::{OpenclVersion, CommandQueueInfo, CommandQueueInfoResult, CommandQueueProperties,
+    QueuePriority};
+use standard::{Platform, Device, Context, Queue};
+
+/// Returns the properties bitfield of `queue`.
+fn queue_properties(queue: &Queue) -> CommandQueueProperties {
+    match queue.info(CommandQueueInfo::Properties).unwrap() {
+        CommandQueueInfoResult::Properties(props) => props,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn queue_builder() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            let context = Context::builder()
+                .platform(platform)
+                .devices(device)
+                .build().unwrap();
+
+            let queue = Queue::builder()
+                .device(device)
+                .profiling()
+                .build(&context).unwrap();
+
+            assert!(queue_properties(&queue).contains(CommandQueueProperties::PROFILING_ENABLE));
+            queue.finish().unwrap();
+
+            // The device defaults to the first in the context:
+            let queue = Queue::builder().build(&context).unwrap();
+            assert_eq!(queue.device(), device);
+
+            let device_version = device.version().unwrap();
+            let props_supported = cfg!(feature = "opencl_version_2_0") &&
+                device_version >= OpenclVersion::new(2, 0);
+
+            if props_supported {
+                let queue = Queue::builder()
+                    .device(device)
+                    .on_device()
+                    .build(&context).unwrap();
+
+                let props = queue_properties(&queue);
+                assert!(props.contains(CommandQueueProperties::ON_DEVICE |
+                    CommandQueueProperties::OUT_OF_ORDER_EXEC_MODE_ENABLE));
+
+                match queue.info(CommandQueueInfo::Size).unwrap() {
+                    CommandQueueInfoResult::Size(size) => assert!(size > 0),
+                    _ => unreachable!(),
+                }
+            } else {
+                // Properties beyond those of OpenCL 1.x are rejected:
+                assert!(Queue::builder()
+                    .device(device)
+                    .priority(QueuePriority::High)
+                    .build(&context).is_err());
+            }
+        }
+    }
+}
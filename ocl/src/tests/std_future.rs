@@ -0,0 +1,131 @@
+//! Tests the `std::future::Future` implementations.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::Duration;
+use core::ClEventPtrRef;
+use core::error::{ErrorKind as OclCoreErrorKind, ApiWrapperError};
+use error::ErrorKind;
+use ffi;
+use standard::{ProQue, Context, Event, EventList};
+use ::RwVec;
+use async::BufferMapSink;
+
+const WORK_SIZE: usize = 1 << 10;
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+"#;
+
+/// Unparks a thread when woken.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Polls `future` to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+#[test]
+fn std_future_events() {
+    let pro_que = ProQue::builder()
+        .src(SRC)
+        .dims(WORK_SIZE)
+        .build().unwrap();
+
+    let buffer = pro_que.create_buffer::<f32>().unwrap();
+    let kernel = pro_que.create_kernel("add").unwrap()
+        .arg_buf(&buffer)
+        .arg_scl(1.0f32);
+
+    let mut event = Event::empty();
+    unsafe { kernel.cmd().enew(&mut event).enq().unwrap(); }
+    block_on(event).unwrap();
+
+    let mut events = EventList::new();
+    for _ in 0..4 {
+        unsafe { kernel.cmd().enew(&mut events).enq().unwrap(); }
+    }
+    block_on(events).unwrap();
+
+    let mut vec = vec![0.0f32; WORK_SIZE];
+    buffer.read(&mut vec).enq().unwrap();
+    assert!(vec.iter().all(|&v| v == 5.0f32));
+}
+
+#[test]
+fn std_future_event_terminated() {
+    let context = Context::builder().build().unwrap();
+
+    // Terminated before and after the first poll:
+    for &delay_ms in &[0, 20] {
+        let event = Event::user(&context).unwrap();
+        let event_ptr = unsafe { *event.as_ptr_ref() as usize };
+        let setter = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(delay_ms));
+            unsafe { ffi::clSetUserEventStatus(event_ptr as ffi::cl_event, -1); }
+        });
+        if delay_ms == 0 { thread::sleep(Duration::from_millis(20)); }
+
+        let events = EventList::from(vec![event.clone()]);
+        match *block_on(event).unwrap_err().kind() {
+            ErrorKind::OclCore(ref err) => match *err.kind() {
+                OclCoreErrorKind::ApiWrapper(ApiWrapperError::EventTerminated(-1)) => (),
+                ref kind => panic!("Unexpected error kind: {:?}", kind),
+            },
+            ref kind => panic!("Unexpected error kind: {:?}", kind),
+        }
+        assert!(block_on(events).is_err());
+        setter.join().unwrap();
+    }
+}
+
+#[test]
+fn std_future_guards() {
+    let pro_que = ProQue::builder()
+        .src(SRC)
+        .dims(WORK_SIZE)
+        .build().unwrap();
+
+    let buffer = pro_que.create_buffer::<f32>().unwrap();
+    buffer.cmd().fill(3.0f32, None).enq().unwrap();
+
+    // `FutureWriteGuard`:
+    let rw_vec = RwVec::from(vec![0.0f32; WORK_SIZE]);
+    let guard = block_on(buffer.read(&rw_vec).enq_async().unwrap()).unwrap();
+    assert!(guard.iter().all(|&v| v == 3.0f32));
+    drop(guard);
+
+    // `FutureMemMap`:
+    let mut mem_map = block_on(unsafe { buffer.map().read().enq_async().unwrap() }).unwrap();
+    assert!(mem_map.iter().all(|&v| v == 3.0f32));
+    mem_map.unmap().enq().unwrap();
+
+    // `FutureSinkMapGuard`:
+    let sink = BufferMapSink::<f32>::new(pro_que.queue().clone(), WORK_SIZE).unwrap();
+    let mut sink_guard = block_on(sink.write().enq().unwrap()).unwrap();
+    for v in sink_guard.iter_mut() { *v = 4.0f32; }
+    drop(sink_guard);
+
+    let mut vec = vec![0.0f32; WORK_SIZE];
+    sink.buffer().read(&mut vec).enq().unwrap();
+    assert!(vec.iter().all(|&v| v == 4.0f32));
+}
@@ -0,0 +1,80 @@
+//! Tests device partitioning (fission).
+
+use core::{DeviceInfo, DeviceInfoResult, DevicePartitionProperty, OpenclVersion};
+use standard::{Platform, Device, Context, ProQue};
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+"#;
+
+/// Returns true if `device` can be partitioned equally into at least two
+/// sub-devices.
+fn supports_equal_partition(device: &Device) -> bool {
+    if device.version().map(|v| v < OpenclVersion::new(1, 2)).unwrap_or(true) { return false; }
+
+    let max_sub_devices = match device.info(DeviceInfo::PartitionMaxSubDevices) {
+        Ok(DeviceInfoResult::PartitionMaxSubDevices(max)) => max,
+        _ => return false,
+    };
+
+    match device.info(DeviceInfo::PartitionProperties) {
+        Ok(DeviceInfoResult::PartitionProperties(props)) => {
+            max_sub_devices > 1 && props.contains(&DevicePartitionProperty::Equally)
+        },
+        _ => false,
+    }
+}
+
+#[test]
+fn partition_equally() {
+    for platform in Platform::list() {
+        for device in Device::list_all(&platform).unwrap() {
+            if !supports_equal_partition(&device) { continue; }
+
+            let sub_devices = device.partition_equally(1).unwrap();
+            assert!(sub_devices.len() > 1);
+
+            for sub_device in sub_devices.iter() {
+                match sub_device.info(DeviceInfo::ParentDevice).unwrap() {
+                    DeviceInfoResult::ParentDevice(Some(parent)) => {
+                        assert_eq!(parent, *device.as_core())
+                    },
+                    res => panic!("Unexpected parent device: {:?}", res),
+                }
+            }
+
+            // A context containing every sub-device:
+            let context = Context::builder()
+                .platform(platform)
+                .devices(&sub_devices)
+                .build().unwrap();
+            assert_eq!(context.devices().len(), sub_devices.len());
+
+            // Run a kernel on a single sub-device:
+            let pro_que = ProQue::builder()
+                .platform(platform)
+                .device(&sub_devices[0])
+                .src(SRC)
+                .dims(1 << 10)
+                .build().unwrap();
+
+            let buffer = pro_que.create_buffer::<f32>().unwrap();
+            let kernel = pro_que.create_kernel("add").unwrap()
+                .arg_buf(&buffer)
+                .arg_scl(10.0f32);
+
+            unsafe { kernel.enq().unwrap(); }
+
+            let mut vec = vec![0.0f32; buffer.len()];
+            buffer.read(&mut vec).enq().unwrap();
+            assert!(vec.iter().all(|&v| v == 10.0f32));
+
+            // Clones hold their own reference:
+            let sub_device = sub_devices[1].clone();
+            drop(sub_devices);
+            assert!(sub_device.name().is_ok());
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Tests kernel sub-group queries.
+
+use core::{OpenclVersion, KernelSubGroupInfo, KernelSubGroupInfoResult};
+use standard::{Platform, Device, ProQue, SpatialDims};
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+"#;
+
+const WORK_SIZE: usize = 1 << 12;
+
+#[test]
+fn sub_group_info() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if device.version().unwrap() < OpenclVersion::new(2, 1) { continue; }
+
+            let max_num_sub_groups = device.max_num_sub_groups().unwrap();
+            if max_num_sub_groups == 0 { continue; }
+
+            let pro_que = ProQue::builder()
+                .platform(platform)
+                .device(device)
+                .src(SRC)
+                .dims(WORK_SIZE)
+                .build().unwrap();
+
+            let kernel = pro_que.create_kernel("add").unwrap()
+                .arg_buf_named::<f32, ::Buffer<f32>>("buffer", None)
+                .arg_scl(1.0f32);
+
+            match kernel.sub_group_info(device, KernelSubGroupInfo::MaxNumSubGroups, &[])
+                    .unwrap() {
+                KernelSubGroupInfoResult::MaxNumSubGroups(n) => {
+                    assert!(n <= max_num_sub_groups as usize);
+                },
+                _ => unreachable!(),
+            }
+
+            let lws = SpatialDims::One(device.max_wg_size().unwrap().min(64));
+            let sub_group_size = kernel.max_sub_group_size_for_lws(device, lws).unwrap();
+            let sub_group_count = kernel.sub_group_count_for_lws(device, lws).unwrap();
+            assert!(sub_group_size > 0);
+            assert!(sub_group_size * sub_group_count >= lws.to_len());
+
+            // A local work size yielding exactly one sub-group:
+            if let Ok(lws) = kernel.lws_for_sub_group_count(device, 1) {
+                assert_eq!(lws.dim_count(), 1);
+                assert_eq!(kernel.sub_group_count_for_lws(device, lws).unwrap(), 1);
+            }
+        }
+    }
+}
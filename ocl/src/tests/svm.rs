@@ -0,0 +1,212 @@
+//! Tests shared virtual memory.
+
+use std::sync::Arc;
+use core::{DeviceInfo, DeviceInfoResult, DeviceSvmCapabilities, OpenclVersion, KernelExecInfo};
+use ffi::c_void;
+use error::ErrorKind;
+use standard::{Platform, Device, ProQue, Kernel, SvmVec, SvmError};
+use ::MapFlags;
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* svm, float addend) {
+        svm[get_global_id(0)] += addend;
+    }
+
+    __kernel void gather(__global ulong* ptrs, __global float* out) {
+        __global float* ptr = (__global float*)ptrs[get_global_id(0)];
+        out[get_global_id(0)] = *ptr;
+    }
+"#;
+
+const WORK_SIZE: usize = 1 << 10;
+
+/// Returns true if `device` supports coarse-grained SVM buffers.
+fn supports_svm(device: &Device) -> bool {
+    if device.version().map(|v| v < OpenclVersion::new(2, 0)).unwrap_or(true) { return false; }
+
+    match device.info(DeviceInfo::SvmCapabilities) {
+        Ok(DeviceInfoResult::SvmCapabilities(caps)) => {
+            caps.contains(DeviceSvmCapabilities::COARSE_GRAIN_BUFFER)
+        },
+        _ => false,
+    }
+}
+
+#[test]
+fn svm_kernel_arg() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if !supports_svm(&device) { continue; }
+
+            let pro_que = ProQue::builder()
+                .platform(platform)
+                .device(device)
+                .src(SRC)
+                .dims(WORK_SIZE)
+                .build().unwrap();
+
+            let mut svm = SvmVec::<f32>::new(pro_que.queue().clone(), WORK_SIZE).unwrap();
+            assert!(!svm.is_host_accessible());
+
+            match *SvmVec::<f32>::new(pro_que.queue().clone(), usize::max_value())
+                    .unwrap_err().kind() {
+                ErrorKind::Svm(SvmError::LenTooLarge { len }) => {
+                    assert_eq!(len, usize::max_value());
+                },
+                ref kind => panic!("Unexpected error: {:?}", kind),
+            }
+
+            svm.map(MapFlags::new().read().write()).unwrap();
+            assert!(svm.iter().all(|&v| v == 0.0f32));
+            for (i, val) in svm.iter_mut().enumerate() { *val = i as f32; }
+            svm.unmap().unwrap();
+
+            let kernel = pro_que.create_kernel("add").unwrap()
+                .arg_svm(&svm)
+                .arg_scl(10.0f32);
+
+            unsafe { kernel.enq().unwrap(); }
+
+            svm.map(MapFlags::new().read()).unwrap();
+            for (i, &val) in svm.iter().enumerate() {
+                assert_eq!(val, i as f32 + 10.0f32);
+            }
+            svm.unmap().unwrap();
+
+            let mut vec = vec![0.0f32; WORK_SIZE];
+            svm.copy_to_slice(&mut vec).unwrap();
+            assert_eq!(vec[WORK_SIZE - 1], (WORK_SIZE - 1) as f32 + 10.0f32);
+        }
+    }
+}
+
+#[test]
+fn svm_kernel_duplicate_rebind() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if !supports_svm(&device) { continue; }
+
+            let pro_que = ProQue::builder()
+                .platform(platform)
+                .device(device)
+                .src(SRC)
+                .dims(WORK_SIZE)
+                .build().unwrap();
+
+            let mut svm = SvmVec::<f32>::new(pro_que.queue().clone(), WORK_SIZE).unwrap();
+            let buffer = pro_que.create_buffer::<f32>().unwrap();
+
+            let mut kernel = pro_que.create_kernel("add").unwrap()
+                .arg_svm(&svm)
+                .arg_scl(10.0f32);
+            assert_eq!(Arc::strong_count(svm.alloc()), 2);
+
+            // Replacing the SVM argument releases the allocation and the
+            // duplicate refers to the buffer instead:
+            kernel.set_arg_idx(0, &buffer).unwrap();
+            assert_eq!(Arc::strong_count(svm.alloc()), 1);
+
+            let duplicate = kernel.duplicate().unwrap();
+            unsafe { duplicate.enq().unwrap(); }
+
+            let mut vec = vec![0.0f32; WORK_SIZE];
+            buffer.read(&mut vec).enq().unwrap();
+            assert!(vec.iter().all(|&v| v == 10.0f32));
+
+            svm.map(MapFlags::new().read()).unwrap();
+            assert!(svm.iter().all(|&v| v == 0.0f32));
+            svm.unmap().unwrap();
+        }
+    }
+}
+
+#[test]
+fn svm_indirect_pointers() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if !supports_svm(&device) { continue; }
+
+            let pro_que = ProQue::builder()
+                .platform(platform)
+                .device(device)
+                .src(SRC)
+                .dims(WORK_SIZE)
+                .build().unwrap();
+
+            let queue = pro_que.queue().clone();
+            let mut data = SvmVec::<f32>::new(queue.clone(), WORK_SIZE).unwrap();
+            let mut ptrs = SvmVec::<u64>::new(queue.clone(), WORK_SIZE).unwrap();
+            let out = SvmVec::<f32>::new(queue.clone(), WORK_SIZE).unwrap();
+
+            let src: Vec<f32> = (0..WORK_SIZE).map(|i| i as f32).collect();
+            data.copy_from_slice(&src).unwrap();
+
+            // Point each element in reverse order:
+            ptrs.map(MapFlags::new().write()).unwrap();
+            for (i, ptr) in ptrs.iter_mut().enumerate() {
+                *ptr = unsafe { data.as_ptr().offset((WORK_SIZE - 1 - i) as isize) } as u64;
+            }
+            ptrs.unmap().unwrap();
+
+            let kernel = Kernel::new("gather", pro_que.program()).unwrap()
+                .queue(queue.clone())
+                .gws(WORK_SIZE)
+                .arg_svm(&ptrs)
+                .arg_svm(&out);
+
+            kernel.set_exec_info(KernelExecInfo::SvmPtrs(&[data.as_ptr() as *const c_void]))
+                .unwrap();
+
+            // The allocation is kept alive by the kernel:
+            drop(ptrs);
+
+            unsafe { kernel.enq().unwrap(); }
+
+            let mut vec = vec![0.0f32; WORK_SIZE];
+            out.copy_to_slice(&mut vec).unwrap();
+            for (i, &val) in vec.iter().enumerate() {
+                assert_eq!(val, (WORK_SIZE - 1 - i) as f32);
+            }
+        }
+    }
+}
+
+#[test]
+fn svm_fine_grained() {
+    for platform in Platform::list() {
+        for device in Device::list_all(platform).unwrap() {
+            if !supports_svm(&device) { continue; }
+
+            match device.info(DeviceInfo::SvmCapabilities) {
+                Ok(DeviceInfoResult::SvmCapabilities(caps)) => {
+                    if !caps.contains(DeviceSvmCapabilities::FINE_GRAIN_BUFFER) { continue; }
+                },
+                _ => continue,
+            }
+
+            let pro_que = ProQue::builder()
+                .platform(platform)
+                .device(device)
+                .src(SRC)
+                .dims(WORK_SIZE)
+                .build().unwrap();
+
+            let flags = ::SvmMemFlags::new().read_write().fine_grain_buffer();
+            let mut svm = SvmVec::<f32>::with_flags(pro_que.queue().clone(), flags, WORK_SIZE)
+                .unwrap();
+
+            // Accessible without mapping:
+            assert!(svm.is_host_accessible());
+            for val in svm.iter_mut() { *val = 5.0f32; }
+
+            let kernel = pro_que.create_kernel("add").unwrap()
+                .arg_svm(&svm)
+                .arg_scl(1.0f32);
+
+            unsafe { kernel.enq().unwrap(); }
+            pro_que.queue().finish().unwrap();
+
+            assert!(svm.iter().all(|&v| v == 6.0f32));
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Tests `TraceRecorder`.
+
+use standard::{Context, Queue, Program, ProQue, TraceRecorder, Event};
+
+const WORK_SIZE: usize = 1 << 12;
+
+static SRC: &'static str = r#"
+    __kernel void add(__global float* buffer, float addend) {
+        buffer[get_global_id(0)] += addend;
+    }
+"#;
+
+#[test]
+fn trace_recorder() {
+    let context = Context::builder().build().unwrap();
+    let device = context.devices()[0];
+    let recorder = TraceRecorder::new();
+    let queue = Queue::builder()
+        .device(device)
+        .trace_recorder(recorder.clone())
+        .build(&context).unwrap();
+    let program = Program::builder()
+        .devices(device)
+        .src(SRC)
+        .build(&context).unwrap();
+    let pro_que = ProQue::new(context, queue, program, Some(WORK_SIZE));
+
+    let buffer = pro_que.create_buffer::<f32>().unwrap();
+    let kernel = pro_que.create_kernel("add").unwrap()
+        .arg_buf(&buffer)
+        .arg_scl(1.0f32);
+
+    let mut vec = vec![0.0f32; WORK_SIZE];
+
+    let mut kernel_event = Event::empty();
+    unsafe { kernel.cmd().label("add \"one\"").enew(&mut kernel_event).enq().unwrap(); }
+    assert!(!kernel_event.is_empty());
+    buffer.read(&mut vec).ewait(&kernel_event).enq().unwrap();
+    assert_eq!(recorder.len(), 2);
+
+    let mut json = Vec::new();
+    recorder.write_chrome_trace(&mut json).unwrap();
+    let json = String::from_utf8(json).unwrap();
+
+    assert!(json.starts_with("{\"traceEvents\":["));
+    // Labels are escaped, unlabeled commands are named by kind:
+    assert!(json.contains("\"name\":\"add \\\"one\\\"\",\"cat\":\"running\""));
+    assert!(json.contains("\"name\":\"buffer:read\",\"cat\":\"running\""));
+    assert!(json.contains("\"name\":\"Queue 0\""));
+    // One flow arrow for the `ewait` dependency:
+    assert_eq!(json.matches("\"ph\":\"s\"").count(), 1);
+    assert_eq!(json.matches("\"ph\":\"f\"").count(), 1);
+
+    recorder.clear();
+    assert!(recorder.is_empty());
+}